@@ -40,6 +40,13 @@ fn gamma_lut() {
         }
     }
     writeln!(w, "];").unwrap();
+    writeln!(w, "const DECODE_SRGB_F32: [f32; 256] = [").unwrap();
+    for i in 0..256 {
+        let s = i as f32 / 255.0;
+        let v = srgb_gamma_decode(s);
+        writeln!(w, "    {v:?},").unwrap();
+    }
+    writeln!(w, "];").unwrap();
 
     println!("cargo:rerun-if-changed=src/srgb_gamma.rs");
 }
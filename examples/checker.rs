@@ -5,15 +5,8 @@ use std::io;
 use std::io::Write;
 
 fn main() -> Result<(), io::Error> {
-    let v = SGray8::new(255);
     let mut r = Raster::with_clear(16, 16);
-    for y in 0..16 {
-        for x in 0..16 {
-            if x + y & 1 != 0 {
-                *r.pixel_mut(x, y) = v;
-            }
-        }
-    }
+    r.fill_with((), |x, y| SGray8::new(((x + y) & 1) as u8 * 255));
     write_pgm(&r, "checker.pgm")
 }
 
@@ -0,0 +1,284 @@
+// access.rs    Raster access traits.
+//
+// Copyright (c) 2024  Douglas P Lau
+//
+use crate::borrowed::BorrowedRaster;
+use crate::chan::{Linear, Premultiplied};
+use crate::el::Pixel;
+use crate::matte::Matte;
+use crate::ops::Blend;
+use crate::raster::{clip_regions, Raster, Region, Rows, RowsMut};
+
+/// Read access to an image, owned or borrowed.
+///
+/// This allows functions to be written once for both [Raster] and
+/// [BorrowedRaster].  Only `width`, `height` and `pixels` are required; the
+/// other methods are provided.
+///
+/// [BorrowedRaster]: struct.BorrowedRaster.html
+/// [Raster]: struct.Raster.html
+pub trait RasterRef<P: Pixel> {
+    /// Get width in pixels.
+    fn width(&self) -> u32;
+
+    /// Get height in pixels.
+    fn height(&self) -> u32;
+
+    /// Get a slice of all pixels.
+    ///
+    /// The length must be `width` * `height`.
+    fn pixels(&self) -> &[P];
+
+    /// Get one pixel.
+    fn pixel(&self, x: i32, y: i32) -> P {
+        let width = self.width() as i32;
+        assert!(x >= 0 && x < width);
+        assert!(y >= 0 && y < self.height() as i32);
+        self.pixels()[(width * y + x) as usize]
+    }
+
+    /// Get `Region` of entire image.
+    fn region(&self) -> Region {
+        Region::new(0, 0, self.width(), self.height())
+    }
+
+    /// Get intersection with a `Region`.
+    fn intersection<R>(&self, reg: R) -> Region
+    where
+        R: Into<Region>,
+    {
+        reg.into().intersection(self.region())
+    }
+
+    /// Get an `Iterator` of rows within the image.
+    ///
+    /// * `reg` Region of the image to iterate.
+    fn rows<R>(&self, reg: R) -> Rows<'_, P>
+    where
+        R: Into<Region>,
+    {
+        let reg = self.intersection(reg);
+        Rows::new(self.pixels(), self.width() as i32, reg)
+    }
+}
+
+/// Write access to an image, owned or borrowed.
+///
+/// Only `pixels_mut` is required; the other methods are provided.
+///
+/// ### Example
+/// ```
+/// use pix::rgb::SRgb8;
+/// use pix::{Raster, RasterMut};
+///
+/// fn fill_red<R: RasterMut<SRgb8>>(r: &mut R) {
+///     RasterMut::copy_color(r, (), SRgb8::new(0xFF, 0, 0));
+/// }
+///
+/// let mut r = Raster::with_clear(4, 4);
+/// fill_red(&mut r);
+/// assert_eq!(r.pixel(2, 2), SRgb8::new(0xFF, 0, 0));
+/// ```
+pub trait RasterMut<P: Pixel>: RasterRef<P> {
+    /// Get a mutable slice of all pixels.
+    fn pixels_mut(&mut self) -> &mut [P];
+
+    /// Get a mutable pixel.
+    fn pixel_mut(&mut self, x: i32, y: i32) -> &mut P {
+        let width = self.width() as i32;
+        assert!(x >= 0 && x < width);
+        assert!(y >= 0 && y < self.height() as i32);
+        &mut self.pixels_mut()[(width * y + x) as usize]
+    }
+
+    /// Get an `Iterator` of mutable rows within the image.
+    ///
+    /// * `reg` Region of the image to iterate.
+    fn rows_mut<R>(&mut self, reg: R) -> RowsMut<'_, P>
+    where
+        R: Into<Region>,
+    {
+        let reg = self.intersection(reg);
+        let width = self.width() as i32;
+        RowsMut::new(self.pixels_mut(), width, reg)
+    }
+
+    /// Clear all pixels to default value.
+    fn clear(&mut self) {
+        self.pixels_mut().fill(P::default());
+    }
+
+    /// Copy a color to a region of the image.
+    ///
+    /// * `reg` Region within `self`.
+    /// * `clr` Source `Pixel` color.
+    fn copy_color<R>(&mut self, reg: R, clr: P)
+    where
+        R: Into<Region>,
+    {
+        for drow in RasterMut::rows_mut(self, reg) {
+            P::copy_color(drow, &clr);
+        }
+    }
+}
+
+/// Copy from a source image to a destination image.
+///
+/// Shared by [Raster] and [BorrowedRaster].
+pub(crate) fn copy_raster<P, D, S>(
+    dst: &mut D,
+    to: Region,
+    src: &S,
+    from: Region,
+) where
+    P: Pixel,
+    D: RasterMut<P>,
+    S: RasterRef<P>,
+{
+    let (to, from) = clip_regions(dst.region(), to, src.region(), from);
+    let srows = src.rows(from);
+    let drows = dst.rows_mut(to);
+    for (drow, srow) in drows.zip(srows) {
+        P::copy_slice(drow, srow);
+    }
+}
+
+/// Composite a source color to a region of an image.
+///
+/// Shared by [Raster] and [BorrowedRaster].
+pub(crate) fn composite_color<P, D, O>(dst: &mut D, reg: Region, clr: P, op: O)
+where
+    P: Pixel<Alpha = Premultiplied, Gamma = Linear>,
+    D: RasterMut<P>,
+    O: Blend,
+{
+    let reg = dst.intersection(reg);
+    if reg.width() > 0 && reg.height() > 0 {
+        for drow in dst.rows_mut(reg) {
+            P::composite_color(drow, &clr, op);
+        }
+    }
+}
+
+/// Composite from a matte image and color to a destination image.
+///
+/// Shared by [Raster] and [BorrowedRaster].
+pub(crate) fn composite_matte<P, D, M, S, O>(
+    dst: &mut D,
+    to: Region,
+    src: &S,
+    from: Region,
+    clr: P,
+    op: O,
+) where
+    P: Pixel<Alpha = Premultiplied, Gamma = Linear>,
+    D: RasterMut<P>,
+    M: Pixel<Chan = P::Chan, Model = Matte, Gamma = P::Gamma>,
+    S: RasterRef<M>,
+    O: Blend,
+{
+    let (to, from) = clip_regions(dst.region(), to, src.region(), from);
+    let srows = src.rows(from);
+    let drows = dst.rows_mut(to);
+    for (drow, srow) in drows.zip(srows) {
+        P::composite_matte(drow, srow, &clr, op);
+    }
+}
+
+/// Composite from a source image to a destination image.
+///
+/// Shared by [Raster] and [BorrowedRaster].
+pub(crate) fn composite_raster<P, D, S, O>(
+    dst: &mut D,
+    to: Region,
+    src: &S,
+    from: Region,
+    op: O,
+) where
+    P: Pixel<Alpha = Premultiplied, Gamma = Linear>,
+    D: RasterMut<P>,
+    S: RasterRef<P>,
+    O: Blend,
+{
+    let (to, from) = clip_regions(dst.region(), to, src.region(), from);
+    let srows = src.rows(from);
+    let drows = dst.rows_mut(to);
+    for (drow, srow) in drows.zip(srows) {
+        P::composite_slice(drow, srow, op);
+    }
+}
+
+impl<P: Pixel> RasterRef<P> for Raster<P> {
+    fn width(&self) -> u32 {
+        Raster::width(self)
+    }
+
+    fn height(&self) -> u32 {
+        Raster::height(self)
+    }
+
+    fn pixels(&self) -> &[P] {
+        Raster::pixels(self)
+    }
+}
+
+impl<P: Pixel> RasterMut<P> for Raster<P> {
+    fn pixels_mut(&mut self) -> &mut [P] {
+        Raster::pixels_mut(self)
+    }
+}
+
+impl<P: Pixel> RasterRef<P> for BorrowedRaster<'_, P> {
+    fn width(&self) -> u32 {
+        BorrowedRaster::width(self)
+    }
+
+    fn height(&self) -> u32 {
+        BorrowedRaster::height(self)
+    }
+
+    fn pixels(&self) -> &[P] {
+        BorrowedRaster::pixels(self)
+    }
+}
+
+impl<P: Pixel> RasterMut<P> for BorrowedRaster<'_, P> {
+    fn pixels_mut(&mut self) -> &mut [P] {
+        BorrowedRaster::pixels_mut(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rgb::*;
+    use crate::*;
+
+    fn fill_red<R: RasterMut<SRgb8>>(r: &mut R) {
+        RasterMut::copy_color(r, (), SRgb8::new(0xFF, 0, 0));
+    }
+
+    fn count_red<R: RasterRef<SRgb8>>(r: &R) -> usize {
+        RasterRef::rows(r, ())
+            .flatten()
+            .filter(|p| **p == SRgb8::new(0xFF, 0, 0))
+            .count()
+    }
+
+    #[test]
+    fn owned_and_borrowed() {
+        let mut r = Raster::with_clear(3, 2);
+        fill_red(&mut r);
+        assert_eq!(count_red(&r), 6);
+        let mut buf = [SRgb8::default(); 8];
+        {
+            let mut b = Raster::from_slice_mut(4, 2, &mut buf);
+            fill_red(&mut b);
+            assert_eq!(count_red(&b), 8);
+            RasterMut::clear(&mut b);
+            *RasterMut::pixel_mut(&mut b, 1, 1) = SRgb8::new(0xFF, 0, 0);
+            assert_eq!(RasterRef::pixel(&b, 1, 1), SRgb8::new(0xFF, 0, 0));
+        }
+        assert_eq!(buf[5], SRgb8::new(0xFF, 0, 0));
+        assert_eq!(buf[4], SRgb8::default());
+    }
+}
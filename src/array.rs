@@ -0,0 +1,73 @@
+// array.rs     ndarray interoperability (optional feature).
+//
+// Copyright (c) 2024  Douglas P Lau
+//
+use crate::el::Pixel;
+use crate::raster::Raster;
+use ndarray::ArrayView3;
+
+impl<P: Pixel> Raster<P> {
+    /// Get a view of the pixels as a 3-dimensional array.
+    ///
+    /// The view is shaped (*height*, *width*, *channels*), so a channel is
+    /// addressed as `[[y, x, c]]`.  No data is copied.
+    ///
+    /// Elements are raw channel values ([Ch8], [Ch16], [Ch32] or [Ch64]), in
+    /// the order of the color model, with no gamma or alpha conversion.  For
+    /// *premultiplied* formats, color channels are already multiplied by
+    /// alpha.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::chan::Ch8;
+    /// use pix::rgb::SRgb8;
+    /// use pix::Raster;
+    ///
+    /// let r = Raster::with_color(4, 2, SRgb8::new(0x10, 0x20, 0x30));
+    /// let a = r.as_array_view();
+    /// let sum: u32 = a.iter().map(|c| u32::from(u8::from(*c))).sum();
+    /// assert_eq!(sum, 8 * 0x60);
+    /// ```
+    /// [Ch8]: chan/struct.Ch8.html
+    /// [Ch16]: chan/struct.Ch16.html
+    /// [Ch32]: chan/struct.Ch32.html
+    /// [Ch64]: chan/struct.Ch64.html
+    pub fn as_array_view(&self) -> ArrayView3<'_, P::Chan> {
+        let channels =
+            std::mem::size_of::<P>() / std::mem::size_of::<P::Chan>();
+        let len = self.pixels().len() * channels;
+        // SAFETY: pixel types are repr(C) arrays of channels, with no padding
+        let chans = unsafe {
+            std::slice::from_raw_parts(
+                self.pixels().as_ptr() as *const P::Chan,
+                len,
+            )
+        };
+        let shape = (self.height() as usize, self.width() as usize, channels);
+        ArrayView3::from_shape(shape, chans).expect("shape matches length")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::chan::*;
+    use crate::gray::*;
+    use crate::rgb::*;
+    use crate::*;
+
+    #[test]
+    fn view_shape() {
+        let mut r = Raster::<SRgba8>::with_clear(5, 3);
+        *r.pixel_mut(4, 1) = SRgba8::new(1, 2, 3, 4);
+        let a = r.as_array_view();
+        assert_eq!(a.dim(), (3, 5, 4));
+        assert_eq!(a[[1, 4, 0]], Ch8::new(1));
+        assert_eq!(a[[1, 4, 3]], Ch8::new(4));
+        assert_eq!(a[[1, 3, 3]], Ch8::new(0));
+        let mut r = Raster::<Gray32>::with_clear(2, 7);
+        *r.pixel_mut(1, 6) = Gray32::new(0.5);
+        let a = r.as_array_view();
+        assert_eq!(a.dim(), (7, 2, 1));
+        assert_eq!(a[[6, 1, 0]], Ch32::new(0.5));
+    }
+}
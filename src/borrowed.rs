@@ -0,0 +1,236 @@
+// borrowed.rs  Borrowed raster images.
+//
+// Copyright (c) 2024  Douglas P Lau
+//
+use crate::access::{self, RasterMut, RasterRef};
+use crate::chan::{Linear, Premultiplied};
+use crate::el::Pixel;
+use crate::matte::Matte;
+use crate::ops::Blend;
+use crate::raster::{Raster, Region, Rows, RowsMut};
+use crate::raster::{HEIGHT_TOO_BIG, TOO_BIG, WIDTH_TOO_BIG};
+use std::convert::TryFrom;
+
+/// Image borrowed from a mutable slice of pixels.
+///
+/// This is created by the [from_slice_mut] method of [Raster], and has the
+/// same drawing methods.  Changes are written directly to the slice.
+///
+/// [from_slice_mut]: struct.Raster.html#method.from_slice_mut
+/// [Raster]: struct.Raster.html
+pub struct BorrowedRaster<'a, P: Pixel> {
+    width: i32,
+    height: i32,
+    pixels: &'a mut [P],
+}
+
+impl<'a, P: Pixel> BorrowedRaster<'a, P> {
+    /// Create a new borrowed raster.
+    pub(crate) fn new(width: u32, height: u32, pixels: &'a mut [P]) -> Self {
+        let width = i32::try_from(width).expect(WIDTH_TOO_BIG);
+        let height = i32::try_from(height).expect(HEIGHT_TOO_BIG);
+        let len = usize::try_from(width.checked_mul(height).expect(TOO_BIG))
+            .expect(TOO_BIG);
+        assert_eq!(len, pixels.len());
+        BorrowedRaster {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Get width of raster.
+    pub fn width(&self) -> u32 {
+        self.width as u32
+    }
+
+    /// Get height of raster.
+    pub fn height(&self) -> u32 {
+        self.height as u32
+    }
+
+    /// Clear all pixels to default value.
+    pub fn clear(&mut self) {
+        RasterMut::clear(self);
+    }
+
+    /// Get one pixel.
+    pub fn pixel(&self, x: i32, y: i32) -> P {
+        RasterRef::pixel(self, x, y)
+    }
+
+    /// Get a mutable pixel.
+    pub fn pixel_mut(&mut self, x: i32, y: i32) -> &mut P {
+        RasterMut::pixel_mut(self, x, y)
+    }
+
+    /// Get a slice of all pixels.
+    pub fn pixels(&self) -> &[P] {
+        self.pixels
+    }
+
+    /// Get a mutable slice of all pixels.
+    pub fn pixels_mut(&mut self) -> &mut [P] {
+        self.pixels
+    }
+
+    /// Get an `Iterator` of rows within the raster.
+    ///
+    /// * `reg` Region of the raster to iterate.
+    pub fn rows<R>(&self, reg: R) -> Rows<'_, P>
+    where
+        R: Into<Region>,
+    {
+        RasterRef::rows(self, reg)
+    }
+
+    /// Get an `Iterator` of mutable rows within the raster.
+    ///
+    /// * `reg` Region of the raster to iterate.
+    pub fn rows_mut<R>(&mut self, reg: R) -> RowsMut<'_, P>
+    where
+        R: Into<Region>,
+    {
+        RasterMut::rows_mut(self, reg)
+    }
+
+    /// Get `Region` of entire raster.
+    pub fn region(&self) -> Region {
+        RasterRef::region(self)
+    }
+
+    /// Get intersection with a `Region`.
+    pub fn intersection<R>(&self, reg: R) -> Region
+    where
+        R: Into<Region>,
+    {
+        RasterRef::intersection(self, reg)
+    }
+
+    /// Copy a color to a region of the raster.
+    ///
+    /// See [Raster::copy_color](struct.Raster.html#method.copy_color).
+    pub fn copy_color<R>(&mut self, reg: R, clr: P)
+    where
+        R: Into<Region>,
+    {
+        RasterMut::copy_color(self, reg, clr);
+    }
+
+    /// Copy from a source `Raster`.
+    ///
+    /// See [Raster::copy_raster](struct.Raster.html#method.copy_raster).
+    pub fn copy_raster<R0, R1>(&mut self, to: R0, src: &Raster<P>, from: R1)
+    where
+        R0: Into<Region>,
+        R1: Into<Region>,
+    {
+        access::copy_raster(self, to.into(), src, from.into());
+    }
+}
+
+impl<P> BorrowedRaster<'_, P>
+where
+    P: Pixel<Alpha = Premultiplied, Gamma = Linear>,
+{
+    /// Composite a source color to a region of the raster.
+    ///
+    /// See [Raster::composite_color].
+    ///
+    /// [Raster::composite_color]: struct.Raster.html#method.composite_color
+    pub fn composite_color<R, O>(&mut self, reg: R, clr: P, op: O)
+    where
+        R: Into<Region>,
+        O: Blend,
+    {
+        access::composite_color(self, reg.into(), clr, op);
+    }
+
+    /// Composite from a matte `Raster` and color.
+    ///
+    /// See [Raster::composite_matte].
+    ///
+    /// [Raster::composite_matte]: struct.Raster.html#method.composite_matte
+    pub fn composite_matte<R0, R1, M, O>(
+        &mut self,
+        to: R0,
+        src: &Raster<M>,
+        from: R1,
+        clr: P,
+        op: O,
+    ) where
+        R0: Into<Region>,
+        R1: Into<Region>,
+        M: Pixel<Chan = P::Chan, Model = Matte, Gamma = P::Gamma>,
+        O: Blend,
+    {
+        access::composite_matte(self, to.into(), src, from.into(), clr, op);
+    }
+
+    /// Composite from a source `Raster`.
+    ///
+    /// See [Raster::composite_raster].
+    ///
+    /// [Raster::composite_raster]: struct.Raster.html#method.composite_raster
+    pub fn composite_raster<R0, R1, O>(
+        &mut self,
+        to: R0,
+        src: &Raster<P>,
+        from: R1,
+        op: O,
+    ) where
+        R0: Into<Region>,
+        R1: Into<Region>,
+        O: Blend,
+    {
+        access::composite_raster(self, to.into(), src, from.into(), op);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::matte::*;
+    use crate::ops::SrcOver;
+    use crate::rgb::*;
+    use crate::*;
+
+    #[test]
+    fn draw_into_slice() {
+        let mut buf = [Rgba8p::default(); 12];
+        let clr = Rgba8p::new(0x20, 0x40, 0x60, 0xFF);
+        {
+            let mut r = Raster::from_slice_mut(4, 3, &mut buf);
+            r.composite_color((1, 1, 2, 1), clr, SrcOver);
+            let src = Raster::with_color(1, 1, clr);
+            r.copy_raster((3, 2), &src, ());
+        }
+        let z = Rgba8p::default();
+        let v = [z, z, z, z, z, clr, clr, z, z, z, z, clr];
+        assert_eq!(buf, v);
+    }
+
+    #[test]
+    fn composite_same_as_owned() {
+        let clr = Rgba8p::new(0x20, 0x40, 0x60, 0x80);
+        let matte = Raster::with_color(2, 2, Matte8::new(0x80));
+        let src = Raster::with_color(2, 2, clr);
+        let mut owned = Raster::with_color(4, 3, Rgba8p::new(0, 0, 0xFF, 0xFF));
+        let mut buf = [Rgba8p::new(0, 0, 0xFF, 0xFF); 12];
+        {
+            let mut r = Raster::from_slice_mut(4, 3, &mut buf);
+            r.composite_matte((-1, 0), &matte, (), clr, SrcOver);
+            r.composite_raster((3, 1), &src, (), SrcOver);
+        }
+        owned.composite_matte((-1, 0), &matte, (), clr, SrcOver);
+        owned.composite_raster((3, 1), &src, (), SrcOver);
+        assert_eq!(&buf, owned.pixels());
+        assert_ne!(buf[0], buf[1]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn wrong_length() {
+        let mut buf = [SRgb8::default(); 12];
+        Raster::from_slice_mut(4, 4, &mut buf);
+    }
+}
@@ -71,6 +71,12 @@ pub trait Gamma: Copy + Clone + Debug + Default + PartialEq + Sealed {
     fn to_linear<C: Channel>(c: C) -> C;
     /// Convert a `Channel` value from linear.
     fn from_linear<C: Channel>(c: C) -> C;
+    /// Convert a `Channel` value to linear `f32`.
+    ///
+    /// This avoids losing precision when filtering integer channels.
+    fn to_linear_f32<C: Channel>(c: C) -> f32;
+    /// Convert a linear `f32` value to a `Channel`.
+    fn from_linear_f32<C: Channel>(v: f32) -> C;
 }
 
 /// [Channel](trait.Channel.html)s are encoded with linear
@@ -96,6 +102,14 @@ impl Gamma for Linear {
     fn from_linear<C: Channel>(c: C) -> C {
         c
     }
+    /// Convert a `Channel` value to linear `f32`.
+    fn to_linear_f32<C: Channel>(c: C) -> f32 {
+        c.to_f32()
+    }
+    /// Convert a linear `f32` value to a `Channel`.
+    fn from_linear_f32<C: Channel>(v: f32) -> C {
+        C::from(v)
+    }
 }
 
 impl Gamma for Srgb {
@@ -107,17 +121,26 @@ impl Gamma for Srgb {
     fn from_linear<C: Channel>(c: C) -> C {
         c.encode_srgb()
     }
+    /// Convert a `Channel` value to linear `f32`.
+    fn to_linear_f32<C: Channel>(c: C) -> f32 {
+        c.decode_srgb_f32()
+    }
+    /// Convert a linear `f32` value to a `Channel`.
+    fn from_linear_f32<C: Channel>(v: f32) -> C {
+        C::from(srgb_gamma_encode(v))
+    }
 }
 
 /// *Component* of a [color model], such as *red*, *green*, *etc*.
 ///
-/// Existing `Channel`s are [Ch8], [Ch16] and [Ch32].
+/// Existing `Channel`s are [Ch8], [Ch16], [Ch32] and [Ch64].
 ///
 /// This trait is *sealed*, and cannot be implemented outside of this crate.
 ///
 /// [Ch8]: struct.Ch8.html
 /// [Ch16]: struct.Ch16.html
 /// [Ch32]: struct.Ch32.html
+/// [Ch64]: struct.Ch64.html
 /// [color model]: ../trait.ColorModel.html
 pub trait Channel:
     Copy
@@ -155,7 +178,14 @@ pub trait Channel:
     /// Decode an sRGB gamma value into linear intensity
     fn decode_srgb(self) -> Self;
 
+    /// Decode an sRGB gamma value into linear intensity as `f32`
+    fn decode_srgb_f32(self) -> f32;
+
     /// Linear interpolation
+    ///
+    /// The result is `self` when `t` is `MIN`, and `rhs` when `t` is `MAX`.
+    /// Unlike compositing, no *alpha* semantics are implied, so this is
+    /// suitable for gradients or animation.
     fn lerp(self, rhs: Self, t: Self) -> Self;
 }
 
@@ -222,6 +252,28 @@ pub struct Ch16(u16);
 #[repr(transparent)]
 pub struct Ch32(f32);
 
+/// 64-bit color [Channel](trait.Channel.html).
+///
+/// The `Channel` is represented by an `f64`, but values are guaranteed to be
+/// between 0 and 1, inclusive.  This is useful for high-precision
+/// pipelines, such as scientific imaging.
+///
+/// ```
+/// use pix::chan::{Ch32, Ch64, Ch8, Channel};
+///
+/// let c: Ch64 = 0.0.into();
+/// assert_eq!(c, Ch64::MIN);
+/// let c: Ch8 = c.into();
+/// assert_eq!(c, Ch8::MIN);
+/// let c: Ch64 = 1.0.into();
+/// assert_eq!(c, Ch64::MAX);
+/// let c: Ch32 = c.into();
+/// assert_eq!(c, Ch32::MAX);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+#[repr(transparent)]
+pub struct Ch64(f64);
+
 impl Ch8 {
     /// Create a new 8-bit `Channel` value.
     pub const fn new(value: u8) -> Self {
@@ -265,6 +317,11 @@ impl Channel for Ch8 {
         Self::new(s)
     }
 
+    /// Decode an sRGB gamma value into linear intensity as `f32`
+    fn decode_srgb_f32(self) -> f32 {
+        DECODE_SRGB_F32[usize::from(u8::from(self))]
+    }
+
     /// Linear interpolation
     #[inline]
     fn lerp(self, rhs: Self, t: Self) -> Self {
@@ -409,6 +466,11 @@ impl Channel for Ch16 {
         Self::new(s)
     }
 
+    /// Decode an sRGB gamma value into linear intensity as `f32`
+    fn decode_srgb_f32(self) -> f32 {
+        srgb_gamma_decode(self.to_f32())
+    }
+
     /// Linear interpolation
     #[inline]
     fn lerp(self, rhs: Self, t: Self) -> Self {
@@ -584,6 +646,11 @@ impl Channel for Ch32 {
         Self::new(s)
     }
 
+    /// Decode an sRGB gamma value into linear intensity as `f32`
+    fn decode_srgb_f32(self) -> f32 {
+        srgb_gamma_decode(f32::from(self))
+    }
+
     /// Linear interpolation
     #[inline]
     fn lerp(self, rhs: Self, t: Self) -> Self {
@@ -694,25 +761,256 @@ where
     }
 }
 
+/// Encode an sRGB gamma value from linear intensity (`f64`)
+fn srgb_gamma_encode_f64(v: f64) -> f64 {
+    if v <= 0.0 {
+        0.0
+    } else if v < 0.003_130_8 {
+        v * 12.92
+    } else if v < 1.0 {
+        v.powf(1.0 / 2.4) * 1.055 - 0.055
+    } else {
+        1.0
+    }
+}
+
+/// Decode an sRGB gamma value into linear intensity (`f64`)
+fn srgb_gamma_decode_f64(v: f64) -> f64 {
+    if v <= 0.0 {
+        0.0
+    } else if v < 0.04045 {
+        v / 12.92
+    } else if v < 1.0 {
+        ((v + 0.055) / 1.055).powf(2.4)
+    } else {
+        1.0
+    }
+}
+
+impl Ch64 {
+    /// Create a new 64-bit `Channel` value.
+    ///
+    /// Returns [MIN](trait.Channel.html#associatedconstant.MIN) if value is
+    ///         less than 0.0, or `NaN`.
+    /// Returns [MAX](trait.Channel.html#associatedconstant.MAX) if value is
+    ///         greater than 1.0.
+    pub fn new(value: f64) -> Self {
+        let v = if value.is_nan() || value < 0.0 {
+            0.0
+        } else if value > 1.0 {
+            1.0
+        } else {
+            value
+        };
+        Ch64(v)
+    }
+}
+
+impl Channel for Ch64 {
+    const MIN: Ch64 = Ch64(0.0);
+
+    const MID: Ch64 = Ch64(0.5);
+
+    const MAX: Ch64 = Ch64(1.0);
+
+    /// Convert to `f32`
+    fn to_f32(self) -> f32 {
+        self.0 as f32
+    }
+
+    /// Wrapping addition
+    fn wrapping_add(self, rhs: Self) -> Self {
+        let v = self.0 + rhs.0;
+        if v <= 1.0 {
+            Self::new(v)
+        } else {
+            Self::new(v - 1.0)
+        }
+    }
+
+    /// Wrapping subtraction
+    fn wrapping_sub(self, rhs: Self) -> Self {
+        let v = self.0 - rhs.0;
+        if v >= 0.0 {
+            Self::new(v)
+        } else {
+            Self::new(v + 1.0)
+        }
+    }
+
+    /// Encode an sRGB gamma value from linear intensity
+    fn encode_srgb(self) -> Self {
+        let s = srgb_gamma_encode_f64(f64::from(self));
+        Self::new(s)
+    }
+
+    /// Decode an sRGB gamma value into linear intensity
+    fn decode_srgb(self) -> Self {
+        let s = srgb_gamma_decode_f64(f64::from(self));
+        Self::new(s)
+    }
+
+    /// Decode an sRGB gamma value into linear intensity as `f32`
+    fn decode_srgb_f32(self) -> f32 {
+        srgb_gamma_decode_f64(f64::from(self)) as f32
+    }
+
+    /// Linear interpolation
+    #[inline]
+    fn lerp(self, rhs: Self, t: Self) -> Self {
+        let v0 = f64::from(self);
+        let v1 = f64::from(rhs);
+        let r = v0 + f64::from(t) * (v1 - v0);
+        Self::new(r)
+    }
+}
+
+impl From<Ch8> for Ch64 {
+    fn from(c: Ch8) -> Self {
+        Ch64(f64::from(c.0) / 255.0)
+    }
+}
+
+impl From<Ch16> for Ch64 {
+    fn from(c: Ch16) -> Self {
+        Ch64(f64::from(c.0) / 65535.0)
+    }
+}
+
+impl From<Ch32> for Ch64 {
+    fn from(c: Ch32) -> Self {
+        Ch64(f64::from(c.0))
+    }
+}
+
+impl From<f32> for Ch64 {
+    fn from(value: f32) -> Self {
+        Ch64::new(value.into())
+    }
+}
+
+impl From<f64> for Ch64 {
+    fn from(value: f64) -> Self {
+        Ch64::new(value)
+    }
+}
+
+impl From<Ch64> for f64 {
+    fn from(c: Ch64) -> f64 {
+        c.0
+    }
+}
+
+impl From<Ch64> for Ch8 {
+    fn from(c: Ch64) -> Self {
+        let value = c.0;
+        debug_assert!((0.0..=1.0).contains(&value));
+        // this cast is not UB since the value is guaranteed
+        // to be between 0.0 and 1.0 (see bug #10184)
+        Ch8::new((value * 255.0).round() as u8)
+    }
+}
+
+impl From<Ch64> for Ch16 {
+    fn from(c: Ch64) -> Self {
+        let value = c.0;
+        debug_assert!((0.0..=1.0).contains(&value));
+        // this cast is not UB since the value is guaranteed
+        // to be between 0.0 and 1.0 (see bug #10184)
+        Ch16::new((value * 65535.0).round() as u16)
+    }
+}
+
+impl From<Ch64> for Ch32 {
+    fn from(c: Ch64) -> Self {
+        Ch32::new(c.0 as f32)
+    }
+}
+
+impl Eq for Ch64 {}
+
+#[allow(clippy::derive_ord_xor_partial_ord)]
+impl Ord for Ch64 {
+    fn cmp(&self, other: &Ch64) -> Ordering {
+        self.partial_cmp(other).unwrap()
+    }
+}
+
+impl<R> Add<R> for Ch64
+where
+    Self: From<R>,
+{
+    type Output = Self;
+    fn add(self, rhs: R) -> Self {
+        let value = self.0 + Self::from(rhs).0;
+        Ch64(value.min(1.0))
+    }
+}
+
+impl<R> Sub<R> for Ch64
+where
+    Self: From<R>,
+{
+    type Output = Self;
+    fn sub(self, rhs: R) -> Self {
+        let value = self.0 - Self::from(rhs).0;
+        Ch64(value.max(0.0))
+    }
+}
+
+impl<R> Mul<R> for Ch64
+where
+    Self: From<R>,
+{
+    type Output = Self;
+    fn mul(self, rhs: R) -> Self {
+        Ch64(self.0 * Self::from(rhs).0)
+    }
+}
+
+impl<R> Div<R> for Ch64
+where
+    Self: From<R>,
+{
+    type Output = Self;
+    fn div(self, rhs: R) -> Self {
+        let v = Self::from(rhs).0;
+        if v > 0.0 {
+            Ch64((self.0 / v).min(1.0))
+        } else {
+            Ch64(0.0)
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::chan::*;
 
     #[test]
     fn lut_encode_u8() {
-        for i in 0..=255 {
+        for (i, lut) in ENCODE_SRGB_U8.iter().enumerate() {
             let s = i as f32 / 255.0;
             let v = (srgb_gamma_encode(s) * 255.0).round() as u8;
-            assert_eq!(v, ENCODE_SRGB_U8[i]);
+            assert_eq!(v, *lut);
         }
     }
 
     #[test]
     fn lut_decode_u8() {
-        for i in 0..=255 {
+        for (i, lut) in DECODE_SRGB_U8.iter().enumerate() {
             let s = i as f32 / 255.0;
             let v = (srgb_gamma_decode(s) * 255.0).round() as u8;
-            assert_eq!(v, DECODE_SRGB_U8[i]);
+            assert_eq!(v, *lut);
+        }
+    }
+
+    #[test]
+    fn lut_decode_f32() {
+        for (i, lut) in DECODE_SRGB_F32.iter().enumerate() {
+            let s = i as f32 / 255.0;
+            assert_eq!(srgb_gamma_decode(s), *lut);
+            assert_eq!(Ch8::new(i as u8).decode_srgb_f32(), *lut);
         }
     }
 
@@ -741,6 +1039,22 @@ mod test {
         assert_eq!(Ch16::new(65535), Ch8::new(255).into());
     }
 
+    #[test]
+    fn depth_matrix() {
+        assert_eq!(Ch16::new(0xABAB), Ch16::from(Ch8::new(0xAB)));
+        assert_eq!(Ch8::new(0xAB), Ch8::from(Ch16::new(0xABFF)));
+        assert_eq!(Ch8::new(0xAB), Ch8::from(Ch16::new(0xAB00)));
+        assert_eq!(Ch32::new(1.0), Ch32::from(Ch16::new(0xFFFF)));
+        assert_eq!(Ch16::new(0x8000), Ch16::from(Ch32::new(0.5)));
+        assert_eq!(Ch8::new(0xFF), Ch8::from(Ch32::from(2.0)));
+        assert_eq!(Ch16::new(0), Ch16::from(Ch32::from(-1.0)));
+        for i in 0..=255 {
+            let c8 = Ch8::new(i);
+            assert_eq!(c8, Ch8::from(Ch32::from(Ch16::from(c8))));
+            assert_eq!(Ch16::from(c8), Ch16::from(Ch32::from(c8)));
+        }
+    }
+
     #[test]
     fn ch32_into() {
         assert_eq!(Ch32::new(1.0), 1.0.into());
@@ -818,4 +1132,76 @@ mod test {
         assert_eq!(Ch32::new(0.0625), Ch32::new(0.5) * 0.125);
         assert_eq!(Ch32::new(0.03125), Ch32::new(0.5) * 0.0625);
     }
+    #[test]
+    fn ch64_into() {
+        assert_eq!(Ch64::new(1.0), 1.0.into());
+        assert_eq!(Ch64::new(0.5), 0.5.into());
+        assert_eq!(Ch64::new(0.25), 0.25_f32.into());
+        assert_eq!(Ch64::new(0.0), Ch8::new(0).into());
+        assert_eq!(Ch64::new(1.0), Ch8::new(255).into());
+        assert_eq!(Ch64::new(1.0), Ch16::new(65535).into());
+        assert_eq!(Ch64::new(0.5), Ch32::new(0.5).into());
+        assert_eq!(Ch8::new(128), Ch64::new(0.5).into());
+        assert_eq!(Ch16::new(32768), Ch64::new(0.5).into());
+        assert_eq!(Ch32::new(0.25), Ch64::new(0.25).into());
+    }
+    #[test]
+    fn ch64_mul() {
+        assert_eq!(Ch64::new(1.0), Ch64::new(1.0) * 1.0);
+        assert_eq!(Ch64::new(0.5), Ch64::new(1.0) * 0.5);
+        assert_eq!(Ch64::new(0.25), Ch64::new(1.0) * 0.25);
+        assert_eq!(Ch64::new(0.25), Ch64::new(0.5) * 0.5);
+        assert_eq!(Ch64::new(0.125), Ch64::new(0.5) * 0.25);
+        assert_eq!(Ch64::new(0.0625), Ch64::new(0.25) * 0.25);
+    }
+    #[test]
+    fn ch64_div() {
+        assert_eq!(Ch64::new(1.0), Ch64::new(0.5) / 0.5);
+        assert_eq!(Ch64::new(1.0), Ch64::new(0.25) / 0.25);
+        assert_eq!(Ch64::new(0.5), Ch64::new(0.25) / 0.5);
+        assert_eq!(Ch64::new(0.5), Ch64::new(0.125) / 0.25);
+        assert_eq!(Ch64::new(1.0), Ch64::new(0.5) / 0.25);
+        assert_eq!(Ch64::new(0.0), Ch64::new(0.5) / 0.0);
+    }
+    #[test]
+    fn ch64_srgb() {
+        for i in 0..=255 {
+            let c = Ch8::new(i);
+            let v = Ch64::from(c);
+            assert_eq!(c.decode_srgb(), Ch8::from(v.decode_srgb()));
+            assert_eq!(c.encode_srgb(), Ch8::from(v.encode_srgb()));
+            let d = f64::from(v.encode_srgb().decode_srgb()) - f64::from(v);
+            assert!(d.abs() < 1e-12, "{i} {d}");
+        }
+    }
+    #[test]
+    fn ch8_lerp() {
+        let (a, b) = (Ch8::new(0x10), Ch8::new(0xF0));
+        assert_eq!(a, a.lerp(b, Ch8::MIN));
+        assert_eq!(b, a.lerp(b, Ch8::MAX));
+        assert_eq!(Ch8::new(0x80), a.lerp(b, Ch8::new(0x80)));
+        assert_eq!(b, b.lerp(a, Ch8::MIN));
+        assert_eq!(a, b.lerp(a, Ch8::MAX));
+        assert_eq!(Ch8::new(0x80), b.lerp(a, Ch8::new(0x7F)));
+    }
+    #[test]
+    fn ch16_lerp() {
+        let (a, b) = (Ch16::new(0x1000), Ch16::new(0xF000));
+        assert_eq!(a, a.lerp(b, Ch16::MIN));
+        assert_eq!(b, a.lerp(b, Ch16::MAX));
+        assert_eq!(Ch16::new(0x8000), a.lerp(b, Ch16::new(0x8000)));
+        assert_eq!(b, b.lerp(a, Ch16::MIN));
+        assert_eq!(a, b.lerp(a, Ch16::MAX));
+        assert_eq!(Ch16::new(0x8000), b.lerp(a, Ch16::new(0x7FFF)));
+    }
+    #[test]
+    fn ch32_lerp() {
+        let (a, b) = (Ch32::new(0.25), Ch32::new(0.75));
+        assert_eq!(a, a.lerp(b, Ch32::MIN));
+        assert_eq!(b, a.lerp(b, Ch32::MAX));
+        assert_eq!(Ch32::new(0.5), a.lerp(b, Ch32::new(0.5)));
+        assert_eq!(b, b.lerp(a, Ch32::MIN));
+        assert_eq!(a, b.lerp(a, Ch32::MAX));
+        assert_eq!(Ch32::new(0.5), b.lerp(a, Ch32::new(0.5)));
+    }
 }
@@ -0,0 +1,343 @@
+// cmyk.rs      CMYK color model.
+//
+// Copyright (c) 2024  Douglas P Lau
+//
+//! [CMYK] color model and types.
+//!
+//! [cmyk]: https://en.wikipedia.org/wiki/CMYK_color_model
+use crate::chan::{
+    Ch16, Ch32, Ch8, Channel, Linear, Premultiplied, Srgb, Straight,
+};
+use crate::el::{Pix4, Pix5, PixRgba, Pixel};
+use crate::ColorModel;
+use std::ops::Range;
+
+/// [CMYK] subtractive [color model].
+///
+/// The components are *[cyan]*, *[magenta]*, *[yellow]*, *[key]* (black) and
+/// optional *[alpha]*.
+///
+/// Conversion to and from RGB is naive, without any ink or paper profile.
+/// When converting from RGB, *key* is set as high as possible (full gray
+/// component replacement).
+///
+/// [alpha]: ../el/trait.Pixel.html#method.alpha
+/// [cmyk]: https://en.wikipedia.org/wiki/CMYK_color_model
+/// [color model]: ../trait.ColorModel.html
+/// [cyan]: #method.cyan
+/// [key]: #method.key
+/// [magenta]: #method.magenta
+/// [yellow]: #method.yellow
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Cmyk {}
+
+impl Cmyk {
+    /// Get the *cyan* component.
+    ///
+    /// # Example: Get CMYK Cyan
+    /// ```
+    /// use pix::cmyk::{Cmyk, Cmyk8};
+    /// use pix::chan::Ch8;
+    ///
+    /// let p = Cmyk8::new(0x93, 0x80, 0xA0, 0x10);
+    /// assert_eq!(Cmyk::cyan(p), Ch8::new(0x93));
+    /// ```
+    pub fn cyan<P>(p: P) -> P::Chan
+    where
+        P: Pixel<Model = Self>,
+    {
+        p.one()
+    }
+
+    /// Get a mutable reference to the *cyan* component.
+    ///
+    /// # Example: Modify CMYK Cyan
+    /// ```
+    /// use pix::cmyk::{Cmyk, Cmyk8};
+    /// use pix::chan::Ch8;
+    ///
+    /// let mut p = Cmyk8::new(0x88, 0x77, 0x66, 0x10);
+    /// *Cmyk::cyan_mut(&mut p) = 0x55.into();
+    /// assert_eq!(Cmyk::cyan(p), Ch8::new(0x55));
+    /// ```
+    pub fn cyan_mut<P>(p: &mut P) -> &mut P::Chan
+    where
+        P: Pixel<Model = Self>,
+    {
+        p.one_mut()
+    }
+
+    /// Get the *magenta* component.
+    ///
+    /// # Example: CMYK Magenta
+    /// ```
+    /// use pix::cmyk::{Cmyk, Cmyk16};
+    /// use pix::chan::Ch16;
+    ///
+    /// let p = Cmyk16::new(0x2000, 0x1234, 0x8000, 0x0100);
+    /// assert_eq!(Cmyk::magenta(p), Ch16::new(0x1234));
+    /// ```
+    pub fn magenta<P>(p: P) -> P::Chan
+    where
+        P: Pixel<Model = Self>,
+    {
+        p.two()
+    }
+
+    /// Get a mutable reference to the *magenta* component.
+    ///
+    /// # Example: Modify CMYK Magenta
+    /// ```
+    /// use pix::cmyk::{Cmyk, Cmyk16};
+    /// use pix::chan::Ch16;
+    ///
+    /// let mut p = Cmyk16::new(0x2000, 0x1234, 0x8000, 0x0100);
+    /// *Cmyk::magenta_mut(&mut p) = 0x4321.into();
+    /// assert_eq!(Cmyk::magenta(p), Ch16::new(0x4321));
+    /// ```
+    pub fn magenta_mut<P>(p: &mut P) -> &mut P::Chan
+    where
+        P: Pixel<Model = Self>,
+    {
+        p.two_mut()
+    }
+
+    /// Get the *yellow* component.
+    ///
+    /// # Example: CMYK Yellow
+    /// ```
+    /// use pix::cmyk::{Cmyk, Cmyk32};
+    /// use pix::chan::Ch32;
+    ///
+    /// let p = Cmyk32::new(0.25, 0.5, 1.0, 0.0);
+    /// assert_eq!(Cmyk::yellow(p), Ch32::new(1.0));
+    /// ```
+    pub fn yellow<P>(p: P) -> P::Chan
+    where
+        P: Pixel<Model = Self>,
+    {
+        p.three()
+    }
+
+    /// Get a mutable reference to the *yellow* component.
+    ///
+    /// # Example: Modify CMYK Yellow
+    /// ```
+    /// use pix::cmyk::{Cmyk, Cmyk32};
+    /// use pix::chan::Ch32;
+    ///
+    /// let mut p = Cmyk32::new(0.25, 0.5, 1.0, 0.0);
+    /// *Cmyk::yellow_mut(&mut p) = Ch32::new(0.75);
+    /// assert_eq!(Cmyk::yellow(p), Ch32::new(0.75));
+    /// ```
+    pub fn yellow_mut<P>(p: &mut P) -> &mut P::Chan
+    where
+        P: Pixel<Model = Self>,
+    {
+        p.three_mut()
+    }
+
+    /// Get the *key* (black) component.
+    ///
+    /// # Example: CMYK Key
+    /// ```
+    /// use pix::cmyk::{Cmyk, Cmyk8};
+    /// use pix::chan::Ch8;
+    ///
+    /// let p = Cmyk8::new(0x00, 0x40, 0x80, 0xC0);
+    /// assert_eq!(Cmyk::key(p), Ch8::new(0xC0));
+    /// ```
+    pub fn key<P>(p: P) -> P::Chan
+    where
+        P: Pixel<Model = Self>,
+    {
+        p.four()
+    }
+
+    /// Get a mutable reference to the *key* (black) component.
+    ///
+    /// # Example: Modify CMYK Key
+    /// ```
+    /// use pix::cmyk::{Cmyk, Cmyk8};
+    /// use pix::chan::Ch8;
+    ///
+    /// let mut p = Cmyk8::new(0x00, 0x40, 0x80, 0xC0);
+    /// *Cmyk::key_mut(&mut p) = 0x20.into();
+    /// assert_eq!(Cmyk::key(p), Ch8::new(0x20));
+    /// ```
+    pub fn key_mut<P>(p: &mut P) -> &mut P::Chan
+    where
+        P: Pixel<Model = Self>,
+    {
+        p.four_mut()
+    }
+}
+
+impl ColorModel for Cmyk {
+    const CIRCULAR: Range<usize> = 0..0;
+    const LINEAR: Range<usize> = 0..4;
+    const ALPHA: usize = 4;
+
+    /// Convert into *red*, *green*, *blue* and *alpha* components
+    fn into_rgba<P>(p: P) -> PixRgba<P>
+    where
+        P: Pixel<Model = Self>,
+    {
+        let white = P::Chan::MAX - Cmyk::key(p);
+        let red = (P::Chan::MAX - Cmyk::cyan(p)) * white;
+        let green = (P::Chan::MAX - Cmyk::magenta(p)) * white;
+        let blue = (P::Chan::MAX - Cmyk::yellow(p)) * white;
+        PixRgba::<P>::new::<P::Chan>(red, green, blue, p.alpha())
+    }
+
+    /// Convert from *red*, *green*, *blue* and *alpha* components
+    fn from_rgba<P>(rgba: PixRgba<P>) -> P
+    where
+        P: Pixel<Model = Self>,
+    {
+        let chan = rgba.channels();
+        let white = chan[0].max(chan[1]).max(chan[2]);
+        let key = P::Chan::MAX - white;
+        let cyan = (white - chan[0]) / white;
+        let magenta = (white - chan[1]) / white;
+        let yellow = (white - chan[2]) / white;
+        let alpha = chan[3];
+        P::from_channels(&[cyan, magenta, yellow, key, alpha])
+    }
+}
+
+/// [Cmyk](struct.Cmyk.html) 8-bit opaque (no *alpha* channel)
+/// [linear](../chan/struct.Linear.html) gamma [pixel](../el/trait.Pixel.html)
+/// format.
+pub type Cmyk8 = Pix4<Ch8, Cmyk, Straight, Linear>;
+
+/// [Cmyk](struct.Cmyk.html) 16-bit opaque (no *alpha* channel)
+/// [linear](../chan/struct.Linear.html) gamma [pixel](../el/trait.Pixel.html)
+/// format.
+pub type Cmyk16 = Pix4<Ch16, Cmyk, Straight, Linear>;
+
+/// [Cmyk](struct.Cmyk.html) 32-bit opaque (no *alpha* channel)
+/// [linear](../chan/struct.Linear.html) gamma [pixel](../el/trait.Pixel.html)
+/// format.
+pub type Cmyk32 = Pix4<Ch32, Cmyk, Straight, Linear>;
+
+/// [Cmyk](struct.Cmyk.html) 8-bit [straight](../chan/struct.Straight.html)
+/// alpha [linear](../chan/struct.Linear.html) gamma
+/// [pixel](../el/trait.Pixel.html) format.
+pub type Cmyka8 = Pix5<Ch8, Cmyk, Straight, Linear>;
+
+/// [Cmyk](struct.Cmyk.html) 16-bit [straight](../chan/struct.Straight.html)
+/// alpha [linear](../chan/struct.Linear.html) gamma
+/// [pixel](../el/trait.Pixel.html) format.
+pub type Cmyka16 = Pix5<Ch16, Cmyk, Straight, Linear>;
+
+/// [Cmyk](struct.Cmyk.html) 32-bit [straight](../chan/struct.Straight.html)
+/// alpha [linear](../chan/struct.Linear.html) gamma
+/// [pixel](../el/trait.Pixel.html) format.
+pub type Cmyka32 = Pix5<Ch32, Cmyk, Straight, Linear>;
+
+/// [Cmyk](struct.Cmyk.html) 8-bit
+/// [premultiplied](../chan/struct.Premultiplied.html) alpha
+/// [linear](../chan/struct.Linear.html) gamma [pixel](../el/trait.Pixel.html)
+/// format.
+pub type Cmyka8p = Pix5<Ch8, Cmyk, Premultiplied, Linear>;
+
+/// [Cmyk](struct.Cmyk.html) 16-bit
+/// [premultiplied](../chan/struct.Premultiplied.html) alpha
+/// [linear](../chan/struct.Linear.html) gamma [pixel](../el/trait.Pixel.html)
+/// format.
+pub type Cmyka16p = Pix5<Ch16, Cmyk, Premultiplied, Linear>;
+
+/// [Cmyk](struct.Cmyk.html) 32-bit
+/// [premultiplied](../chan/struct.Premultiplied.html) alpha
+/// [linear](../chan/struct.Linear.html) gamma [pixel](../el/trait.Pixel.html)
+/// format.
+pub type Cmyka32p = Pix5<Ch32, Cmyk, Premultiplied, Linear>;
+
+/// [Cmyk](struct.Cmyk.html) 8-bit opaque (no *alpha* channel)
+/// [sRGB](../chan/struct.Srgb.html) gamma [pixel](../el/trait.Pixel.html)
+/// format.
+pub type SCmyk8 = Pix4<Ch8, Cmyk, Straight, Srgb>;
+
+/// [Cmyk](struct.Cmyk.html) 16-bit opaque (no *alpha* channel)
+/// [sRGB](../chan/struct.Srgb.html) gamma [pixel](../el/trait.Pixel.html)
+/// format.
+pub type SCmyk16 = Pix4<Ch16, Cmyk, Straight, Srgb>;
+
+/// [Cmyk](struct.Cmyk.html) 32-bit opaque (no *alpha* channel)
+/// [sRGB](../chan/struct.Srgb.html) gamma [pixel](../el/trait.Pixel.html)
+/// format.
+pub type SCmyk32 = Pix4<Ch32, Cmyk, Straight, Srgb>;
+
+/// [Cmyk](struct.Cmyk.html) 8-bit [straight](../chan/struct.Straight.html)
+/// alpha [sRGB](../chan/struct.Srgb.html) gamma
+/// [pixel](../el/trait.Pixel.html) format.
+pub type SCmyka8 = Pix5<Ch8, Cmyk, Straight, Srgb>;
+
+/// [Cmyk](struct.Cmyk.html) 16-bit [straight](../chan/struct.Straight.html)
+/// alpha [sRGB](../chan/struct.Srgb.html) gamma
+/// [pixel](../el/trait.Pixel.html) format.
+pub type SCmyka16 = Pix5<Ch16, Cmyk, Straight, Srgb>;
+
+/// [Cmyk](struct.Cmyk.html) 32-bit [straight](../chan/struct.Straight.html)
+/// alpha [sRGB](../chan/struct.Srgb.html) gamma
+/// [pixel](../el/trait.Pixel.html) format.
+pub type SCmyka32 = Pix5<Ch32, Cmyk, Straight, Srgb>;
+
+/// [Cmyk](struct.Cmyk.html) 8-bit
+/// [premultiplied](../chan/struct.Premultiplied.html) alpha
+/// [sRGB](../chan/struct.Srgb.html) gamma [pixel](../el/trait.Pixel.html)
+/// format.
+pub type SCmyka8p = Pix5<Ch8, Cmyk, Premultiplied, Srgb>;
+
+/// [Cmyk](struct.Cmyk.html) 16-bit
+/// [premultiplied](../chan/struct.Premultiplied.html) alpha
+/// [sRGB](../chan/struct.Srgb.html) gamma [pixel](../el/trait.Pixel.html)
+/// format.
+pub type SCmyka16p = Pix5<Ch16, Cmyk, Premultiplied, Srgb>;
+
+/// [Cmyk](struct.Cmyk.html) 32-bit
+/// [premultiplied](../chan/struct.Premultiplied.html) alpha
+/// [sRGB](../chan/struct.Srgb.html) gamma [pixel](../el/trait.Pixel.html)
+/// format.
+pub type SCmyka32p = Pix5<Ch32, Cmyk, Premultiplied, Srgb>;
+
+#[cfg(test)]
+mod test {
+    use crate::cmyk::*;
+    use crate::el::Pixel;
+    use crate::rgb::*;
+
+    #[test]
+    fn cmyk_to_rgb() {
+        assert_eq!(Rgb8::new(0, 255, 255), Cmyk8::new(255, 0, 0, 0).convert());
+        assert_eq!(Rgb8::new(0, 0, 0), Cmyk8::new(0, 0, 0, 255).convert());
+        assert_eq!(
+            Rgb8::new(255, 255, 0),
+            Cmyk32::new(0.0, 0.0, 1.0, 0.0).convert(),
+        );
+        assert_eq!(
+            Rgb32::new(0.25, 0.5, 0.5),
+            Cmyk32::new(0.5, 0.0, 0.0, 0.5).convert(),
+        );
+        assert_eq!(
+            Rgba8::new(0, 0, 255, 128),
+            Cmyka16::new(65535, 65535, 0, 0, 32896).convert(),
+        );
+    }
+
+    #[test]
+    fn rgb_to_cmyk() {
+        assert_eq!(Cmyk8::new(255, 0, 0, 0), Rgb8::new(0, 255, 255).convert());
+        assert_eq!(Cmyk8::new(0, 0, 0, 255), Rgb8::new(0, 0, 0).convert());
+        assert_eq!(Cmyk8::new(0, 0, 0, 0), Rgb8::new(255, 255, 255).convert());
+        assert_eq!(
+            Cmyk32::new(0.5, 0.0, 0.0, 0.5),
+            Rgb32::new(0.25, 0.5, 0.5).convert(),
+        );
+        assert_eq!(
+            Cmyka8::new(0, 255, 255, 0, 128),
+            Rgba8::new(255, 0, 0, 128).convert(),
+        );
+    }
+}
@@ -0,0 +1,143 @@
+// compositor.rs    Floating-point compositing accumulator.
+//
+// Copyright (c) 2024  Douglas P Lau
+//
+use crate::chan::Ch32;
+use crate::el::Pixel;
+use crate::ops::Blend;
+use crate::raster::{clip_regions, Raster, Region};
+use crate::rgb::Rgba32p;
+
+/// Accumulator for compositing many layers.
+///
+/// Layers are composited into an [Rgba32p] buffer (*linear*, *premultiplied*,
+/// 32-bit float), then converted to the final format once with [resolve].
+/// This avoids accumulating rounding errors when stacking many layers in an
+/// 8-bit format.
+///
+/// ### Example
+/// ```
+/// use pix::ops::SrcOver;
+/// use pix::rgb::{Rgba8p, SRgba8};
+/// use pix::{Compositor, Raster};
+///
+/// let mut c = Compositor::new(100, 100);
+/// let r0 = Raster::with_color(50, 50, Rgba8p::new(0x40, 0, 0, 0x40));
+/// let r1 = Raster::with_color(50, 50, Rgba8p::new(0, 0, 0x40, 0x40));
+/// c.layer((10, 10), &r0, (), SrcOver);
+/// c.layer((30, 30), &r1, (), SrcOver);
+/// let r = c.resolve::<SRgba8>();
+/// ```
+/// [resolve]: #method.resolve
+/// [Rgba32p]: rgb/type.Rgba32p.html
+pub struct Compositor {
+    buffer: Raster<Rgba32p>,
+}
+
+impl Compositor {
+    /// Create a new compositor, with all pixels transparent.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` or `height` is greater than `std::i32::MAX` or
+    /// `width` * `height` is greater than `std::i32::MAX`.
+    pub fn new(width: u32, height: u32) -> Self {
+        let buffer = Raster::with_clear(width, height);
+        Compositor { buffer }
+    }
+
+    /// Get the accumulation buffer.
+    pub fn buffer(&self) -> &Raster<Rgba32p> {
+        &self.buffer
+    }
+
+    /// Composite a layer from a source `Raster`.
+    ///
+    /// * `to` Region within accumulation buffer (destination).
+    /// * `src` Source `Raster`, in any format.
+    /// * `from` Region within source `Raster`.
+    /// * `op` Compositing operation.
+    ///
+    /// Source pixels are converted to [Rgba32p] before compositing.  Regions
+    /// are clipped the same way as [Raster::composite_raster].
+    ///
+    /// [Raster::composite_raster]: struct.Raster.html#method.composite_raster
+    /// [Rgba32p]: rgb/type.Rgba32p.html
+    pub fn layer<R0, R1, P, O>(
+        &mut self,
+        to: R0,
+        src: &Raster<P>,
+        from: R1,
+        op: O,
+    ) where
+        R0: Into<Region>,
+        R1: Into<Region>,
+        P: Pixel,
+        Ch32: From<P::Chan>,
+        O: Blend,
+    {
+        let (to, from) = clip_regions(
+            self.buffer.region(),
+            to.into(),
+            src.region(),
+            from.into(),
+        );
+        let drows = self.buffer.rows_mut(to);
+        let srows = src.rows(from);
+        for (drow, srow) in drows.zip(srows) {
+            for (d, s) in drow.iter_mut().zip(srow) {
+                d.composite_channels(&s.convert(), op);
+            }
+        }
+    }
+
+    /// Resolve the accumulated layers into a `Raster`.
+    pub fn resolve<P>(&self) -> Raster<P>
+    where
+        P: Pixel,
+        P::Chan: From<Ch32>,
+    {
+        Raster::with_raster(&self.buffer)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::el::Pixel;
+    use crate::ops::SrcOver;
+    use crate::rgb::*;
+    use crate::*;
+
+    #[test]
+    fn layers() {
+        let colors = [
+            Rgba8p::new(0x21, 0x08, 0x10, 0x31),
+            Rgba8p::new(0x05, 0x1B, 0x02, 0x23),
+            Rgba8p::new(0x11, 0x0D, 0x2A, 0x37),
+        ];
+        let mut c = Compositor::new(1, 1);
+        let mut r = Raster::<Rgba8p>::with_clear(1, 1);
+        let mut exact = [0.0_f64; 4];
+        for i in 0..30 {
+            let clr = colors[i % colors.len()];
+            let src = Raster::with_color(1, 1, clr);
+            c.layer((), &src, (), SrcOver);
+            r.composite_raster((), &src, (), SrcOver);
+            let a = f64::from(u8::from(clr.alpha())) / 255.0;
+            for (e, v) in exact.iter_mut().zip(clr.channels()) {
+                *e = f64::from(u8::from(*v)) / 255.0 + *e * (1.0 - a);
+            }
+        }
+        let err = |p: Rgba8p| {
+            p.channels()
+                .iter()
+                .zip(exact)
+                .map(|(v, e)| (f64::from(u8::from(*v)) - e * 255.0).abs())
+                .fold(0.0, f64::max)
+        };
+        let e32 = err(c.resolve::<Rgba8p>().pixel(0, 0));
+        let e8 = err(r.pixel(0, 0));
+        assert!(e32 <= 0.5, "{e32}");
+        assert!(e32 < e8, "{e32} {e8}");
+    }
+}
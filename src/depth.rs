@@ -0,0 +1,154 @@
+// depth.rs     Raster images with a depth buffer.
+//
+// Copyright (c) 2024  Douglas P Lau
+//
+use crate::chan::{Channel, Linear, Premultiplied};
+use crate::el::Pixel;
+use crate::ops::Blend;
+use crate::raster::{clip_regions, Raster, Region};
+
+/// Image with a depth (z) buffer.
+///
+/// Each pixel has a 16-bit depth value, with smaller values nearer to the
+/// viewer.  Depth is initialized to `u16::MAX` (farthest).
+///
+/// This allows sprites to be layered by depth in any order using
+/// [composite_raster_depth](#method.composite_raster_depth).
+///
+/// ### Example
+/// ```
+/// use pix::ops::SrcOver;
+/// use pix::rgb::Rgba8p;
+/// use pix::{DepthRaster, Raster};
+///
+/// let mut r = DepthRaster::<Rgba8p>::with_clear(100, 100);
+/// let near = Raster::with_color(10, 10, Rgba8p::new(0, 0, 0xFF, 0xFF));
+/// let far = Raster::with_color(10, 10, Rgba8p::new(0xFF, 0, 0, 0xFF));
+/// r.composite_raster_depth((20, 20), &near, (), 100, SrcOver);
+/// r.composite_raster_depth((25, 25), &far, (), 200, SrcOver);
+/// assert_eq!(r.raster().pixel(25, 25), Rgba8p::new(0, 0, 0xFF, 0xFF));
+/// ```
+pub struct DepthRaster<P: Pixel> {
+    raster: Raster<P>,
+    depth: Vec<u16>,
+}
+
+impl<P: Pixel> From<Raster<P>> for DepthRaster<P> {
+    /// Create a depth raster, with all depth values farthest.
+    fn from(raster: Raster<P>) -> Self {
+        let depth = vec![u16::MAX; raster.pixels().len()];
+        DepthRaster { raster, depth }
+    }
+}
+
+impl<P: Pixel> From<DepthRaster<P>> for Raster<P> {
+    /// Get the raster, discarding depth values.
+    fn from(r: DepthRaster<P>) -> Self {
+        r.raster
+    }
+}
+
+impl<P: Pixel> DepthRaster<P> {
+    /// Create a new depth raster with all pixels set to the default value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` or `height` is greater than `std::i32::MAX` or
+    /// `width` * `height` is greater than `std::i32::MAX`.
+    pub fn with_clear(width: u32, height: u32) -> Self {
+        Raster::with_clear(width, height).into()
+    }
+
+    /// Get the raster.
+    pub fn raster(&self) -> &Raster<P> {
+        &self.raster
+    }
+
+    /// Get the depth of one pixel.
+    pub fn depth(&self, x: i32, y: i32) -> u16 {
+        let width = self.raster.width() as i32;
+        assert!(x >= 0 && x < width);
+        assert!(y >= 0 && y < self.raster.height() as i32);
+        self.depth[(width * y + x) as usize]
+    }
+
+    /// Reset all depth values to farthest.
+    pub fn clear_depth(&mut self) {
+        self.depth.fill(u16::MAX);
+    }
+}
+
+impl<P> DepthRaster<P>
+where
+    P: Pixel<Alpha = Premultiplied, Gamma = Linear>,
+{
+    /// Composite from a source `Raster` at a given depth.
+    ///
+    /// * `to` Region within `self` (destination).
+    /// * `src` Source `Raster`.
+    /// * `from` Region within source `Raster`.
+    /// * `z` Depth of source pixels (smaller is nearer).
+    /// * `op` Compositing operation.
+    ///
+    /// Regions are clipped the same way as [Raster::composite_raster].  Only
+    /// source pixels which are nearer than the stored depth (and not fully
+    /// transparent) are composited, and their depth is stored.
+    ///
+    /// [Raster::composite_raster]: struct.Raster.html#method.composite_raster
+    pub fn composite_raster_depth<R0, R1, O>(
+        &mut self,
+        to: R0,
+        src: &Raster<P>,
+        from: R1,
+        z: u16,
+        op: O,
+    ) where
+        R0: Into<Region>,
+        R1: Into<Region>,
+        O: Blend,
+    {
+        let (to, from) = clip_regions(
+            self.raster.region(),
+            to.into(),
+            src.region(),
+            from.into(),
+        );
+        let width = self.raster.width() as usize;
+        let rows = self.raster.rows_mut(to).zip(src.rows(from));
+        for (j, (drow, srow)) in rows.enumerate() {
+            let start = (to.top() as usize + j) * width + to.left() as usize;
+            let zrow = &mut self.depth[start..start + drow.len()];
+            for ((d, s), dz) in drow.iter_mut().zip(srow).zip(zrow) {
+                if z < *dz && s.alpha() > P::Chan::MIN {
+                    d.composite_channels(s, op);
+                    *dz = z;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ops::SrcOver;
+    use crate::rgb::*;
+    use crate::*;
+
+    #[test]
+    fn near_wins() {
+        let near = Raster::with_color(2, 2, Rgba8p::new(0, 0, 0xFF, 0xFF));
+        let far = Raster::with_color(3, 3, Rgba8p::new(0xFF, 0, 0, 0xFF));
+        let mut r0 = DepthRaster::<Rgba8p>::with_clear(4, 4);
+        r0.composite_raster_depth((1, 1), &far, (), 200, SrcOver);
+        r0.composite_raster_depth((0, 0), &near, (), 100, SrcOver);
+        let mut r1 = DepthRaster::<Rgba8p>::with_clear(4, 4);
+        r1.composite_raster_depth((0, 0), &near, (), 100, SrcOver);
+        r1.composite_raster_depth((1, 1), &far, (), 200, SrcOver);
+        assert_eq!(r0.raster().pixels(), r1.raster().pixels());
+        assert_eq!(r0.raster().pixel(1, 1), Rgba8p::new(0, 0, 0xFF, 0xFF));
+        assert_eq!(r0.raster().pixel(2, 2), Rgba8p::new(0xFF, 0, 0, 0xFF));
+        assert_eq!(r0.depth(1, 1), 100);
+        assert_eq!(r0.depth(3, 3), 200);
+        assert_eq!(r0.depth(3, 0), u16::MAX);
+    }
+}
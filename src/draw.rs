@@ -0,0 +1,452 @@
+// draw.rs      Drawing shapes on rasters.
+//
+// Copyright (c) 2024  Douglas P Lau
+//
+use crate::chan::{Channel, Linear, Premultiplied};
+use crate::el::Pixel;
+use crate::ops::SrcOver;
+use crate::raster::{Raster, Region};
+
+/// Get distance from a point to a line segment
+fn segment_distance(p: (f32, f32), a: (f32, f32), b: (f32, f32)) -> f32 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let len2 = dx * dx + dy * dy;
+    let t = if len2 > 0.0 {
+        (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / len2).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (p.0 - (a.0 + t * dx)).hypot(p.1 - (a.1 + t * dy))
+}
+
+/// Get Cohen-Sutherland outcode of a point, for a clip box at the origin
+fn outcode(p: (f64, f64), xmax: f64, ymax: f64) -> u8 {
+    let mut code = 0;
+    if p.0 < 0.0 {
+        code |= 1;
+    } else if p.0 > xmax {
+        code |= 2;
+    }
+    if p.1 < 0.0 {
+        code |= 4;
+    } else if p.1 > ymax {
+        code |= 8;
+    }
+    code
+}
+
+/// Clip a line segment to a box from the origin to (`xmax`, `ymax`)
+///
+/// This uses the Cohen-Sutherland algorithm.  Returns `None` if the segment
+/// is entirely outside of the box.
+fn clip_line(
+    mut p0: (f64, f64),
+    mut p1: (f64, f64),
+    xmax: f64,
+    ymax: f64,
+) -> Option<((f64, f64), (f64, f64))> {
+    let mut c0 = outcode(p0, xmax, ymax);
+    let mut c1 = outcode(p1, xmax, ymax);
+    // each end point crosses at most two edges
+    for _ in 0..4 {
+        if c0 | c1 == 0 {
+            break;
+        }
+        if c0 & c1 != 0 {
+            return None;
+        }
+        let code = if c0 != 0 { c0 } else { c1 };
+        let (dx, dy) = (p1.0 - p0.0, p1.1 - p0.1);
+        let p = if code & 8 != 0 {
+            (p0.0 + dx * (ymax - p0.1) / dy, ymax)
+        } else if code & 4 != 0 {
+            (p0.0 - dx * p0.1 / dy, 0.0)
+        } else if code & 2 != 0 {
+            (xmax, p0.1 + dy * (xmax - p0.0) / dx)
+        } else {
+            (0.0, p0.1 - dy * p0.0 / dx)
+        };
+        if code == c0 {
+            p0 = p;
+            c0 = outcode(p0, xmax, ymax);
+        } else {
+            p1 = p;
+            c1 = outcode(p1, xmax, ymax);
+        }
+    }
+    (c0 & c1 == 0).then_some((p0, p1))
+}
+
+/// Check if two pixels are within a tolerance (8-bit channel distance)
+fn within_tolerance<P: Pixel>(a: P, b: P, tolerance: u8) -> bool {
+    let dist2: f32 = a
+        .channels()
+        .iter()
+        .zip(b.channels())
+        .map(|(a, b)| (a.to_f32() - b.to_f32()) * 255.0)
+        .map(|d| d * d)
+        .sum();
+    dist2 <= f32::from(tolerance) * f32::from(tolerance)
+}
+
+impl<P: Pixel> Raster<P> {
+    /// Flood fill a 4-connected area, starting from a seed pixel.
+    ///
+    /// * `x` Seed X position.
+    /// * `y` Seed Y position.
+    /// * `clr` Fill color.
+    /// * `tolerance` Maximum distance from the seed color, with channels
+    ///   scaled to 8 bits (square root of the sum of squared differences).
+    ///
+    /// If the seed is outside of the `Raster`, or is already `clr`, nothing
+    /// is changed.  An explicit stack is used, so large areas cannot
+    /// overflow the call stack.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::SRgb8;
+    /// use pix::Raster;
+    ///
+    /// let mut r = Raster::<SRgb8>::with_clear(16, 16);
+    /// r.flood_fill(8, 8, SRgb8::new(0xFF, 0, 0), 0);
+    /// assert_eq!(r.pixel(0, 15), SRgb8::new(0xFF, 0, 0));
+    /// ```
+    pub fn flood_fill(&mut self, x: i32, y: i32, clr: P, tolerance: u8) {
+        let Some(seed) = self.get_pixel(x, y) else {
+            return;
+        };
+        if seed == clr {
+            return;
+        }
+        let width = self.width() as usize;
+        let mut filled = vec![false; self.pixels().len()];
+        let mut stack = vec![(x, y)];
+        while let Some((x, y)) = stack.pop() {
+            let i = y as usize * width + x as usize;
+            let p = self.pixels()[i];
+            if filled[i] || !within_tolerance(p, seed, tolerance) {
+                continue;
+            }
+            filled[i] = true;
+            self.pixels_mut()[i] = clr;
+            for (x, y) in [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)] {
+                if self.get_pixel(x, y).is_some() {
+                    stack.push((x, y));
+                }
+            }
+        }
+    }
+
+    /// Draw a 1-pixel line, without anti-aliasing.
+    ///
+    /// * `x0` / `y0` Start position.
+    /// * `x1` / `y1` End position.
+    /// * `clr` Line color.
+    ///
+    /// Pixels are set using Bresenham's algorithm, including both end points.
+    /// The line is first clipped to the `Raster`, so only visible pixels are
+    /// visited.  For anti-aliased lines, use
+    /// [draw_polyline](#method.draw_polyline).
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::SRgb8;
+    /// use pix::Raster;
+    ///
+    /// let mut r = Raster::<SRgb8>::with_clear(16, 16);
+    /// r.draw_line(-4, 2, 20, 10, SRgb8::new(0xFF, 0xFF, 0));
+    /// assert_eq!(r.pixel(8, 6), SRgb8::new(0xFF, 0xFF, 0));
+    /// ```
+    pub fn draw_line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, clr: P) {
+        if self.pixels().is_empty() {
+            return;
+        }
+        let xmax = f64::from(self.width() - 1);
+        let ymax = f64::from(self.height() - 1);
+        let p0 = (f64::from(x0), f64::from(y0));
+        let p1 = (f64::from(x1), f64::from(y1));
+        let Some((p0, p1)) = clip_line(p0, p1, xmax, ymax) else {
+            return;
+        };
+        let (x0, y0) = (p0.0.round() as i64, p0.1.round() as i64);
+        let (x1, y1) = (p1.0.round() as i64, p1.1.round() as i64);
+        let (dx, sx) = ((x1 - x0).abs(), (x1 - x0).signum());
+        let (dy, sy) = (-(y1 - y0).abs(), (y1 - y0).signum());
+        let (mut x, mut y) = (x0, y0);
+        let mut err = dx + dy;
+        loop {
+            if let Some(p) = self.get_pixel_mut(x as i32, y as i32) {
+                *p = clr;
+            }
+            if x == x1 && y == y1 {
+                break;
+            }
+            let e2 = err * 2;
+            if e2 >= dy {
+                err += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += sy;
+            }
+        }
+    }
+
+    /// Draw a 1-pixel rectangle outline.
+    ///
+    /// * `reg` Region within `self`.
+    /// * `clr` Outline color.
+    ///
+    /// The region is clipped to the `Raster`, and only the perimeter pixels
+    /// of the clipped region are set.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::SRgb8;
+    /// use pix::Raster;
+    ///
+    /// let mut r = Raster::<SRgb8>::with_clear(16, 16);
+    /// r.draw_rect((4, 4, 8, 8), SRgb8::new(0, 0xFF, 0));
+    /// assert_eq!(r.pixel(11, 6), SRgb8::new(0, 0xFF, 0));
+    /// assert_eq!(r.pixel(10, 6), SRgb8::default());
+    /// ```
+    pub fn draw_rect<R>(&mut self, reg: R, clr: P)
+    where
+        R: Into<Region>,
+    {
+        let reg = self.intersection(reg);
+        let (x, y) = (reg.left(), reg.top());
+        let (w, h) = (reg.width(), reg.height());
+        if w > 0 && h > 0 {
+            self.copy_color((x, y, w, 1), clr);
+            self.copy_color((x, reg.bottom() - 1, w, 1), clr);
+            self.copy_color((x, y, 1, h), clr);
+            self.copy_color((reg.right() - 1, y, 1, h), clr);
+        }
+    }
+
+    /// Fill a rectangle with a color.
+    ///
+    /// * `reg` Region within `self`.
+    /// * `clr` Fill color.
+    ///
+    /// This is the same as [copy_color](#method.copy_color).  To blend the
+    /// color instead, use [composite_color](#method.composite_color).
+    pub fn fill_rect<R>(&mut self, reg: R, clr: P)
+    where
+        R: Into<Region>,
+    {
+        self.copy_color(reg, clr);
+    }
+}
+
+impl<P> Raster<P>
+where
+    P: Pixel<Alpha = Premultiplied, Gamma = Linear>,
+{
+    /// Draw an anti-aliased polyline.
+    ///
+    /// * `points` Connected points, in pixel coordinates (pixel centers are
+    ///   at 0.5 offsets).
+    /// * `width` Stroke width, in pixels.
+    /// * `clr` Stroke color.
+    ///
+    /// Segments have round joins and caps.  Coverage of each pixel is
+    /// estimated from the distance of its center to the nearest segment, and
+    /// the color is composited using [SrcOver].
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::Rgba8p;
+    /// use pix::Raster;
+    ///
+    /// let mut r = Raster::with_clear(64, 64);
+    /// let pts = [(8.0, 8.0), (56.0, 16.0), (32.0, 56.0)];
+    /// r.draw_polyline(&pts, 3.0, Rgba8p::new(0xFF, 0xFF, 0, 0xFF));
+    /// ```
+    /// [SrcOver]: ops/struct.SrcOver.html
+    pub fn draw_polyline(&mut self, points: &[(f32, f32)], width: f32, clr: P) {
+        let Some(first) = points.first() else {
+            return;
+        };
+        let half = width / 2.0;
+        let (x0, y0, x1, y1) = points.iter().fold(
+            (first.0, first.1, first.0, first.1),
+            |(x0, y0, x1, y1), p| {
+                (x0.min(p.0), y0.min(p.1), x1.max(p.0), y1.max(p.1))
+            },
+        );
+        // clip bounds in f32, so far off-canvas points cannot overflow i32
+        let (w, h) = (self.width() as f32, self.height() as f32);
+        let left = (x0 - half - 1.0).floor().clamp(0.0, w);
+        let top = (y0 - half - 1.0).floor().clamp(0.0, h);
+        let right = (x1 + half + 1.0).ceil().clamp(left, w);
+        let bottom = (y1 + half + 1.0).ceil().clamp(top, h);
+        let reg = Region::new(
+            left as i32,
+            top as i32,
+            (right - left) as u32,
+            (bottom - top) as u32,
+        );
+        if reg.width() == 0 || reg.height() == 0 {
+            return;
+        }
+        self.composite_color_coverage(
+            reg,
+            clr,
+            |x, y| {
+                let p = (x as f32 + 0.5, y as f32 + 0.5);
+                let d = if points.len() > 1 {
+                    points
+                        .windows(2)
+                        .map(|s| segment_distance(p, s[0], s[1]))
+                        .fold(f32::INFINITY, f32::min)
+                } else {
+                    segment_distance(p, *first, *first)
+                };
+                (half + 0.5 - d).clamp(0.0, 1.0)
+            },
+            SrcOver,
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::gray::*;
+    use crate::rgb::*;
+    use crate::*;
+
+    #[test]
+    fn polyline_join() {
+        let mut r = Raster::<Rgba8p>::with_clear(10, 10);
+        let clr = Rgba8p::new(0xFF, 0xFF, 0xFF, 0xFF);
+        let pts = [(2.5, 2.5), (7.5, 2.5), (7.5, 7.5)];
+        r.draw_polyline(&pts, 2.0, clr);
+        // corner and both legs are fully covered
+        assert_eq!(r.pixel(7, 2), clr);
+        assert_eq!(r.pixel(4, 2), clr);
+        assert_eq!(r.pixel(7, 5), clr);
+        // outside the stroke
+        assert_eq!(r.pixel(4, 5), Rgba8p::default());
+        assert_eq!(r.pixel(0, 0), Rgba8p::default());
+        // anti-aliased edge of round join
+        let p = r.pixel(8, 1);
+        assert!(p != clr && p != Rgba8p::default(), "{p:?}");
+    }
+
+    #[test]
+    fn polyline_off_canvas() {
+        let mut r = Raster::<Rgba8p>::with_clear(8, 4);
+        let clr = Rgba8p::new(0xFF, 0xFF, 0xFF, 0xFF);
+        r.draw_polyline(&[(0.5, 1.5), (3.0e9, 1.5)], 1.0, clr);
+        r.draw_polyline(&[(-3.0e9, -3.0e9), (-2.0e9, -3.0e9)], 1.0, clr);
+        r.draw_polyline(&[(3.0e9, 3.0e9)], 4.0, clr);
+        for x in 0..8 {
+            assert_eq!(r.pixel(x, 1), clr, "{x}");
+            assert_eq!(r.pixel(x, 3), Rgba8p::default(), "{x}");
+        }
+    }
+
+    #[test]
+    fn draw_line() {
+        let clr = SGray8::new(0xFF);
+        let mut r = Raster::<SGray8>::with_clear(5, 5);
+        r.draw_line(0, 0, 4, 4, clr);
+        // clipped at both ends
+        r.draw_line(3, 6, 3, -3, clr);
+        for y in 0..5 {
+            for x in 0..5 {
+                let v = if x == y || x == 3 {
+                    clr
+                } else {
+                    SGray8::new(0)
+                };
+                assert_eq!(r.pixel(x, y), v, "{x},{y}");
+            }
+        }
+    }
+
+    #[test]
+    fn draw_line_clipped() {
+        let clr = SGray8::new(0xFF);
+        let mut r = Raster::<SGray8>::with_clear(8, 8);
+        r.draw_line(-1_000_000_000, 5, 1_000_000_000, 5, clr);
+        r.draw_line(i32::MIN, i32::MIN, i32::MAX, i32::MAX, clr);
+        // entirely outside
+        r.draw_line(-10, 20, 20, 50, clr);
+        r.draw_line(i32::MIN, -1, i32::MAX, -1, clr);
+        for y in 0..8 {
+            for x in 0..8 {
+                let v = if y == 5 || x == y {
+                    clr
+                } else {
+                    SGray8::new(0)
+                };
+                assert_eq!(r.pixel(x, y), v, "{x},{y}");
+            }
+        }
+        let mut r = Raster::<SGray8>::with_clear(0, 0);
+        r.draw_line(0, 0, 4, 4, clr);
+    }
+
+    #[test]
+    fn draw_rect() {
+        let clr = SGray8::new(0xFF);
+        let mut r = Raster::<SGray8>::with_color(6, 6, SGray8::new(0x40));
+        r.draw_rect((1, 1, 4, 4), clr);
+        for y in 0..6 {
+            for x in 0..6 {
+                let v = match (x, y) {
+                    (2..=3, 2..=3) => SGray8::new(0x40),
+                    (1..=4, 1..=4) => clr,
+                    _ => SGray8::new(0x40),
+                };
+                assert_eq!(r.pixel(x, y), v, "{x},{y}");
+            }
+        }
+        // partially off-screen
+        let mut r = Raster::<SGray8>::with_clear(4, 4);
+        r.draw_rect((-2, 1, 5, 9), clr);
+        for y in 0..4 {
+            for x in 0..4 {
+                let set = (x == 0 || x == 2 || y == 1 || y == 3) && x < 3;
+                let v = if set && y > 0 { clr } else { SGray8::new(0) };
+                assert_eq!(r.pixel(x, y), v, "{x},{y}");
+            }
+        }
+        r.fill_rect((1, -1, 2, 3), clr);
+        assert_eq!(r.pixel(1, 0), clr);
+        assert_eq!(r.pixel(1, 2), SGray8::new(0));
+    }
+
+    #[test]
+    fn flood_fill() {
+        let border = SRgb8::new(0, 0, 0xFF);
+        let clr = SRgb8::new(0xFF, 0, 0);
+        let mut r = Raster::with_color(8, 8, SRgb8::new(0x80, 0x80, 0x80));
+        r.copy_color((1, 1, 6, 6), border);
+        r.copy_color((2, 2, 4, 4), SRgb8::new(0x40, 0x40, 0x40));
+        *r.pixel_mut(3, 3) = SRgb8::new(0x44, 0x40, 0x3C);
+        let r0 = r.clone();
+        r.flood_fill(-1, 3, clr, 255);
+        assert_eq!(r.pixels(), r0.pixels());
+        r.flood_fill(2, 2, clr, 5);
+        for (i, (p, p0)) in r.pixels().iter().zip(r0.pixels()).enumerate() {
+            let (x, y) = (i % 8, i / 8);
+            if (2..6).contains(&x) && (2..6).contains(&y) && (x, y) != (3, 3) {
+                assert_eq!(*p, clr);
+            } else {
+                assert_eq!(p, p0, "{x},{y}");
+            }
+        }
+        // filling with the same color does nothing
+        r.flood_fill(3, 2, clr, 255);
+        assert_eq!(r.pixel(3, 3), SRgb8::new(0x44, 0x40, 0x3C));
+        let mut r = r0.clone();
+        r.flood_fill(2, 2, clr, 6);
+        assert_eq!(r.pixel(3, 3), clr);
+        assert_eq!(r.pixel(1, 1), border);
+    }
+}
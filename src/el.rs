@@ -4,7 +4,7 @@
 // Copyright (c) 2019-2020  Jeron Aldaron Lau
 //
 //! Module for `pix::el` items
-use crate::chan::{Alpha, Channel, Gamma, Linear, Premultiplied};
+use crate::chan::{Alpha, Ch32, Channel, Gamma, Linear, Premultiplied};
 use crate::matte::Matte;
 use crate::ops::Blend;
 use crate::private::Sealed;
@@ -27,8 +27,8 @@ use std::marker::PhantomData;
 /// ### Type Alias Naming Scheme
 ///
 /// * _Gamma_: `S` for [sRGB] gamma encoding; [linear] if omitted.
-/// * _Color model_: [`Rgb`] / [`Bgr`] / [`Gray`] / [`Cmy`] / [`Hsv`] /
-///                  [`Hsl`] / [`Hwb`] / [`YCbCr`] / [`Matte`].
+/// * _Color model_: [`Rgb`] / [`Bgr`] / [`Gray`] / [`Cmy`] / [`Cmyk`] /
+///   [`Hsv`] / [`Hsl`] / [`Hwb`] / [`YCbCr`] / [`Matte`].
 /// * _Alpha_: `a` to include alpha channel enabling translucent pixels.
 /// * _Bit depth_: `8` / `16` / `32` for 8-bit integer, 16-bit integer and
 ///   32-bit floating-point [channels].
@@ -37,6 +37,7 @@ use std::marker::PhantomData;
 /// [`bgr`]: ../bgr/struct.Bgr.html
 /// [channels]: ../chan/trait.Channel.html
 /// [`cmy`]: ../cmy/struct.Cmy.html
+/// [`cmyk`]: ../cmyk/struct.Cmyk.html
 /// [`gray`]: ../gray/struct.Gray.html
 /// [`hsl`]: ../hsl/struct.Hsl.html
 /// [`hsv`]: ../hsv/struct.Hsv.html
@@ -118,6 +119,16 @@ pub trait Pixel: Clone + Copy + Debug + Default + PartialEq + Sealed {
         &mut self.channels_mut()[3]
     }
 
+    /// Get the fifth channel.
+    fn five(self) -> Self::Chan {
+        *self.channels().get(4).unwrap_or(&Self::Chan::MAX)
+    }
+
+    /// Get a mutable reference to the fifth channel
+    fn five_mut(&mut self) -> &mut Self::Chan {
+        &mut self.channels_mut()[4]
+    }
+
     /// Get the *alpha* channel.
     ///
     /// # Example: Get Alpha
@@ -213,6 +224,10 @@ pub trait Pixel: Clone + Copy + Debug + Default + PartialEq + Sealed {
         Self: Pixel<Alpha = Premultiplied, Gamma = Linear>,
         O: Blend,
     {
+        #[cfg(feature = "simd")]
+        if crate::simd::composite_slice(dst, src, op) {
+            return;
+        }
         for (d, s) in dst.iter_mut().zip(src) {
             d.composite_channels(s, op);
         }
@@ -224,23 +239,7 @@ pub trait Pixel: Clone + Copy + Debug + Default + PartialEq + Sealed {
         Self: Pixel<Alpha = Premultiplied, Gamma = Linear>,
         O: Blend,
     {
-        let da1 = Self::Chan::MAX - self.alpha();
-        let sa1 = Self::Chan::MAX - src.alpha();
-        // circular channels
-        let d_chan = &mut self.channels_mut()[Self::Model::CIRCULAR];
-        let s_chan = &src.channels()[Self::Model::CIRCULAR];
-        d_chan
-            .iter_mut()
-            .zip(s_chan)
-            .for_each(|(d, s)| circ_composite(d, da1, *s, sa1, op));
-        // linear channels
-        let d_chan = &mut self.channels_mut()[Self::Model::LINEAR];
-        let s_chan = &src.channels()[Self::Model::LINEAR];
-        d_chan
-            .iter_mut()
-            .zip(s_chan)
-            .for_each(|(d, s)| O::composite(d, da1, s, sa1));
-        O::composite(self.alpha_mut(), da1, &src.alpha(), sa1);
+        composite_premultiplied(self, src, op);
     }
 
     /// Composite the channels of two pixels with alpha
@@ -269,10 +268,38 @@ pub trait Pixel: Clone + Copy + Debug + Default + PartialEq + Sealed {
             .iter_mut()
             .zip(s_chan)
             .for_each(|(d, s)| O::composite(d, da1, &(*s * *alpha), sa1));
-        O::composite(self.alpha_mut(), da1, &(src.alpha() * *alpha), sa1);
+        O::composite_alpha(self.alpha_mut(), da1, &(src.alpha() * *alpha), sa1);
     }
 }
 
+/// Composite the channels of two pixels, which must be premultiplied
+///
+/// The pixel type is not required to be *premultiplied*, so that *straight*
+/// pixels can be premultiplied in place before compositing.
+pub(crate) fn composite_premultiplied<P, O>(dst: &mut P, src: &P, op: O)
+where
+    P: Pixel,
+    O: Blend,
+{
+    let da1 = P::Chan::MAX - dst.alpha();
+    let sa1 = P::Chan::MAX - src.alpha();
+    // circular channels
+    let d_chan = &mut dst.channels_mut()[P::Model::CIRCULAR];
+    let s_chan = &src.channels()[P::Model::CIRCULAR];
+    d_chan
+        .iter_mut()
+        .zip(s_chan)
+        .for_each(|(d, s)| circ_composite(d, da1, *s, sa1, op));
+    // linear channels
+    let d_chan = &mut dst.channels_mut()[P::Model::LINEAR];
+    let s_chan = &src.channels()[P::Model::LINEAR];
+    d_chan
+        .iter_mut()
+        .zip(s_chan)
+        .for_each(|(d, s)| O::composite(d, da1, s, sa1));
+    O::composite_alpha(dst.alpha_mut(), da1, &src.alpha(), sa1);
+}
+
 /// Calculate composite for a circular channel
 #[inline]
 fn circ_composite<C, O>(d: &mut C, da1: C, mut s: C, sa1: C, _op: O)
@@ -311,6 +338,30 @@ where
 pub type PixRgba<P> =
     Pix4<<P as Pixel>::Chan, Rgb, <P as Pixel>::Alpha, <P as Pixel>::Gamma>;
 
+/// Convert a pixel to *straight* alpha, *linear* gamma RGBA values.
+pub(crate) fn to_linear_rgba<P: Pixel>(p: P) -> [f32; 4] {
+    let rgba = P::Model::into_rgba::<P>(p);
+    let chan = rgba.channels();
+    let alpha = Ch32::new(chan[3].to_f32());
+    let mut v = [0.0, 0.0, 0.0, alpha.to_f32()];
+    for (v, c) in v.iter_mut().zip(&chan[..3]) {
+        let c = Ch32::new(P::Gamma::to_linear_f32(*c));
+        *v = P::Alpha::decode(c, alpha).to_f32();
+    }
+    v
+}
+
+/// Convert *straight* alpha, *linear* gamma RGBA values to a pixel.
+pub(crate) fn from_linear_rgba<P: Pixel>(rgba: [f32; 4]) -> P {
+    let alpha = Ch32::new(rgba[3]);
+    let mut chan = [P::Chan::from(alpha.to_f32()); 4];
+    for (c, v) in chan.iter_mut().zip(&rgba[..3]) {
+        let v = P::Alpha::encode(Ch32::new(*v), alpha).to_f32();
+        *c = P::Gamma::from_linear_f32(v);
+    }
+    P::Model::from_rgba::<P>(PixRgba::<P>::from_channels(&chan))
+}
+
 /// Convert a pixel to another format with the same color model.
 ///
 /// * `D` Destination pixel format.
@@ -726,13 +777,276 @@ where
     }
 }
 
+/// [Pixel] with five [channel]s in its [color model].
+///
+/// [channel]: ../chan/trait.Channel.html
+/// [color model]: ../trait.ColorModel.html
+/// [pixel]: trait.Pixel.html
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[repr(C)]
+pub struct Pix5<C, M, A, G>
+where
+    C: Channel,
+    M: ColorModel,
+    A: Alpha,
+    G: Gamma,
+{
+    channels: [C; 5],
+    _model: PhantomData<M>,
+    _alpha: PhantomData<A>,
+    _gamma: PhantomData<G>,
+}
+
+impl<C, M, A, G> Pix5<C, M, A, G>
+where
+    C: Channel,
+    M: ColorModel,
+    A: Alpha,
+    G: Gamma,
+{
+    /// Create a five-channel color.
+    ///
+    /// ## Example
+    /// ```
+    /// use pix::cmyk::Cmyka8;
+    ///
+    /// let cmyka = Cmyka8::new(128, 200, 255, 64, 128);
+    /// ```
+    pub fn new<H>(one: H, two: H, three: H, four: H, five: H) -> Self
+    where
+        C: From<H>,
+    {
+        let one = C::from(one);
+        let two = C::from(two);
+        let three = C::from(three);
+        let four = C::from(four);
+        let five = C::from(five);
+        let channels = [one, two, three, four, five];
+        Pix5 {
+            channels,
+            _model: PhantomData,
+            _alpha: PhantomData,
+            _gamma: PhantomData,
+        }
+    }
+}
+
+impl<C, M, A, G> Pixel for Pix5<C, M, A, G>
+where
+    C: Channel,
+    M: ColorModel,
+    A: Alpha,
+    G: Gamma,
+{
+    type Chan = C;
+    type Model = M;
+    type Alpha = A;
+    type Gamma = G;
+
+    fn from_channels(ch: &[C]) -> Self {
+        let one = ch[0];
+        let two = ch[1];
+        let three = ch[2];
+        let four = ch[3];
+        let five = ch[4];
+        Self::new::<C>(one, two, three, four, five)
+    }
+
+    fn from_bit_depth<P>(p: P) -> Self
+    where
+        P: Pixel,
+        Self::Chan: From<P::Chan>,
+    {
+        debug_assert_eq!(TypeId::of::<Self::Model>(), TypeId::of::<P::Model>());
+        let one = Self::Chan::from(p.one());
+        let two = Self::Chan::from(p.two());
+        let three = Self::Chan::from(p.three());
+        let four = Self::Chan::from(p.four());
+        let five = Self::Chan::from(p.five());
+        Self::new::<Self::Chan>(one, two, three, four, five)
+    }
+
+    fn channels(&self) -> &[Self::Chan] {
+        &self.channels
+    }
+
+    fn channels_mut(&mut self) -> &mut [Self::Chan] {
+        &mut self.channels
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::el::*;
     use crate::gray::*;
+    use crate::hwb::*;
     use crate::matte::*;
     use crate::rgb::*;
 
+    /// Check that converting `src` results in exactly `expected`
+    fn check<S, D>(src: S, expected: D)
+    where
+        S: Pixel,
+        D: Pixel,
+        D::Chan: From<S::Chan>,
+    {
+        let got = src.convert::<D>();
+        assert_eq!(got, expected, "{src:?} -> {}", std::any::type_name::<D>());
+    }
+
+    #[test]
+    fn conversion_bytes() {
+        let p = SRgba8::new(0xFF, 0x80, 0x00, 0x80);
+        assert_eq!(p.convert::<Rgba8p>(), Rgba8p::new(0x80, 0x1B, 0x00, 0x80));
+        assert_eq!(p.convert::<SRgba8p>(), SRgba8p::new(0xBC, 0x5C, 0, 0x80));
+        assert_eq!(p.convert::<SGraya8>(), SGraya8::new(0x92, 0x80));
+        assert_eq!(p.convert::<Graya8p>(), Graya8p::new(0x2F, 0x80));
+        assert_eq!(p.convert::<SHwba8>(), SHwba8::new(0x15, 0, 0, 0x80));
+        assert_eq!(
+            p.convert::<Hwba16p>(),
+            Hwba16p::new(0x0935, 0, 0x7F7F, 0x8080)
+        );
+    }
+
+    #[test]
+    fn conversion_table() {
+        let src = SRgba16::new(0x1234, 0x9ABC, 0xDEF0, 0xC000);
+        check(src, Rgb8::new(0x02, 0x52, 0xBA));
+        check(src, Rgb16::new(0x0190, 0x52E1, 0xBB21));
+        check(src, Rgb32::new(0.006108976, 0.32375425, 0.73098123));
+        check(src, Rgba8::new(0x02, 0x52, 0xBA, 0xC0));
+        check(src, Rgba16::new(0x0190, 0x52E1, 0xBB21, 0xC000));
+        check(
+            src,
+            Rgba32::new(0.006108976, 0.32375425, 0.73098123, 0.75001144),
+        );
+        check(src, Rgba8p::new(0x01, 0x3D, 0x8C, 0xC0));
+        check(src, Rgba16p::new(0x012C, 0x3E29, 0x8C59, 0xC000));
+        check(
+            src,
+            Rgba32p::new(0.004581802, 0.2428194, 0.5482443, 0.75001144),
+        );
+        check(src, SRgb8::new(0x12, 0x9A, 0xDE));
+        check(src, SRgb16::new(0x1234, 0x9ABC, 0xDEF0));
+        check(src, SRgb32::new(0.071107045, 0.6044404, 0.8708629));
+        check(src, SRgba8::new(0x12, 0x9A, 0xDE, 0xC0));
+        check(src, SRgba16::new(0x1234, 0x9ABC, 0xDEF0, 0xC000));
+        check(
+            src,
+            SRgba32::new(0.071107045, 0.6044404, 0.8708629, 0.75001144),
+        );
+        check(src, SRgba8p::new(0x0D, 0x86, 0xC4, 0xC0));
+        check(src, SRgba16p::new(0x0E8C, 0x87AA, 0xC42A, 0xC000));
+        check(
+            src,
+            SRgba32p::new(0.056862436, 0.52995235, 0.7662808, 0.75001144),
+        );
+        check(src, Gray8::new(0x49));
+        check(src, Gray16::new(0x491E));
+        check(src, Gray32::new(0.28562465));
+        check(src, Graya8::new(0x49, 0xC0));
+        check(src, Graya16::new(0x491E, 0xC000));
+        check(src, Graya32::new(0.28562465, 0.75001144));
+        check(src, Graya8p::new(0x36, 0xC0));
+        check(src, Graya16p::new(0x36D7, 0xC000));
+        check(src, Graya32p::new(0.21422176, 0.75001144));
+        check(src, SGray8::new(0x82));
+        check(src, SGray16::new(0x82A2));
+        check(src, SGray32::new(0.51028943));
+        check(src, SGraya8::new(0x82, 0xC0));
+        check(src, SGraya16::new(0x82A2, 0xC000));
+        check(src, SGraya32::new(0.51028943, 0.75001144));
+        check(src, SGraya8p::new(0x71, 0xC0));
+        check(src, SGraya16p::new(0x7248, 0xC000));
+        check(src, SGraya32p::new(0.44643635, 0.75001144));
+        check(src, Hwb8::new(0x98, 0x01, 0x45));
+        check(src, Hwb16::new(0x97F8, 0x018F, 0x44DE));
+        check(src, Hwb32::new(0.5936319, 0.0061090197, 0.26901877));
+        check(src, Hwba8::new(0x98, 0x01, 0x45, 0xC0));
+        check(src, Hwba16::new(0x97F8, 0x018F, 0x44DE, 0xC000));
+        check(
+            src,
+            Hwba32::new(0.5936319, 0.0061090197, 0.26901877, 0.75001144),
+        );
+        check(src, Hwba8p::new(0x98, 0x00, 0x73, 0xC0));
+        check(src, Hwba16p::new(0x97F8, 0x012B, 0x73A6, 0xC000));
+        check(
+            src,
+            Hwba32p::new(0.5936319, 0.004581802, 0.4517557, 0.75001144),
+        );
+        check(src, SHwb8::new(0x8E, 0x11, 0x21));
+        check(src, SHwb16::new(0x8E36, 0x1234, 0x210F));
+        check(src, SHwb32::new(0.55552167, 0.07110701, 0.1291371));
+        check(src, SHwba8::new(0x8E, 0x11, 0x21, 0xC0));
+        check(src, SHwba16::new(0x8E36, 0x1234, 0x210F, 0xC000));
+        check(
+            src,
+            SHwba32::new(0.55552167, 0.07110701, 0.1291371, 0.75001144),
+        );
+        check(src, SHwba8p::new(0x8E, 0x0C, 0x3B, 0xC0));
+        check(src, SHwba16p::new(0x8E36, 0x0E8B, 0x3BD5, 0xC000));
+        check(
+            src,
+            SHwba32p::new(0.55552167, 0.0568624, 0.23371923, 0.75001144),
+        );
+
+        let src = Rgba32p::new(0.2, 0.45, 0.6, 0.75);
+        check(src, Rgb8::new(0x33, 0x73, 0x99));
+        check(src, Rgb16::new(0x3333, 0x7333, 0x9999));
+        check(src, Rgb32::new(0.2, 0.45, 0.6));
+        check(src, Rgba8::new(0x44, 0x9A, 0xCD, 0xBF));
+        check(src, Rgba16::new(0x4444, 0x999A, 0xCCCD, 0xBFFF));
+        check(src, Rgba32::new(0.26666668, 0.59999996, 0.8, 0.75));
+        check(src, Rgba8p::new(0x33, 0x73, 0x99, 0xBF));
+        check(src, Rgba16p::new(0x3333, 0x7333, 0x9999, 0xBFFF));
+        check(src, Rgba32p::new(0.2, 0.45, 0.6, 0.75));
+        check(src, SRgb8::new(0x7C, 0xB3, 0xCB));
+        check(src, SRgb16::new(0x7C0A, 0xB38F, 0xCC38));
+        check(src, SRgb32::new(0.4845292, 0.7014107, 0.7977377));
+        check(src, SRgba8::new(0x8D, 0xCC, 0xE8, 0xBF));
+        check(src, SRgba16::new(0x8DA0, 0xCC38, 0xE805, 0xBFFF));
+        check(src, SRgba32::new(0.5532369, 0.79773766, 0.9063317, 0.75));
+        check(src, SRgba8p::new(0x7C, 0xB3, 0xCB, 0xBF));
+        check(src, SRgba16p::new(0x7C0A, 0xB38F, 0xCC38, 0xBFFF));
+        check(src, SRgba32p::new(0.4845292, 0.7014107, 0.7977377, 0.75));
+        check(src, Gray8::new(0x8B));
+        check(src, Gray16::new(0x8B28));
+        check(src, Gray32::new(0.5435733));
+        check(src, Graya8::new(0x8B, 0xBF));
+        check(src, Graya16::new(0x8B28, 0xBFFF));
+        check(src, Graya32::new(0.5435733, 0.75));
+        check(src, Graya8p::new(0x68, 0xBF));
+        check(src, Graya16p::new(0x685D, 0xBFFF));
+        check(src, Graya32p::new(0.40767998, 0.75));
+        check(src, SGray8::new(0xC1));
+        check(src, SGray16::new(0xC0EB));
+        check(src, SGray32::new(0.75359726));
+        check(src, SGraya8::new(0xC1, 0xBF));
+        check(src, SGraya16::new(0xC0EB, 0xBFFF));
+        check(src, SGraya32::new(0.75359726, 0.75));
+        check(src, SGraya8p::new(0xA9, 0xBF));
+        check(src, SGraya16p::new(0xA989, 0xBFFF));
+        check(src, SGraya32p::new(0.66225654, 0.75));
+        check(src, Hwb8::new(0x8F, 0x43, 0x32));
+        check(src, Hwb16::new(0x8FFF, 0x4443, 0x3332));
+        check(src, Hwb32::new(0.5625, 0.2666667, 0.19999999));
+        check(src, Hwba8::new(0x8F, 0x43, 0x32, 0xBF));
+        check(src, Hwba16::new(0x8FFF, 0x4443, 0x3332, 0xBFFF));
+        check(src, Hwba32::new(0.5625, 0.2666667, 0.19999999, 0.75));
+        check(src, Hwba8p::new(0x8F, 0x33, 0x66, 0xBF));
+        check(src, Hwba16p::new(0x8FFF, 0x3333, 0x6666, 0xBFFF));
+        check(src, Hwba32p::new(0.5625, 0.2, 0.39999998, 0.75));
+        check(src, SHwb8::new(0x8D, 0x8D, 0x17));
+        check(src, SHwb16::new(0x8D1F, 0x8DA0, 0x17FA));
+        check(src, SHwb32::new(0.5512582, 0.5532369, 0.09366828));
+        check(src, SHwba8::new(0x8D, 0x8D, 0x17, 0xBF));
+        check(src, SHwba16::new(0x8D1F, 0x8DA0, 0x17FA, 0xBFFF));
+        check(src, SHwba32::new(0.5512582, 0.5532369, 0.09366828, 0.75));
+        check(src, SHwba8p::new(0x8C, 0x7C, 0x34, 0xBF));
+        check(src, SHwba16p::new(0x8D1F, 0x7C0A, 0x33C7, 0xBFFF));
+        check(src, SHwba32p::new(0.55125815, 0.4845292, 0.20226228, 0.75));
+    }
+
     #[test]
     fn check_sizes() {
         assert_eq!(std::mem::size_of::<Matte8>(), 1);
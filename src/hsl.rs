@@ -340,4 +340,36 @@ mod test {
             Rgb8::new(255, 0, 255).convert(),
         );
     }
+
+    #[test]
+    fn hsl_round_trip() {
+        let colors = [
+            Rgb32::new(1.0, 0.0, 0.0),
+            Rgb32::new(0.5, 0.5, 0.5),
+            Rgb32::new(0.0, 0.0, 1.0),
+        ];
+        for clr in colors {
+            let hsl: Hsl32 = clr.convert();
+            let rgb: Rgb32 = hsl.convert();
+            assert_eq!(rgb, clr);
+        }
+    }
+
+    #[test]
+    fn rgb_to_hsl_grays() {
+        assert_eq!(
+            Hsl32::new(0.0, 0.0, 0.5),
+            Rgb32::new(0.5, 0.5, 0.5).convert()
+        );
+        assert_eq!(
+            Hsl32::new(0.0, 0.0, 0.0),
+            Rgb32::new(0.0, 0.0, 0.0).convert()
+        );
+        assert_eq!(
+            Hsl32::new(0.0, 0.0, 1.0),
+            Rgb32::new(1.0, 1.0, 1.0).convert()
+        );
+        assert_eq!(Hsl8::new(0, 0, 0), Rgb8::new(0, 0, 0).convert());
+        assert_eq!(Hsl8::new(0, 0, 255), Rgb8::new(255, 255, 255).convert());
+    }
 }
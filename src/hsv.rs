@@ -427,6 +427,39 @@ mod test {
         );
     }
 
+    #[test]
+    fn rgb_to_hsv_grays() {
+        assert_eq!(Hsv8::new(0, 0, 0), Rgb8::new(0, 0, 0).convert());
+        assert_eq!(Hsv16::new(0, 0, 0), Rgb16::new(0, 0, 0).convert());
+        assert_eq!(
+            Hsv32::new(0.0, 0.0, 0.0),
+            Rgb32::new(0.0, 0.0, 0.0).convert()
+        );
+        assert_eq!(Hsv8::new(0, 0, 128), Rgb8::new(128, 128, 128).convert());
+        assert_eq!(
+            Hsv32::new(0.0, 0.0, 0.5),
+            Rgb32::new(0.5, 0.5, 0.5).convert()
+        );
+        assert_eq!(Hsv8::new(0, 0, 255), Rgb8::new(255, 255, 255).convert());
+        let black: Hsv32 = Rgb32::new(0.0, 0.0, 0.0).convert();
+        assert!(black.channels().iter().all(|c| !c.to_f32().is_nan()));
+    }
+
+    #[test]
+    fn hsv_round_trip() {
+        let colors = [
+            Rgb8::new(255, 0, 0),
+            Rgb8::new(0, 0, 0),
+            Rgb8::new(128, 128, 128),
+            Rgb8::new(0, 0, 255),
+            Rgb8::new(255, 255, 255),
+        ];
+        for clr in colors {
+            let hsv: Hsv8 = clr.convert();
+            assert_eq!(clr, hsv.convert());
+        }
+    }
+
     #[test]
     fn composite_hsv() {
         let mut a = Hsva8p::new(0, 64, 64, 128);
@@ -0,0 +1,100 @@
+// image_buf.rs     image crate interoperability (optional feature).
+//
+// Copyright (c) 2024  Douglas P Lau
+//
+use crate::chan::Ch8;
+use crate::el::Pixel;
+use crate::raster::Raster;
+use crate::rgb::SRgba8;
+use image::RgbaImage;
+
+impl Raster<SRgba8> {
+    /// Construct a `Raster` from an `image` crate [RgbaImage].
+    ///
+    /// The image data is treated as *sRGB* gamma with *straight* alpha,
+    /// which matches [SRgba8].  Bytes are copied directly, with no
+    /// conversion.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the image width or height is greater than `std::i32::MAX`.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::{Rgba8p, SRgba8};
+    /// use pix::Raster;
+    ///
+    /// let img = image::RgbaImage::new(32, 32);
+    /// let r = Raster::from_image_rgba8(&img);
+    /// let r = Raster::<Rgba8p>::with_raster(&r);
+    /// ```
+    /// [RgbaImage]: https://docs.rs/image/latest/image/type.RgbaImage.html
+    /// [SRgba8]: rgb/type.SRgba8.html
+    pub fn from_image_rgba8(img: &RgbaImage) -> Self {
+        let (width, height) = img.dimensions();
+        Raster::with_u8_buffer(width, height, img.as_raw().as_slice())
+    }
+}
+
+impl<P: Pixel> Raster<P> {
+    /// Convert to an `image` crate [RgbaImage].
+    ///
+    /// Pixels are first converted to [SRgba8] (*sRGB* gamma, *straight*
+    /// alpha), which is how the `image` crate interprets its data.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::Rgba8p;
+    /// use pix::Raster;
+    ///
+    /// let r = Raster::<Rgba8p>::with_clear(32, 32);
+    /// let img = r.to_image_rgba8();
+    /// assert_eq!(img.dimensions(), (32, 32));
+    /// ```
+    /// [RgbaImage]: https://docs.rs/image/latest/image/type.RgbaImage.html
+    /// [SRgba8]: rgb/type.SRgba8.html
+    pub fn to_image_rgba8(&self) -> RgbaImage
+    where
+        Ch8: From<P::Chan>,
+    {
+        let r = self.to_srgba8();
+        let buf = r.as_u8_slice().to_vec();
+        RgbaImage::from_raw(self.width(), self.height(), buf)
+            .expect("buffer size matches dimensions")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rgb::*;
+    use crate::*;
+    use image::{Rgba, RgbaImage};
+
+    #[test]
+    fn from_image() {
+        let img = RgbaImage::from_fn(4, 3, |x, y| {
+            Rgba([x as u8 * 64, y as u8 * 100, 0x80, 0xFF - x as u8])
+        });
+        let r = Raster::from_image_rgba8(&img);
+        assert_eq!((r.width(), r.height()), (4, 3));
+        assert_eq!(r.pixel(3, 2), SRgba8::new(192, 200, 0x80, 0xFC));
+        let img2 = r.to_image_rgba8();
+        assert_eq!(img2, img);
+    }
+
+    #[test]
+    fn to_image() {
+        let r = Raster::<SRgba8>::with_row_fn(5, 2, |y, row| {
+            for (x, p) in row.iter_mut().enumerate() {
+                *p = SRgba8::new(x as u8 * 50, y as u8, 0xFF, 0x40);
+            }
+        });
+        let img = r.to_image_rgba8();
+        assert_eq!(img.get_pixel(4, 1), &Rgba([200, 1, 0xFF, 0x40]));
+        let r2 = Raster::from_image_rgba8(&img);
+        assert_eq!(r2.pixels(), r.pixels());
+        let r = Raster::with_color(2, 2, Rgba8p::new(0x80, 0, 0, 0x80));
+        let img = r.to_image_rgba8();
+        assert_eq!(img.get_pixel(1, 1), &Rgba([0xFF, 0, 0, 0x80]));
+    }
+}
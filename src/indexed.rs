@@ -0,0 +1,161 @@
+// indexed.rs   Indexed raster images.
+//
+// Copyright (c) 2024  Douglas P Lau
+//
+use crate::el::Pixel;
+use crate::gray::{Gray, Gray16, Gray8};
+use crate::palette::Palette;
+use crate::raster::Raster;
+use crate::rgb::SRgb8;
+
+/// Image with pixels which are indices into a [Palette].
+///
+/// Indices are stored as [Gray8] (up to 256 colors) or [Gray16] (up to
+/// 65536 colors) pixels.  Colors are resolved through the palette with
+/// [to_raster](#method.to_raster).
+///
+/// ### Example
+/// ```
+/// use pix::rgb::SRgb8;
+/// use pix::{Dither, IndexedRaster, Palette, Raster};
+///
+/// let r = Raster::with_color(16, 16, SRgb8::new(0x40, 0x80, 0xC0));
+/// let palette = Palette::from_raster(&r, 256);
+/// let indexed = r.quantize(&palette, Dither::None);
+/// let indexed = IndexedRaster::new(indexed, palette);
+/// assert_eq!(indexed.to_raster().pixel(8, 8), SRgb8::new(0x40, 0x80, 0xC0));
+/// ```
+/// [Gray8]: gray/type.Gray8.html
+/// [Gray16]: gray/type.Gray16.html
+/// [Palette]: struct.Palette.html
+pub struct IndexedRaster<P: Pixel<Model = Gray>> {
+    raster: Raster<P>,
+    palette: Palette,
+}
+
+impl<P: Pixel<Model = Gray>> IndexedRaster<P> {
+    /// Create an indexed raster from a raster of indices and a palette.
+    pub fn new(raster: Raster<P>, palette: Palette) -> Self {
+        IndexedRaster { raster, palette }
+    }
+
+    /// Get the raster of indices.
+    pub fn raster(&self) -> &Raster<P> {
+        &self.raster
+    }
+
+    /// Get the palette.
+    pub fn palette(&self) -> &Palette {
+        &self.palette
+    }
+
+    /// Lookup a palette color, or black if not in the palette
+    fn lookup(&self, i: usize) -> SRgb8 {
+        self.palette.entry(i).unwrap_or_default()
+    }
+
+    /// Resolve all indices to colors
+    fn expand(&self, index: fn(P) -> usize) -> Raster<SRgb8> {
+        let mut r =
+            Raster::with_clear(self.raster.width(), self.raster.height());
+        for (d, s) in r.pixels_mut().iter_mut().zip(self.raster.pixels()) {
+            *d = self.lookup(index(*s));
+        }
+        r
+    }
+}
+
+impl IndexedRaster<Gray8> {
+    /// Get the color of one pixel.
+    ///
+    /// Indices which are not in the palette resolve to black.
+    pub fn color(&self, x: i32, y: i32) -> SRgb8 {
+        self.lookup(index8(self.raster.pixel(x, y)))
+    }
+
+    /// Resolve all indices to colors, using the palette.
+    ///
+    /// Indices which are not in the palette resolve to black.
+    pub fn to_raster(&self) -> Raster<SRgb8> {
+        self.expand(index8)
+    }
+}
+
+impl IndexedRaster<Gray16> {
+    /// Get the color of one pixel.
+    ///
+    /// Indices which are not in the palette resolve to black.
+    pub fn color(&self, x: i32, y: i32) -> SRgb8 {
+        self.lookup(index16(self.raster.pixel(x, y)))
+    }
+
+    /// Resolve all indices to colors, using the palette.
+    ///
+    /// Indices which are not in the palette resolve to black.
+    pub fn to_raster(&self) -> Raster<SRgb8> {
+        self.expand(index16)
+    }
+}
+
+/// Get an 8-bit palette index
+fn index8(p: Gray8) -> usize {
+    u8::from(Gray::value(p)).into()
+}
+
+/// Get a 16-bit palette index
+fn index16(p: Gray16) -> usize {
+    u16::from(Gray::value(p)).into()
+}
+
+impl<P: Pixel<Model = Gray>> From<IndexedRaster<P>> for (Raster<P>, Palette) {
+    /// Get the raster of indices and the palette.
+    fn from(r: IndexedRaster<P>) -> Self {
+        (r.raster, r.palette)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::gray::*;
+    use crate::rgb::*;
+    use crate::*;
+
+    #[test]
+    fn expand() {
+        let mut palette = Palette::new(4);
+        palette.set_entry(SRgb8::new(0xFF, 0, 0));
+        palette.set_entry(SRgb8::new(0, 0xFF, 0));
+        palette.set_entry(SRgb8::new(0, 0, 0xFF));
+        let r = Raster::<Gray16>::with_row_fn(3, 2, |y, row| {
+            for (x, p) in row.iter_mut().enumerate() {
+                *p = Gray16::new((x as u16 + y as u16) % 4);
+            }
+        });
+        let indexed = IndexedRaster::new(r, palette);
+        let r = indexed.to_raster();
+        assert_eq!(r.pixel(0, 0), SRgb8::new(0xFF, 0, 0));
+        assert_eq!(r.pixel(1, 0), SRgb8::new(0, 0xFF, 0));
+        assert_eq!(r.pixel(2, 0), SRgb8::new(0, 0, 0xFF));
+        assert_eq!(r.pixel(1, 1), SRgb8::new(0, 0, 0xFF));
+        // index 3 is not in the palette
+        assert_eq!(r.pixel(2, 1), SRgb8::new(0, 0, 0));
+        assert_eq!(indexed.color(0, 1), SRgb8::new(0, 0xFF, 0));
+        let (r, palette) = indexed.into();
+        assert_eq!(r.pixel(2, 1), Gray16::new(3));
+        assert_eq!(palette.len(), 3);
+    }
+
+    #[test]
+    fn quantized() {
+        let colors = [SRgb8::new(0x10, 0x20, 0x30), SRgb8::new(0xF0, 0xE0, 0)];
+        let r = Raster::<SRgb8>::with_row_fn(4, 4, |y, row| {
+            for (x, p) in row.iter_mut().enumerate() {
+                *p = colors[(x + y as usize) % 2];
+            }
+        });
+        let palette = Palette::from_raster(&r, 16);
+        let indexed: IndexedRaster<Gray8> =
+            IndexedRaster::new(r.quantize(&palette, Dither::None), palette);
+        assert_eq!(indexed.to_raster().pixels(), r.pixels());
+    }
+}
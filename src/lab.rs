@@ -0,0 +1,356 @@
+// lab.rs        CIELAB color model.
+//
+// Copyright (c) 2024  Douglas P Lau
+//
+//! [CIELAB] color model and types.
+//!
+//! [CIELAB]: https://en.wikipedia.org/wiki/CIELAB_color_space
+use crate::chan::{Ch16, Ch32, Ch8, Channel, Linear, Premultiplied, Straight};
+use crate::el::{to_linear_rgba, Pix3, Pix4, PixRgba, Pixel};
+use crate::xyz::{rgb_to_xyz, xyz_to_rgb};
+use crate::ColorModel;
+use std::ops::Range;
+
+/// D65 reference white point (matching sums of the XYZ conversion matrix)
+const WHITE: [f32; 3] = [0.9505, 1.0, 1.089];
+
+/// Threshold between linear and cube root segments
+const DELTA: f32 = 6.0 / 29.0;
+
+/// Range of *a\** and *b\** which maps to channel values
+const AB_RANGE: f32 = 256.0;
+
+/// [Lab] [color model] (CIE L\*a\*b\*) with D65 white point.
+///
+/// The components are *[L]*, *[a]*, *[b]* and optional *[alpha]*.
+///
+/// Channel values are scaled to fit in the 0 to 1 range:
+/// * *L\** from 0 to 100 is stored as *L\** / 100
+/// * *a\** and *b\** from -128 to 128 are stored as (*v* / 256) + 0.5
+///
+/// [alpha]: ../el/trait.Pixel.html#method.alpha
+/// [a]: #method.a
+/// [b]: #method.b
+/// [color model]: ../trait.ColorModel.html
+/// [L]: #method.lightness
+/// [Lab]: https://en.wikipedia.org/wiki/CIELAB_color_space
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Lab {}
+
+impl Lab {
+    /// Get the *L\** component (perceptual lightness).
+    ///
+    /// # Example: Lab *L\**
+    /// ```
+    /// use pix::chan::Ch32;
+    /// use pix::lab::{Lab, Lab32};
+    ///
+    /// let p = Lab32::new(0.25, 0.5, 1.0);
+    /// assert_eq!(Lab::lightness(p), Ch32::new(0.25));
+    /// ```
+    pub fn lightness<P>(p: P) -> P::Chan
+    where
+        P: Pixel<Model = Self>,
+    {
+        p.one()
+    }
+
+    /// Get a mutable reference to the *L\** component.
+    ///
+    /// # Example: Modify Lab *L\**
+    /// ```
+    /// use pix::chan::Ch32;
+    /// use pix::lab::{Lab, Lab32};
+    ///
+    /// let mut p = Lab32::new(0.25, 0.5, 1.0);
+    /// *Lab::lightness_mut(&mut p) = Ch32::new(0.75);
+    /// assert_eq!(Lab::lightness(p), Ch32::new(0.75));
+    /// ```
+    pub fn lightness_mut<P>(p: &mut P) -> &mut P::Chan
+    where
+        P: Pixel<Model = Self>,
+    {
+        p.one_mut()
+    }
+
+    /// Get the *a\** component (green/red).
+    ///
+    /// # Example: Lab *a\**
+    /// ```
+    /// use pix::chan::Ch16;
+    /// use pix::lab::{Lab, Lab16};
+    ///
+    /// let p = Lab16::new(0x2000, 0x1234, 0x8000);
+    /// assert_eq!(Lab::a(p), Ch16::new(0x1234));
+    /// ```
+    pub fn a<P>(p: P) -> P::Chan
+    where
+        P: Pixel<Model = Self>,
+    {
+        p.two()
+    }
+
+    /// Get a mutable reference to the *a\** component.
+    ///
+    /// # Example: Modify Lab *a\**
+    /// ```
+    /// use pix::chan::Ch16;
+    /// use pix::lab::{Lab, Lab16};
+    ///
+    /// let mut p = Lab16::new(0x2000, 0x1234, 0x8000);
+    /// *Lab::a_mut(&mut p) = 0x4321.into();
+    /// assert_eq!(Lab::a(p), Ch16::new(0x4321));
+    /// ```
+    pub fn a_mut<P>(p: &mut P) -> &mut P::Chan
+    where
+        P: Pixel<Model = Self>,
+    {
+        p.two_mut()
+    }
+
+    /// Get the *b\** component (blue/yellow).
+    ///
+    /// # Example: Lab *b\**
+    /// ```
+    /// use pix::chan::Ch8;
+    /// use pix::lab::{Lab, Lab8};
+    ///
+    /// let p = Lab8::new(0x93, 0x80, 0xA0);
+    /// assert_eq!(Lab::b(p), Ch8::new(0xA0));
+    /// ```
+    pub fn b<P>(p: P) -> P::Chan
+    where
+        P: Pixel<Model = Self>,
+    {
+        p.three()
+    }
+
+    /// Get a mutable reference to the *b\** component.
+    ///
+    /// # Example: Modify Lab *b\**
+    /// ```
+    /// use pix::chan::Ch8;
+    /// use pix::lab::{Lab, Lab8};
+    ///
+    /// let mut p = Lab8::new(0x88, 0x77, 0x66);
+    /// *Lab::b_mut(&mut p) = 0x55.into();
+    /// assert_eq!(Lab::b(p), Ch8::new(0x55));
+    /// ```
+    pub fn b_mut<P>(p: &mut P) -> &mut P::Chan
+    where
+        P: Pixel<Model = Self>,
+    {
+        p.three_mut()
+    }
+}
+
+impl ColorModel for Lab {
+    const CIRCULAR: Range<usize> = 0..0;
+    const LINEAR: Range<usize> = 0..3;
+    const ALPHA: usize = 3;
+
+    /// Convert into *red*, *green*, *blue* and *alpha* components
+    fn into_rgba<P>(p: P) -> PixRgba<P>
+    where
+        P: Pixel<Model = Self>,
+    {
+        let lab = from_channels(Self::lightness(p), Self::a(p), Self::b(p));
+        let [red, green, blue] = xyz_to_rgb(lab_to_xyz(lab));
+
+        PixRgba::<P>::new(red, green, blue, p.alpha().to_f32())
+    }
+
+    /// Convert from *red*, *green*, *blue* and *alpha* components
+    fn from_rgba<P>(rgba: PixRgba<P>) -> P
+    where
+        P: Pixel<Model = Self>,
+    {
+        let chan = rgba.channels();
+        let red = chan[0].to_f32();
+        let green = chan[1].to_f32();
+        let blue = chan[2].to_f32();
+        let alpha = chan[3];
+
+        let [l, a, b] = to_channels(xyz_to_lab(rgb_to_xyz([red, green, blue])));
+
+        P::from_channels(&[l.into(), a.into(), b.into(), alpha])
+    }
+}
+
+/// Unscale channels to *L\**, *a\** and *b\**
+pub(crate) fn from_channels<C: Channel>(l: C, a: C, b: C) -> [f32; 3] {
+    let l = l.to_f32() * 100.0;
+    let a = (a.to_f32() - 0.5) * AB_RANGE;
+    let b = (b.to_f32() - 0.5) * AB_RANGE;
+    [l, a, b]
+}
+
+/// Scale *L\**, *a\** and *b\** to channel values
+pub(crate) fn to_channels([l, a, b]: [f32; 3]) -> [f32; 3] {
+    [l / 100.0, a / AB_RANGE + 0.5, b / AB_RANGE + 0.5]
+}
+
+/// Convert *X*, *Y* and *Z* to *L\**, *a\** and *b\**
+pub(crate) fn xyz_to_lab(xyz: [f32; 3]) -> [f32; 3] {
+    let f = |t: f32| {
+        if t > DELTA * DELTA * DELTA {
+            t.cbrt()
+        } else {
+            t / (3.0 * DELTA * DELTA) + 4.0 / 29.0
+        }
+    };
+    let fx = f(xyz[0] / WHITE[0]);
+    let fy = f(xyz[1] / WHITE[1]);
+    let fz = f(xyz[2] / WHITE[2]);
+    [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+}
+
+/// Convert *L\**, *a\** and *b\** to *X*, *Y* and *Z*
+pub(crate) fn lab_to_xyz([l, a, b]: [f32; 3]) -> [f32; 3] {
+    let finv = |t: f32| {
+        if t > DELTA {
+            t * t * t
+        } else {
+            3.0 * DELTA * DELTA * (t - 4.0 / 29.0)
+        }
+    };
+    let fy = (l + 16.0) / 116.0;
+    let fx = fy + a / 500.0;
+    let fz = fy - b / 200.0;
+    [
+        WHITE[0] * finv(fx),
+        WHITE[1] * finv(fy),
+        WHITE[2] * finv(fz),
+    ]
+}
+
+/// Calculate the perceptual difference between two colors (CIE76 ΔE\*).
+///
+/// Both pixels are converted to *L\**, *a\**, *b\** (in `f32`, without
+/// channel scaling) and the Euclidean distance is returned.  A difference
+/// of about 2.3 is just noticeable.  *Alpha* is ignored.
+///
+/// ### Example
+/// ```
+/// use pix::gray::SGray8;
+/// use pix::lab::delta_e;
+/// use pix::rgb::SRgb8;
+///
+/// let d = delta_e(SRgb8::new(0x80, 0x80, 0x80), SGray8::new(0x80));
+/// assert!(d < 0.01);
+/// ```
+pub fn delta_e<A: Pixel, B: Pixel>(a: A, b: B) -> f32 {
+    let lab =
+        |rgba: [f32; 4]| xyz_to_lab(rgb_to_xyz([rgba[0], rgba[1], rgba[2]]));
+    let [l0, a0, b0] = lab(to_linear_rgba(a));
+    let [l1, a1, b1] = lab(to_linear_rgba(b));
+    ((l0 - l1).powi(2) + (a0 - a1).powi(2) + (b0 - b1).powi(2)).sqrt()
+}
+
+/// [Lab](struct.Lab.html) 8-bit opaque (no *alpha* channel)
+/// [linear](../chan/struct.Linear.html) gamma [pixel](../el/trait.Pixel.html)
+/// format.
+pub type Lab8 = Pix3<Ch8, Lab, Straight, Linear>;
+
+/// [Lab](struct.Lab.html) 16-bit opaque (no *alpha* channel)
+/// [linear](../chan/struct.Linear.html) gamma [pixel](../el/trait.Pixel.html)
+/// format.
+pub type Lab16 = Pix3<Ch16, Lab, Straight, Linear>;
+
+/// [Lab](struct.Lab.html) 32-bit opaque (no *alpha* channel)
+/// [linear](../chan/struct.Linear.html) gamma [pixel](../el/trait.Pixel.html)
+/// format.
+pub type Lab32 = Pix3<Ch32, Lab, Straight, Linear>;
+
+/// [Lab](struct.Lab.html) 8-bit
+/// [straight](../chan/struct.Straight.html) alpha
+/// [linear](../chan/struct.Linear.html) gamma [pixel](../el/trait.Pixel.html)
+/// format.
+pub type Laba8 = Pix4<Ch8, Lab, Straight, Linear>;
+
+/// [Lab](struct.Lab.html) 16-bit
+/// [straight](../chan/struct.Straight.html) alpha
+/// [linear](../chan/struct.Linear.html) gamma [pixel](../el/trait.Pixel.html)
+/// format.
+pub type Laba16 = Pix4<Ch16, Lab, Straight, Linear>;
+
+/// [Lab](struct.Lab.html) 32-bit
+/// [straight](../chan/struct.Straight.html) alpha
+/// [linear](../chan/struct.Linear.html) gamma [pixel](../el/trait.Pixel.html)
+/// format.
+pub type Laba32 = Pix4<Ch32, Lab, Straight, Linear>;
+
+/// [Lab](struct.Lab.html) 8-bit
+/// [premultiplied](../chan/struct.Premultiplied.html) alpha
+/// [linear](../chan/struct.Linear.html) gamma [pixel](../el/trait.Pixel.html)
+/// format.
+pub type Laba8p = Pix4<Ch8, Lab, Premultiplied, Linear>;
+
+/// [Lab](struct.Lab.html) 16-bit
+/// [premultiplied](../chan/struct.Premultiplied.html) alpha
+/// [linear](../chan/struct.Linear.html) gamma [pixel](../el/trait.Pixel.html)
+/// format.
+pub type Laba16p = Pix4<Ch16, Lab, Premultiplied, Linear>;
+
+/// [Lab](struct.Lab.html) 32-bit
+/// [premultiplied](../chan/struct.Premultiplied.html) alpha
+/// [linear](../chan/struct.Linear.html) gamma [pixel](../el/trait.Pixel.html)
+/// format.
+pub type Laba32p = Pix4<Ch32, Lab, Premultiplied, Linear>;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::el::Pixel;
+    use crate::rgb::{Rgb32, SRgb32, SRgb8};
+
+    fn assert_lab(p: Lab32, lab: [f32; 3]) {
+        let v = from_channels(Lab::lightness(p), Lab::a(p), Lab::b(p));
+        for (v, e) in v.iter().zip(&lab) {
+            assert!((v - e).abs() < 0.05, "{v} != {e}");
+        }
+    }
+
+    #[test]
+    fn gray_to_lab() {
+        let g = Rgb32::new(0.25, 0.25, 0.25);
+        let p: Lab32 = g.convert();
+        assert_lab(p, [57.075, 0.0, 0.0]);
+        let r: Rgb32 = p.convert();
+        for (c, e) in r.channels().iter().zip(g.channels()) {
+            assert!((c.to_f32() - e.to_f32()).abs() < 0.001);
+        }
+    }
+
+    #[test]
+    fn white_to_lab() {
+        let p: Lab32 = SRgb8::new(255, 255, 255).convert();
+        assert_lab(p, [100.0, 0.0, 0.0]);
+        let p: Lab32 = Rgb32::new(0.0, 0.0, 0.0).convert();
+        assert_lab(p, [0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn delta_e() {
+        use crate::gray::SGray16;
+        let red = SRgb8::new(0xFF, 0, 0);
+        assert_eq!(super::delta_e(red, red), 0.0);
+        let black = SRgb8::new(0, 0, 0);
+        let white = SGray16::new(0xFFFF);
+        assert!((super::delta_e(black, white) - 100.0).abs() < 0.1);
+        let d = super::delta_e(red, SRgb32::new(1.0, 0.0, 0.0));
+        assert!(d < 0.01, "{d}");
+        let d = super::delta_e(red, black);
+        assert!((d - 117.34).abs() < 0.1, "{d}");
+    }
+
+    #[test]
+    fn red_to_lab() {
+        let p: Lab32 = SRgb8::new(255, 0, 0).convert();
+        assert_lab(p, [53.24, 80.09, 67.20]);
+        let r: SRgb32 = p.convert();
+        assert!((r.one().to_f32() - 1.0).abs() < 0.001);
+        assert!(r.two().to_f32() < 0.001);
+        assert!(r.three().to_f32() < 0.001);
+    }
+}
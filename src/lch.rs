@@ -0,0 +1,279 @@
+// lch.rs        CIE LCh(ab) color model.
+//
+// Copyright (c) 2024  Douglas P Lau
+//
+//! [LCh] color model and types.
+//!
+//! [LCh]: https://en.wikipedia.org/wiki/CIELAB_color_space#Cylindrical_model
+use crate::chan::{Ch16, Ch32, Ch8, Channel, Linear, Premultiplied, Straight};
+use crate::el::{Pix3, Pix4, PixRgba, Pixel};
+use crate::lab::{lab_to_xyz, xyz_to_lab};
+use crate::xyz::{rgb_to_xyz, xyz_to_rgb};
+use crate::ColorModel;
+use std::f32::consts::TAU;
+use std::ops::Range;
+
+/// Maximum *chroma* which maps to channel values
+const CHROMA_MAX: f32 = 150.0;
+
+/// *Chroma* below this is treated as neutral, with *hue* of 0
+const CHROMA_EPSILON: f32 = 0.01;
+
+/// [LCh] [color model], the cylindrical form of [Lab](../lab/struct.Lab.html).
+///
+/// The components are *[lightness]*, *[chroma]*, *[hue]* and optional
+/// *[alpha]*.
+///
+/// Channel values are scaled to fit in the 0 to 1 range:
+/// * *L\** from 0 to 100 is stored as *L\** / 100
+/// * *C\** from 0 to 150 is stored as *C\** / 150
+/// * *h* from 0 to 360 degrees is stored as *h* / 360
+///
+/// [alpha]: ../el/trait.Pixel.html#method.alpha
+/// [chroma]: #method.chroma
+/// [color model]: ../trait.ColorModel.html
+/// [hue]: #method.hue
+/// [lch]: https://en.wikipedia.org/wiki/CIELAB_color_space#Cylindrical_model
+/// [lightness]: #method.lightness
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Lch {}
+
+impl Lch {
+    /// Get the *lightness* component.
+    ///
+    /// # Example: LCh *lightness*
+    /// ```
+    /// use pix::chan::Ch32;
+    /// use pix::lch::{Lch, Lch32};
+    ///
+    /// let p = Lch32::new(0.25, 0.5, 1.0);
+    /// assert_eq!(Lch::lightness(p), Ch32::new(0.25));
+    /// ```
+    pub fn lightness<P>(p: P) -> P::Chan
+    where
+        P: Pixel<Model = Self>,
+    {
+        p.one()
+    }
+
+    /// Get a mutable reference to the *lightness* component.
+    ///
+    /// # Example: Modify LCh *lightness*
+    /// ```
+    /// use pix::chan::Ch32;
+    /// use pix::lch::{Lch, Lch32};
+    ///
+    /// let mut p = Lch32::new(0.25, 0.5, 1.0);
+    /// *Lch::lightness_mut(&mut p) = Ch32::new(0.75);
+    /// assert_eq!(Lch::lightness(p), Ch32::new(0.75));
+    /// ```
+    pub fn lightness_mut<P>(p: &mut P) -> &mut P::Chan
+    where
+        P: Pixel<Model = Self>,
+    {
+        p.one_mut()
+    }
+
+    /// Get the *chroma* component.
+    ///
+    /// # Example: LCh *chroma*
+    /// ```
+    /// use pix::chan::Ch16;
+    /// use pix::lch::{Lch, Lch16};
+    ///
+    /// let p = Lch16::new(0x2000, 0x1234, 0x8000);
+    /// assert_eq!(Lch::chroma(p), Ch16::new(0x1234));
+    /// ```
+    pub fn chroma<P>(p: P) -> P::Chan
+    where
+        P: Pixel<Model = Self>,
+    {
+        p.two()
+    }
+
+    /// Get a mutable reference to the *chroma* component.
+    ///
+    /// # Example: Modify LCh *chroma*
+    /// ```
+    /// use pix::chan::Ch16;
+    /// use pix::lch::{Lch, Lch16};
+    ///
+    /// let mut p = Lch16::new(0x2000, 0x1234, 0x8000);
+    /// *Lch::chroma_mut(&mut p) = 0x4321.into();
+    /// assert_eq!(Lch::chroma(p), Ch16::new(0x4321));
+    /// ```
+    pub fn chroma_mut<P>(p: &mut P) -> &mut P::Chan
+    where
+        P: Pixel<Model = Self>,
+    {
+        p.two_mut()
+    }
+
+    /// Get the *hue* component.
+    ///
+    /// The degrees are mapped from [Channel::MIN] (0) to [Channel::MAX] (360).
+    /// Neutral colors (with no *chroma*) have a *hue* of 0.
+    ///
+    /// # Example: LCh *hue*
+    /// ```
+    /// use pix::chan::Ch8;
+    /// use pix::lch::{Lch, Lch8};
+    ///
+    /// let p = Lch8::new(0x93, 0x80, 0xA0);
+    /// assert_eq!(Lch::hue(p), Ch8::new(0xA0));
+    /// ```
+    /// [Channel::MIN]: ../chan/trait.Channel.html#associatedconstant.MIN
+    /// [Channel::MAX]: ../chan/trait.Channel.html#associatedconstant.MAX
+    pub fn hue<P>(p: P) -> P::Chan
+    where
+        P: Pixel<Model = Self>,
+    {
+        p.three()
+    }
+
+    /// Get a mutable reference to the *hue* component.
+    ///
+    /// # Example: Modify LCh *hue*
+    /// ```
+    /// use pix::chan::Ch8;
+    /// use pix::lch::{Lch, Lch8};
+    ///
+    /// let mut p = Lch8::new(0x88, 0x77, 0x66);
+    /// *Lch::hue_mut(&mut p) = 0x55.into();
+    /// assert_eq!(Lch::hue(p), Ch8::new(0x55));
+    /// ```
+    pub fn hue_mut<P>(p: &mut P) -> &mut P::Chan
+    where
+        P: Pixel<Model = Self>,
+    {
+        p.three_mut()
+    }
+}
+
+impl ColorModel for Lch {
+    const CIRCULAR: Range<usize> = 2..3;
+    const LINEAR: Range<usize> = 0..2;
+    const ALPHA: usize = 3;
+
+    /// Convert into *red*, *green*, *blue* and *alpha* components
+    fn into_rgba<P>(p: P) -> PixRgba<P>
+    where
+        P: Pixel<Model = Self>,
+    {
+        let l = Self::lightness(p).to_f32() * 100.0;
+        let c = Self::chroma(p).to_f32() * CHROMA_MAX;
+        let h = Self::hue(p).to_f32() * TAU;
+        let lab = [l, c * h.cos(), c * h.sin()];
+        let [red, green, blue] = xyz_to_rgb(lab_to_xyz(lab));
+
+        PixRgba::<P>::new(red, green, blue, p.alpha().to_f32())
+    }
+
+    /// Convert from *red*, *green*, *blue* and *alpha* components
+    fn from_rgba<P>(rgba: PixRgba<P>) -> P
+    where
+        P: Pixel<Model = Self>,
+    {
+        let chan = rgba.channels();
+        let red = chan[0].to_f32();
+        let green = chan[1].to_f32();
+        let blue = chan[2].to_f32();
+        let alpha = chan[3];
+
+        let [l, a, b] = xyz_to_lab(rgb_to_xyz([red, green, blue]));
+        let c = a.hypot(b);
+        let h = if c < CHROMA_EPSILON {
+            0.0
+        } else {
+            b.atan2(a).rem_euclid(TAU) / TAU
+        };
+        let l = l / 100.0;
+        let c = c / CHROMA_MAX;
+
+        P::from_channels(&[l.into(), c.into(), h.into(), alpha])
+    }
+}
+
+/// [Lch](struct.Lch.html) 8-bit opaque (no *alpha* channel)
+/// [linear](../chan/struct.Linear.html) gamma [pixel](../el/trait.Pixel.html)
+/// format.
+pub type Lch8 = Pix3<Ch8, Lch, Straight, Linear>;
+
+/// [Lch](struct.Lch.html) 16-bit opaque (no *alpha* channel)
+/// [linear](../chan/struct.Linear.html) gamma [pixel](../el/trait.Pixel.html)
+/// format.
+pub type Lch16 = Pix3<Ch16, Lch, Straight, Linear>;
+
+/// [Lch](struct.Lch.html) 32-bit opaque (no *alpha* channel)
+/// [linear](../chan/struct.Linear.html) gamma [pixel](../el/trait.Pixel.html)
+/// format.
+pub type Lch32 = Pix3<Ch32, Lch, Straight, Linear>;
+
+/// [Lch](struct.Lch.html) 8-bit
+/// [straight](../chan/struct.Straight.html) alpha
+/// [linear](../chan/struct.Linear.html) gamma [pixel](../el/trait.Pixel.html)
+/// format.
+pub type Lcha8 = Pix4<Ch8, Lch, Straight, Linear>;
+
+/// [Lch](struct.Lch.html) 16-bit
+/// [straight](../chan/struct.Straight.html) alpha
+/// [linear](../chan/struct.Linear.html) gamma [pixel](../el/trait.Pixel.html)
+/// format.
+pub type Lcha16 = Pix4<Ch16, Lch, Straight, Linear>;
+
+/// [Lch](struct.Lch.html) 32-bit
+/// [straight](../chan/struct.Straight.html) alpha
+/// [linear](../chan/struct.Linear.html) gamma [pixel](../el/trait.Pixel.html)
+/// format.
+pub type Lcha32 = Pix4<Ch32, Lch, Straight, Linear>;
+
+/// [Lch](struct.Lch.html) 8-bit
+/// [premultiplied](../chan/struct.Premultiplied.html) alpha
+/// [linear](../chan/struct.Linear.html) gamma [pixel](../el/trait.Pixel.html)
+/// format.
+pub type Lcha8p = Pix4<Ch8, Lch, Premultiplied, Linear>;
+
+/// [Lch](struct.Lch.html) 16-bit
+/// [premultiplied](../chan/struct.Premultiplied.html) alpha
+/// [linear](../chan/struct.Linear.html) gamma [pixel](../el/trait.Pixel.html)
+/// format.
+pub type Lcha16p = Pix4<Ch16, Lch, Premultiplied, Linear>;
+
+/// [Lch](struct.Lch.html) 32-bit
+/// [premultiplied](../chan/struct.Premultiplied.html) alpha
+/// [linear](../chan/struct.Linear.html) gamma [pixel](../el/trait.Pixel.html)
+/// format.
+pub type Lcha32p = Pix4<Ch32, Lch, Premultiplied, Linear>;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::chan::Ch32;
+    use crate::rgb::SRgb8;
+
+    #[test]
+    fn gray_chroma() {
+        let mut p: Lch32 = SRgb8::new(128, 128, 128).convert();
+        assert!(Lch::chroma(p).to_f32() < 0.001);
+        assert_eq!(Lch::hue(p), Ch32::new(0.0));
+        let g: SRgb8 = p.convert();
+        assert_eq!(g, SRgb8::new(128, 128, 128));
+        *Lch::chroma_mut(&mut p) = Ch32::new(0.2);
+        let r: SRgb8 = p.convert();
+        assert!(r.one() > g.one());
+        assert!(r.two() < g.two());
+        let q: Lch32 = r.convert();
+        let h = Lch::hue(q).to_f32();
+        assert!(!(0.01..=0.99).contains(&h), "{h}");
+    }
+
+    #[test]
+    fn red_round_trip() {
+        let p: Lch32 = SRgb8::new(255, 0, 0).convert();
+        assert!((Lch::lightness(p).to_f32() * 100.0 - 53.24).abs() < 0.05);
+        assert!((Lch::chroma(p).to_f32() * 150.0 - 104.55).abs() < 0.05);
+        assert!((Lch::hue(p).to_f32() * 360.0 - 40.0).abs() < 0.05);
+        let r: SRgb8 = p.convert();
+        assert_eq!(r, SRgb8::new(255, 0, 0));
+    }
+}
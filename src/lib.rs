@@ -10,12 +10,13 @@
 //!
 //! Many image formats are supported:
 //!
-//! * Bit depth: 8- or 16-bit integer and 32-bit float
+//! * Bit depth: 8- or 16-bit integer and 32- or 64-bit float
 //! * Alpha: *premultiplied* or *straight*
 //! * Gamma: *linear* or *sRGB*
 //! * Color models:
 //!   - [`RGB`] / [`BGR`] (*red*, *green*, *blue*)
 //!   - [`CMY`] (*cyan*, *magenta*, *yellow*)
+//!   - [`CMYK`] (*cyan*, *magenta*, *yellow*, *key*)
 //!   - [`Gray`] (*luma* / *relative luminance*)
 //!   - [`HSV`] (*hue*, *saturation*, *value*)
 //!   - [`HSL`] (*hue*, *saturation*, *lightness*)
@@ -24,25 +25,43 @@
 //!   - [`Matte`] (*alpha* only)
 //!   - [`Oklab`] (*lightness*, *green/red*, *blue/yellow*)
 //!   - [`XYZ`] (CIE 1931 XYZ)
+//!   - [`Lab`] (CIE L\*a\*b\*)
+//!   - [`LCh`] (*lightness*, *chroma*, *hue*)
 //!
 //! Compositing with blending [operations] is supported for *premultiplied*
 //! images with *linear* gamma.
 //!
+//! Optional features:
+//!
+//! * `bytemuck`: `Pod` / `Zeroable` for channel and pixel types
+//! * `image`: conversion to and from `image` crate `RgbaImage`
+//! * `ndarray`: view pixels as an `ndarray` array, with [as_array_view]
+//! * `rayon`: parallel raster operations, such as [par_fold]
+//! * `serde`: `Serialize` / `Deserialize` for [Raster] and [Region]
+//! * `simd`: SIMD compositing of 8-bit RGBA with [SrcOver] (x86_64 only)
+//!
 //! [alpha]: chan/trait.Alpha.html
+//! [as_array_view]: struct.Raster.html#method.as_array_view
 //! [`bgr`]: bgr/index.html
 //! [channel]: chan/trait.Channel.html
 //! [`cmy`]: cmy/index.html
+//! [`cmyk`]: cmyk/index.html
 //! [color model]: trait.ColorModel.html
 //! [gamma]: chan/trait.Gamma.html
 //! [`gray`]: gray/index.html
 //! [`hsl`]: hsl/index.html
 //! [`hsv`]: hsv/index.html
 //! [`hwb`]: hwb/index.html
+//! [`lab`]: lab/index.html
+//! [`lch`]: lch/index.html
 //! [`matte`]: matte/index.html
 //! [`oklab`]: oklab/index.html
 //! [operations]: ops/index.html
+//! [par_fold]: struct.Raster.html#method.par_fold
 //! [raster]: struct.Raster.html
+//! [Region]: struct.Region.html
 //! [`rgb`]: rgb/index.html
+//! [SrcOver]: ops/struct.SrcOver.html
 //! [`ycbcr`]: ycc/index.html
 //! [`xyz`]: xyz/index.html
 //!
@@ -69,26 +88,50 @@
 //!
 #![warn(missing_docs)]
 
+mod access;
+#[cfg(feature = "ndarray")]
+mod array;
 pub mod bgr;
+mod borrowed;
 pub mod chan;
 pub mod cmy;
+pub mod cmyk;
+mod compositor;
+mod depth;
+mod draw;
 pub mod el;
 pub mod gray;
 pub mod hsl;
 pub mod hsv;
 mod hue;
 pub mod hwb;
+#[cfg(feature = "image")]
+mod image_buf;
+mod indexed;
+pub mod lab;
+pub mod lch;
 pub mod matte;
 mod model;
 pub mod oklab;
 pub mod ops;
 mod palette;
+#[cfg(feature = "bytemuck")]
+mod pod;
 mod private;
 mod raster;
 pub mod rgb;
+#[cfg(feature = "serde")]
+mod serialize;
+#[cfg(feature = "simd")]
+mod simd;
 pub mod xyz;
 pub mod ycc;
 
+pub use crate::access::{RasterMut, RasterRef};
+pub use crate::borrowed::BorrowedRaster;
+pub use crate::compositor::Compositor;
+pub use crate::depth::DepthRaster;
+pub use crate::indexed::IndexedRaster;
 pub use crate::model::ColorModel;
-pub use crate::palette::Palette;
-pub use crate::raster::{Raster, Region, Rows, RowsMut};
+pub use crate::palette::{Dither, Palette};
+pub use crate::raster::{Filter, Raster, Region, Rows, RowsMut};
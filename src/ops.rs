@@ -24,6 +24,13 @@ pub trait Blend: Any + Copy + Clone {
     /// * `src` Source channel
     /// * `sa1` One minus source *alpha*
     fn composite<C: Channel>(dst: &mut C, da1: C, src: &C, sa1: C);
+
+    /// Composite a destination and source *alpha* channel
+    ///
+    /// By default, this is the same as `composite`.
+    fn composite_alpha<C: Channel>(dst: &mut C, da1: C, src: &C, sa1: C) {
+        Self::composite(dst, da1, src, sa1);
+    }
 }
 
 /// Source only (ignore destination)
@@ -78,6 +85,46 @@ pub struct Clear;
 #[derive(Clone, Copy)]
 pub struct Plus;
 
+/// Multiply compositing (source multiplied by destination, then *alpha*
+/// blended)
+#[derive(Clone, Copy)]
+pub struct Multiply;
+
+/// Screen compositing (inverse of [Multiply] of inverted colors)
+///
+/// The result is never darker than either source or destination.
+#[derive(Clone, Copy)]
+pub struct Screen;
+
+/// Overlay compositing ([Multiply] or [Screen], depending on destination)
+///
+/// Where the destination is less than half of its *alpha*, the colors are
+/// multiplied; otherwise, they are screened.  This increases contrast while
+/// preserving destination highlights and shadows.
+#[derive(Clone, Copy)]
+pub struct Overlay;
+
+/// Darken compositing (minimum of source and destination, then *alpha*
+/// blended)
+#[derive(Clone, Copy)]
+pub struct Darken;
+
+/// Lighten compositing (maximum of source and destination, then *alpha*
+/// blended)
+#[derive(Clone, Copy)]
+pub struct Lighten;
+
+/// Difference compositing (absolute difference of source and destination,
+/// then *alpha* blended)
+#[derive(Clone, Copy)]
+pub struct Difference;
+
+/// Subtract compositing (source subtracted from destination)
+///
+/// Like [Plus], this applies to all channels, including *alpha*.
+#[derive(Clone, Copy)]
+pub struct Subtract;
+
 impl Blend for Src {
     fn composite<C: Channel>(dst: &mut C, _da1: C, src: &C, _sa1: C) {
         *dst = *src;
@@ -159,3 +206,67 @@ impl Blend for Plus {
         *dst = *src + *dst;
     }
 }
+
+impl Blend for Multiply {
+    fn composite<C: Channel>(dst: &mut C, da1: C, src: &C, sa1: C) {
+        *dst = *src * *dst + *src * da1 + *dst * sa1;
+    }
+}
+
+impl Blend for Subtract {
+    fn composite<C: Channel>(dst: &mut C, _da1: C, src: &C, _sa1: C) {
+        *dst = *dst - *src;
+    }
+}
+
+impl Blend for Screen {
+    fn composite<C: Channel>(dst: &mut C, _da1: C, src: &C, _sa1: C) {
+        *dst = *src + (*dst - *src * *dst);
+    }
+}
+
+impl Blend for Overlay {
+    fn composite<C: Channel>(dst: &mut C, da1: C, src: &C, sa1: C) {
+        let (da, sa) = (C::MAX - da1, C::MAX - sa1);
+        let blend = if *dst <= da - *dst {
+            let m = *src * *dst;
+            m + m
+        } else {
+            let m = (da - *dst) * (sa - *src);
+            sa * da - (m + m)
+        };
+        *dst = blend + *src * da1 + *dst * sa1;
+    }
+
+    fn composite_alpha<C: Channel>(dst: &mut C, da1: C, src: &C, sa1: C) {
+        SrcOver::composite(dst, da1, src, sa1);
+    }
+}
+
+impl Blend for Darken {
+    fn composite<C: Channel>(dst: &mut C, da1: C, src: &C, sa1: C) {
+        let (da, sa) = (C::MAX - da1, C::MAX - sa1);
+        let blend = (*src * da).min(*dst * sa);
+        *dst = blend + *src * da1 + *dst * sa1;
+    }
+}
+
+impl Blend for Lighten {
+    fn composite<C: Channel>(dst: &mut C, da1: C, src: &C, sa1: C) {
+        let (da, sa) = (C::MAX - da1, C::MAX - sa1);
+        let blend = (*src * da).max(*dst * sa);
+        *dst = blend + *src * da1 + *dst * sa1;
+    }
+}
+
+impl Blend for Difference {
+    fn composite<C: Channel>(dst: &mut C, da1: C, src: &C, sa1: C) {
+        let (da, sa) = (C::MAX - da1, C::MAX - sa1);
+        let (s, d) = (*src * da, *dst * sa);
+        *dst = (s.max(d) - s.min(d)) + *src * da1 + *dst * sa1;
+    }
+
+    fn composite_alpha<C: Channel>(dst: &mut C, da1: C, src: &C, sa1: C) {
+        SrcOver::composite(dst, da1, src, sa1);
+    }
+}
@@ -8,6 +8,24 @@ use crate::gray::Gray8;
 use crate::raster::Raster;
 use crate::rgb::{Rgb, SRgb8};
 
+/// Dithering method for [Raster::quantize].
+///
+/// [Raster::quantize]: struct.Raster.html#method.quantize
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Dither {
+    /// No dithering; each pixel is mapped to the nearest entry
+    #[default]
+    None,
+    /// Floyd-Steinberg error diffusion, in serpentine order
+    FloydSteinberg,
+    /// Ordered dithering with a 4x4 Bayer matrix
+    Ordered,
+}
+
+/// 4x4 Bayer threshold matrix
+const BAYER: [[u8; 4]; 4] =
+    [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
 /// Color table for use with indexed `Raster`s.
 #[derive(Clone)]
 pub struct Palette {
@@ -28,6 +46,89 @@ impl Palette {
         }
     }
 
+    /// Create a `Palette` from the colors of a `Raster`.
+    ///
+    /// * `raster` Source `Raster`, in any format.
+    /// * `max_colors` Maximum number of entries.
+    ///
+    /// Pixels are converted to [SRgb8].  If there are no more than
+    /// `max_colors` unique colors, the palette contains exactly those colors.
+    /// Otherwise, colors are reduced using median-cut quantization: the box
+    /// of colors with the widest channel range is repeatedly split at its
+    /// median, and each final box is replaced by its mean color.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::SRgb8;
+    /// use pix::{Palette, Raster};
+    ///
+    /// let r = Raster::<SRgb8>::with_row_fn(16, 16, |y, row| {
+    ///     for (x, p) in row.iter_mut().enumerate() {
+    ///         *p = SRgb8::new(x as u8 * 16, y as u8 * 16, 0x80);
+    ///     }
+    /// });
+    /// let palette = Palette::from_raster(&r, 8);
+    /// assert_eq!(palette.len(), 8);
+    /// ```
+    /// [SRgb8]: rgb/type.SRgb8.html
+    pub fn from_raster<P>(raster: &Raster<P>, max_colors: usize) -> Self
+    where
+        P: Pixel,
+        Ch8: From<P::Chan>,
+    {
+        let mut colors: Vec<[u8; 3]> = raster
+            .pixels()
+            .iter()
+            .map(|p| {
+                let c: SRgb8 = p.convert();
+                [c.one().into(), c.two().into(), c.three().into()]
+            })
+            .collect();
+        colors.sort_unstable();
+        // unique colors, with pixel counts
+        let mut counts: Vec<([u8; 3], u64)> = Vec::new();
+        for c in colors {
+            match counts.last_mut() {
+                Some((lc, n)) if *lc == c => *n += 1,
+                _ => counts.push((c, 1)),
+            }
+        }
+        let mut palette = Palette::new(max_colors);
+        if counts.len() <= max_colors {
+            palette.table.extend(
+                counts
+                    .iter()
+                    .map(|(c, _)| SRgb8::from_channels(&c.map(Ch8::new))),
+            );
+            return palette;
+        }
+        let mut boxes = vec![counts];
+        while boxes.len() < max_colors {
+            let Some((i, chan)) = widest_box(&boxes) else {
+                break;
+            };
+            let mut bx = boxes.swap_remove(i);
+            bx.sort_unstable_by_key(|(c, _)| c[chan]);
+            let total: u64 = bx.iter().map(|(_, n)| n).sum();
+            let mut sum = 0;
+            let mut mid = 1;
+            for (j, (_, n)) in bx.iter().enumerate() {
+                sum += n;
+                if sum * 2 >= total {
+                    mid = (j + 1).clamp(1, bx.len() - 1);
+                    break;
+                }
+            }
+            let upper = bx.split_off(mid);
+            boxes.push(bx);
+            boxes.push(upper);
+        }
+        palette
+            .table
+            .extend(boxes.iter().take(max_colors).map(|bx| mean_color(bx)));
+        palette
+    }
+
     /// Get the number of entries.
     pub fn len(&self) -> usize {
         self.table.len()
@@ -41,9 +142,8 @@ impl Palette {
     /// Set the threshold function for matching entries.
     ///
     /// * `threshold_fn` Called when checking whether a color matches an
-    ///                  existing entry.  The parameter is the palette table
-    ///                  size.  Returns the maximum `Channel`-wise difference
-    ///                  to match.
+    ///   existing entry.  The parameter is the palette table size.  Returns
+    ///   the maximum `Channel`-wise difference to match.
     pub fn set_threshold_fn(&mut self, threshold_fn: fn(usize) -> SRgb8) {
         self.threshold_fn = threshold_fn;
     }
@@ -75,11 +175,10 @@ impl Palette {
     /// Index of best matching or added entry if successful.  Otherwise, when
     /// no matches are found and the table is full, `None` is returned.
     pub fn set_entry(&mut self, clr: SRgb8) -> Option<usize> {
-        if let Some((i, dif)) = self.best_match(clr) {
-            if Rgb::within_threshold(dif, (self.threshold_fn)(self.table.len()))
-            {
-                return Some(i);
-            }
+        if let Some((i, dif)) = self.best_match(clr)
+            && Rgb::within_threshold(dif, (self.threshold_fn)(self.table.len()))
+        {
+            return Some(i);
         }
         let i = self.table.len();
         if i < self.table.capacity() {
@@ -107,6 +206,55 @@ impl Palette {
         best
     }
 
+    /// Find the nearest entry to a color.
+    ///
+    /// * `clr` Color to match, in any format.
+    ///
+    /// The color is converted to [SRgb8], and compared to each entry by
+    /// squared RGB distance.  Ties resolve to the lowest index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the palette is empty.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::{SRgb8, SRgba16};
+    /// use pix::Palette;
+    ///
+    /// let mut p = Palette::new(4);
+    /// p.set_entry(SRgb8::new(0, 0, 0));
+    /// p.set_entry(SRgb8::new(0xFF, 0, 0));
+    /// assert_eq!(p.nearest(SRgba16::new(0xA000, 0x2000, 0, 0xFFFF)), 1);
+    /// ```
+    /// [SRgb8]: rgb/type.SRgb8.html
+    pub fn nearest<P>(&self, clr: P) -> usize
+    where
+        P: Pixel,
+        Ch8: From<P::Chan>,
+    {
+        assert!(!self.is_empty(), "Palette must not be empty");
+        self.nearest_rgb(rgb_values(clr.convert()))
+    }
+
+    /// Find the nearest entry to a color, by squared RGB distance.
+    ///
+    /// Ties resolve to the lowest index.
+    fn nearest_rgb(&self, clr: [i32; 3]) -> usize {
+        let mut best = (0, i32::MAX);
+        for (i, c) in self.table.iter().enumerate() {
+            let dist = rgb_values(*c)
+                .iter()
+                .zip(clr)
+                .map(|(c, v)| (c - v).pow(2))
+                .sum();
+            if dist < best.1 {
+                best = (i, dist);
+            }
+        }
+        best.0
+    }
+
     /// Replace a `Palette` entry.
     ///
     /// * `i` Index of entry.
@@ -163,10 +311,137 @@ impl Palette {
     }
 }
 
+impl<P> Raster<P>
+where
+    P: Pixel,
+    Ch8: From<P::Chan>,
+{
+    /// Map pixels to the nearest colors in a `Palette`.
+    ///
+    /// * `palette` Palette of colors, with 1 to 256 entries.
+    /// * `dither` Dithering method.
+    ///
+    /// Pixels are converted to [SRgb8], and the nearest entry is found by
+    /// squared RGB distance.  With [Dither::FloydSteinberg], the quantization
+    /// error of each pixel is spread to its unvisited neighbors.  Values are
+    /// clamped to the channel range before lookup, which limits runaway
+    /// error.
+    ///
+    /// Returns an indexed `Raster`, with palette indices as gray values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the palette is empty or has more than 256 entries.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::SRgb8;
+    /// use pix::{Dither, Palette, Raster};
+    ///
+    /// let r = Raster::<SRgb8>::with_row_fn(64, 64, |y, row| {
+    ///     for (x, p) in row.iter_mut().enumerate() {
+    ///         *p = SRgb8::new(x as u8 * 4, y as u8 * 4, 0x80);
+    ///     }
+    /// });
+    /// let palette = Palette::from_raster(&r, 16);
+    /// let indexed = r.quantize(&palette, Dither::FloydSteinberg);
+    /// ```
+    /// [Dither::FloydSteinberg]: enum.Dither.html#variant.FloydSteinberg
+    /// [SRgb8]: rgb/type.SRgb8.html
+    pub fn quantize(&self, palette: &Palette, dither: Dither) -> Raster<Gray8> {
+        let n = palette.len();
+        assert!(n > 0 && n <= 256, "Palette must have 1 to 256 entries");
+        let width = self.width() as usize;
+        let mut values: Vec<[i32; 3]> = self
+            .pixels()
+            .iter()
+            .map(|p| rgb_values(p.convert()))
+            .collect();
+        let mut indexed = Raster::with_clear(self.width(), self.height());
+        let spread = 255.0 / (n as f32).cbrt();
+        for y in 0..self.height() as usize {
+            let reverse = dither == Dither::FloydSteinberg && y % 2 == 1;
+            for i in 0..width {
+                let x = if reverse { width - 1 - i } else { i };
+                let mut clr = values[y * width + x].map(|v| v.clamp(0, 255));
+                if dither == Dither::Ordered {
+                    let t = f32::from(BAYER[y % 4][x % 4]) + 0.5;
+                    let ofs = ((t / 16.0 - 0.5) * spread) as i32;
+                    clr = clr.map(|v| (v + ofs).clamp(0, 255));
+                }
+                let e = palette.nearest_rgb(clr);
+                *indexed.pixel_mut(x as i32, y as i32) =
+                    Gray8::from_channels(&[Ch8::new(e as u8)]);
+                if dither != Dither::FloydSteinberg {
+                    continue;
+                }
+                let ent = rgb_values(palette.table[e]);
+                let err: [i32; 3] = std::array::from_fn(|c| clr[c] - ent[c]);
+                let (fwd, back) = if reverse {
+                    (x.checked_sub(1), Some(x + 1))
+                } else {
+                    (Some(x + 1), x.checked_sub(1))
+                };
+                let mut diffuse = |x: Option<usize>, y: usize, w: i32| {
+                    if let Some(x) = x.filter(|x| *x < width)
+                        && let Some(v) = values.get_mut(y * width + x)
+                    {
+                        for (v, e) in v.iter_mut().zip(err) {
+                            *v += e * w / 16;
+                        }
+                    }
+                };
+                diffuse(fwd, y, 7);
+                diffuse(back, y + 1, 3);
+                diffuse(Some(x), y + 1, 5);
+                diffuse(fwd, y + 1, 1);
+            }
+        }
+        indexed
+    }
+}
+
+/// Get the channel values of a color.
+fn rgb_values(clr: SRgb8) -> [i32; 3] {
+    [clr.one(), clr.two(), clr.three()].map(|v| i32::from(u8::from(v)))
+}
+
+/// Find the box with the widest channel range, and that channel.
+fn widest_box(boxes: &[Vec<([u8; 3], u64)>]) -> Option<(usize, usize)> {
+    let mut widest = None;
+    let mut max_range = 0;
+    for (i, bx) in boxes.iter().enumerate() {
+        for chan in 0..3 {
+            let lo = bx.iter().map(|(c, _)| c[chan]).min().unwrap_or(0);
+            let hi = bx.iter().map(|(c, _)| c[chan]).max().unwrap_or(0);
+            if hi - lo > max_range {
+                max_range = hi - lo;
+                widest = Some((i, chan));
+            }
+        }
+    }
+    widest
+}
+
+/// Get the mean color of a box, weighted by pixel counts.
+fn mean_color(bx: &[([u8; 3], u64)]) -> SRgb8 {
+    let total: u64 = bx.iter().map(|(_, n)| n).sum();
+    let mut sums = [0; 3];
+    for (c, n) in bx {
+        for (s, v) in sums.iter_mut().zip(c) {
+            *s += u64::from(*v) * n;
+        }
+    }
+    let [r, g, b] = sums.map(|s| ((s + total / 2) / total) as u8);
+    SRgb8::new(r, g, b)
+}
+
 #[cfg(test)]
 mod test {
+    use crate::el::Pixel;
+    use crate::gray::*;
     use crate::rgb::*;
-    use crate::Palette;
+    use crate::{Dither, Palette, Raster};
 
     #[test]
     fn fill_16() {
@@ -222,6 +497,86 @@ mod test {
         assert_eq!(p.histogram(&v[..]), Some(vec![18, 6, 10, 4, 8, 0, 2]));
     }
 
+    #[test]
+    fn from_raster() {
+        let colors = [
+            SRgb8::new(0xFF, 0, 0),
+            SRgb8::new(0, 0x80, 0),
+            SRgb8::new(0, 0, 0x40),
+            SRgb8::new(0x10, 0x20, 0x30),
+        ];
+        let r = Raster::<SRgb8>::with_row_fn(8, 8, |y, row| {
+            for (x, p) in row.iter_mut().enumerate() {
+                *p = colors[(x + y as usize) % colors.len()];
+            }
+        });
+        let p = Palette::from_raster(&r, 16);
+        assert_eq!(p.len(), 4);
+        for c in colors {
+            assert!(p.colors().contains(&c), "{c:?}");
+        }
+        let p = Palette::from_raster(&r, 2);
+        assert_eq!(p.len(), 2);
+        let p = Palette::from_raster(&Raster::<Rgba8p>::with_clear(4, 4), 4);
+        assert_eq!(p.colors(), [SRgb8::new(0, 0, 0)]);
+        assert!(Palette::from_raster(&r, 0).is_empty());
+    }
+
+    #[test]
+    fn median_cut() {
+        let r = Raster::<SGray8>::with_row_fn(256, 1, |_, row| {
+            for (x, p) in row.iter_mut().enumerate() {
+                *p = SGray8::new(x as u8);
+            }
+        });
+        let p = Palette::from_raster(&r, 4);
+        let mut v: Vec<u8> =
+            p.colors().iter().map(|c| c.one().into()).collect();
+        v.sort();
+        assert_eq!(v, [32, 96, 160, 224]);
+    }
+
+    #[test]
+    fn quantize_gradient() {
+        let mut palette = Palette::new(2);
+        palette.set_entry(SRgb8::new(0, 0, 0));
+        palette.set_entry(SRgb8::new(0xFF, 0xFF, 0xFF));
+        let r = Raster::<SGray8>::with_row_fn(64, 16, |_, row| {
+            for (x, p) in row.iter_mut().enumerate() {
+                *p = SGray8::new((x * 255 / 63) as u8);
+            }
+        });
+        let count = |r: &Raster<Gray8>, x0: i32, x1: i32, e: u8| {
+            r.rows((x0, 0, (x1 - x0) as u32, 16))
+                .flatten()
+                .filter(|p| **p == Gray8::new(e))
+                .count()
+        };
+        for dither in [Dither::FloydSteinberg, Dither::Ordered] {
+            let q = r.quantize(&palette, dither);
+            assert!(count(&q, 24, 40, 0) > 64, "{dither:?}");
+            assert!(count(&q, 24, 40, 1) > 64, "{dither:?}");
+            assert_eq!(count(&q, 0, 2, 1), 0, "{dither:?}");
+            assert_eq!(count(&q, 62, 64, 0), 0, "{dither:?}");
+        }
+        let q = r.quantize(&palette, Dither::None);
+        assert_eq!(count(&q, 0, 32, 0), 16 * 32);
+        assert_eq!(count(&q, 32, 64, 1), 16 * 32);
+    }
+
+    #[test]
+    fn nearest() {
+        let mut p = Palette::new(4);
+        p.set_entry(SRgb8::new(0, 0, 0));
+        p.set_entry(SRgb8::new(0x80, 0x80, 0x80));
+        p.set_entry(SRgb8::new(0, 0, 0xFF));
+        assert_eq!(p.nearest(SRgb8::new(0x10, 0x08, 0x20)), 0);
+        assert_eq!(p.nearest(SRgb8::new(0x20, 0x20, 0xC0)), 2);
+        assert_eq!(p.nearest(SGray8::new(0x70)), 1);
+        // equidistant from entries 0 and 1
+        assert_eq!(p.nearest(SRgb8::new(0x40, 0x40, 0x40)), 0);
+    }
+
     #[test]
     fn matching() {
         let mut p = Palette::new(8);
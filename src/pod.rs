@@ -0,0 +1,92 @@
+// pod.rs       bytemuck support (optional feature).
+//
+// Copyright (c) 2024  Douglas P Lau
+//
+//! `Pod` and `Zeroable` are implemented for channels and pixels, which allows
+//! safe casting of pixel slices with `bytemuck`.
+//!
+//! Note: any `f32` or `f64` bit pattern is memory-safe, but `Ch32` and `Ch64`
+//! values cast from other data must be between 0 and 1 (inclusive).
+use crate::chan::{Alpha, Ch16, Ch32, Ch64, Ch8, Channel, Gamma};
+use crate::el::{Pix1, Pix2, Pix3, Pix4, Pix5};
+use crate::ColorModel;
+use bytemuck::{Pod, Zeroable};
+
+// SAFETY: channels are `repr(transparent)` wrappers of `u8`, `u16`, `f32` or
+// `f64`
+
+unsafe impl Zeroable for Ch8 {}
+
+unsafe impl Pod for Ch8 {}
+
+unsafe impl Zeroable for Ch16 {}
+
+unsafe impl Pod for Ch16 {}
+
+unsafe impl Zeroable for Ch32 {}
+
+unsafe impl Pod for Ch32 {}
+
+unsafe impl Zeroable for Ch64 {}
+
+unsafe impl Pod for Ch64 {}
+
+/// Implement `Pod` and `Zeroable` for a pixel type
+macro_rules! impl_pod {
+    ($pix:ident) => {
+        // SAFETY: pixels are `repr(C)`, containing only an array of channels
+        // and zero-sized `PhantomData` fields, so there is no padding
+        unsafe impl<C, M, A, G> Zeroable for $pix<C, M, A, G>
+        where
+            C: Channel + Pod,
+            M: ColorModel,
+            A: Alpha,
+            G: Gamma,
+        {
+        }
+
+        unsafe impl<C, M, A, G> Pod for $pix<C, M, A, G>
+        where
+            C: Channel + Pod,
+            M: ColorModel,
+            A: Alpha,
+            G: Gamma,
+        {
+        }
+    };
+}
+
+impl_pod!(Pix1);
+impl_pod!(Pix2);
+impl_pod!(Pix3);
+impl_pod!(Pix4);
+impl_pod!(Pix5);
+
+#[cfg(test)]
+mod test {
+    use crate::gray::*;
+    use crate::rgb::*;
+    use crate::*;
+
+    #[test]
+    fn cast_rgba8() {
+        let mut r = Raster::with_color(2, 2, Rgba8::new(1, 2, 3, 4));
+        *r.pixel_mut(1, 1) = Rgba8::new(5, 6, 7, 8);
+        let bytes: &[u8] = bytemuck::cast_slice(r.pixels());
+        assert_eq!(bytes.len(), 16);
+        assert_eq!(bytes[..4], [1, 2, 3, 4]);
+        assert_eq!(bytes[12..], [5, 6, 7, 8]);
+        let pixels: &[Rgba8] = bytemuck::cast_slice(bytes);
+        assert_eq!(pixels, r.pixels());
+    }
+
+    #[test]
+    fn cast_16_32() {
+        let r = Raster::with_color(3, 1, Graya16::new(0x1234, 0xFFFF));
+        let v: &[u16] = bytemuck::cast_slice(r.pixels());
+        assert_eq!(v, [0x1234, 0xFFFF, 0x1234, 0xFFFF, 0x1234, 0xFFFF]);
+        let p: Rgb32 = bytemuck::cast([0.25_f32, 0.5, 1.0]);
+        assert_eq!(p, Rgb32::new(0.25, 0.5, 1.0));
+        assert_eq!(<Rgba8 as bytemuck::Zeroable>::zeroed(), Rgba8::default());
+    }
+}
@@ -13,10 +13,10 @@
 //! Hwb::hue(Rgb8::new(255, 255, 255));
 //! ```
 use crate::chan::{
-    Alpha, Ch16, Ch32, Ch8, Channel, Gamma, Linear, Premultiplied, Srgb,
+    Alpha, Ch16, Ch32, Ch64, Ch8, Channel, Gamma, Linear, Premultiplied, Srgb,
     Straight,
 };
-use crate::el::{Pix1, Pix2, Pix3, Pix4};
+use crate::el::{Pix1, Pix2, Pix3, Pix4, Pix5};
 use crate::ColorModel;
 use std::any::Any;
 
@@ -29,6 +29,8 @@ impl Sealed for Ch16 {}
 
 impl Sealed for Ch32 {}
 
+impl Sealed for Ch64 {}
+
 impl Sealed for Straight {}
 
 impl Sealed for Premultiplied {}
@@ -72,3 +74,12 @@ where
     G: Gamma,
 {
 }
+
+impl<C, M, A, G> Sealed for Pix5<C, M, A, G>
+where
+    C: Channel,
+    M: ColorModel,
+    A: Alpha,
+    G: Gamma,
+{
+}
@@ -3,22 +3,109 @@
 // Copyright (c) 2017-2024  Douglas P Lau
 // Copyright (c) 2019-2020  Jeron Aldaron Lau
 //
-use crate::chan::{Ch16, Ch8, Linear, Premultiplied};
-use crate::el::Pixel;
+use crate::access;
+use crate::borrowed::BorrowedRaster;
+use crate::chan::{
+    Alpha, Ch16, Ch32, Ch8, Channel, Gamma, Linear, Premultiplied, Srgb,
+    Straight,
+};
+use crate::el::{
+    composite_premultiplied, from_linear_rgba, to_linear_rgba, PixRgba, Pixel,
+};
+use crate::gray::Gray;
 use crate::matte::Matte;
-use crate::ops::Blend;
+use crate::model::ColorModel;
+use crate::ops::{Blend, Multiply, Plus, SrcOver, Subtract};
+use crate::rgb::{Rgb, SRgb8, SRgba8};
 use std::convert::TryFrom;
-use std::ops::Range;
-use std::slice::{from_raw_parts_mut, ChunksExact, ChunksExactMut};
+use std::fmt;
+use std::ops::{Add, Range};
+use std::ptr::slice_from_raw_parts_mut;
+use std::slice::{ChunksExact, ChunksExactMut};
 
 /// Message for width too big
-const WIDTH_TOO_BIG: &str = "Raster width too big";
+pub(crate) const WIDTH_TOO_BIG: &str = "Raster width too big";
 
 /// Message for height too big
-const HEIGHT_TOO_BIG: &str = "Raster height too big";
+pub(crate) const HEIGHT_TOO_BIG: &str = "Raster height too big";
 
 /// Message for raster too big
-const TOO_BIG: &str = "Raster too big";
+pub(crate) const TOO_BIG: &str = "Raster too big";
+
+/// Message for invalid run-length encoded data
+const INVALID_RLE: &str = "Invalid RLE data";
+
+/// Message for dimensions which are not powers of two
+const NOT_POW2: &str = "Morton order requires power-of-two dimensions";
+
+/// FNV-1a 64-bit offset basis
+const FNV_OFFSET: u64 = 0xCBF2_9CE4_8422_2325;
+
+/// FNV-1a 64-bit prime
+const FNV_PRIME: u64 = 0x0000_0100_0000_01B3;
+
+/// Convert a channel value to a 16-bit integer
+fn chan_u16<C: Channel>(c: C) -> u16 {
+    (c.to_f32() * 65535.0).round() as u16
+}
+
+/// Check if a channel type is floating point (`Ch32` or `Ch64`)
+fn chan_is_float<C: Channel>() -> bool {
+    std::mem::size_of::<C>() >= 4
+}
+
+/// Get the maximum integer value of a channel for summing
+///
+/// This is the native maximum for 8-bit channels, and 16-bit for all others.
+fn chan_int_max<C: Channel>() -> f32 {
+    if std::mem::size_of::<C>() == 1 {
+        255.0
+    } else {
+        65535.0
+    }
+}
+
+/// Convert a channel value to an integer for summing
+fn chan_int<C: Channel>(c: C) -> u64 {
+    (c.to_f32() * chan_int_max::<C>()).round() as u64
+}
+
+/// Check if every channel of two pixels differs by no more than `tol`
+fn within_tol<P: Pixel>(p0: &P, p1: &P, tol: f32) -> bool {
+    p0.channels()
+        .iter()
+        .zip(p1.channels())
+        .all(|(c0, c1)| (c0.to_f32() - c1.to_f32()).abs() <= tol)
+}
+
+/// Update an FNV-1a hash with a slice of bytes
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(hash, |h, b| (h ^ u64::from(*b)).wrapping_mul(FNV_PRIME))
+}
+
+/// Get the Morton (Z-order) index of a pixel
+///
+/// Bits of `x` and `y` are interleaved (starting with `x`), until the bits of
+/// the smaller dimension are exhausted.
+fn morton_index(x: u32, y: u32, width: u32, height: u32) -> usize {
+    let mut i = 0;
+    let mut shift = 0;
+    let mut bit = 1;
+    while bit < width || bit < height {
+        if bit < width {
+            i |= usize::from(x & bit != 0) << shift;
+            shift += 1;
+        }
+        if bit < height {
+            i |= usize::from(y & bit != 0) << shift;
+            shift += 1;
+        }
+        bit <<= 1;
+    }
+    i
+}
 
 /// Image arranged as a rectangular array of pixels.  Rows are ordered top to
 /// bottom, and pixels within rows are left to right.
@@ -30,6 +117,10 @@ const TOO_BIG: &str = "Raster too big";
 /// * [with_pixels](#method.with_pixels)
 /// * [with_u8_buffer](#method.with_u8_buffer)
 /// * [with_u16_buffer](#method.with_u16_buffer)
+/// * [with_row_fn](#method.with_row_fn)
+/// * [with_2d](#method.with_2d)
+/// * [with_rows](#method.with_rows)
+/// * [with_rgba_iter](#method.with_rgba_iter)
 ///
 /// ### Working with byte buffers
 ///
@@ -99,6 +190,18 @@ pub struct RowsMut<'a, P: Pixel> {
 /// let r = Raster::<SRgb8>::with_clear(100, 100);
 /// let reg = r.region(); // (0, 0, 100, 100)
 /// ```
+/// ### Allocate a buffer for a clipped region
+/// ```
+/// use pix::rgb::SRgb8;
+/// use pix::Raster;
+///
+/// let r = Raster::<SRgb8>::with_clear(100, 100);
+/// let reg = r.intersection((80, -10, 50, 50));
+/// assert_eq!((reg.left(), reg.top()), (80, 0));
+/// assert_eq!((reg.right(), reg.bottom()), (100, 40));
+/// let mut buf = Raster::<SRgb8>::with_clear(reg.width(), reg.height());
+/// buf.copy_raster((), &r, reg);
+/// ```
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub struct Region {
     x: i32,
@@ -107,6 +210,41 @@ pub struct Region {
     height: i32,
 }
 
+/// Filter for resampling a [Raster](struct.Raster.html).
+///
+/// Used by [resample_into](struct.Raster.html#method.resample_into).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Filter {
+    /// Nearest neighbor (pixels are replicated or dropped)
+    #[default]
+    Nearest,
+    /// Bilinear interpolation
+    Bilinear,
+}
+
+impl<P: Pixel> fmt::Debug for Raster<P> {
+    /// Format a summary, without pixel data.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Raster<{}> {{ {}x{}, {} px }}",
+            std::any::type_name::<P>(),
+            self.width,
+            self.height,
+            self.pixels.len()
+        )
+    }
+}
+
+impl<P: Pixel> PartialEq for Raster<P> {
+    /// Check if dimensions and all pixels are equal.
+    fn eq(&self, other: &Self) -> bool {
+        self.width == other.width
+            && self.height == other.height
+            && self.pixels == other.pixels
+    }
+}
+
 impl<P: Pixel> From<Raster<P>> for Box<[P]> {
     /// Get internal pixel data as boxed slice.
     fn from(raster: Raster<P>) -> Self {
@@ -132,7 +270,7 @@ where
         let slice = Box::<[P]>::into_raw(pixels);
         let buffer: Box<[u8]> = unsafe {
             let ptr = (*slice).as_mut_ptr() as *mut u8;
-            Box::from_raw(from_raw_parts_mut(ptr, capacity))
+            Box::from_raw(slice_from_raw_parts_mut(ptr, capacity))
         };
         buffer
     }
@@ -149,7 +287,7 @@ where
         let slice = Box::<[P]>::into_raw(pixels);
         let buffer: Box<[u16]> = unsafe {
             let ptr = (*slice).as_mut_ptr() as *mut u16;
-            Box::from_raw(from_raw_parts_mut(ptr, capacity))
+            Box::from_raw(slice_from_raw_parts_mut(ptr, capacity))
         };
         buffer
     }
@@ -225,13 +363,7 @@ impl<P: Pixel> Raster<P> {
         P::Chan: From<S::Chan>,
     {
         let mut r = Raster::with_clear(src.width(), src.height());
-        let srows = src.rows(());
-        let drows = r.rows_mut(());
-        for (drow, srow) in drows.zip(srows) {
-            for (d, s) in drow.iter_mut().zip(srow) {
-                *d = s.convert();
-            }
-        }
+        src.convert_into(&mut r);
         r
     }
 
@@ -309,7 +441,7 @@ impl<P: Pixel> Raster<P> {
         let slice = Box::<[u8]>::into_raw(buffer);
         let pixels: Box<[P]> = unsafe {
             let ptr = (*slice).as_mut_ptr() as *mut P;
-            Box::from_raw(from_raw_parts_mut(ptr, len))
+            Box::from_raw(slice_from_raw_parts_mut(ptr, len))
         };
         Raster {
             width,
@@ -349,7 +481,7 @@ impl<P: Pixel> Raster<P> {
         let slice = Box::<[u16]>::into_raw(buffer);
         let pixels: Box<[P]> = unsafe {
             let ptr = (*slice).as_mut_ptr() as *mut P;
-            Box::from_raw(from_raw_parts_mut(ptr, len))
+            Box::from_raw(slice_from_raw_parts_mut(ptr, len))
         };
         Raster {
             width,
@@ -358,6 +490,337 @@ impl<P: Pixel> Raster<P> {
         }
     }
 
+    /// Construct a `Raster` by filling each row with a closure.
+    ///
+    /// * `width` Width of `Raster`.
+    /// * `height` Height of `Raster`.
+    /// * `f` Closure called with the *y* coordinate and a mutable slice of
+    ///   pixels for each row, from top to bottom.
+    ///
+    /// This allows decoding an image in place, row by row, without allocating
+    /// a separate buffer for each row.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` or `height` is greater than `std::i32::MAX`.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::gray::SGray8;
+    /// use pix::Raster;
+    ///
+    /// let r = Raster::with_row_fn(16, 16, |y, row: &mut [SGray8]| {
+    ///     for p in row.iter_mut() {
+    ///         *p = SGray8::new(y as u8 * 16);
+    ///     }
+    /// });
+    /// assert_eq!(r.pixel(4, 5), SGray8::new(80));
+    /// ```
+    pub fn with_row_fn<F>(width: u32, height: u32, mut f: F) -> Self
+    where
+        F: FnMut(i32, &mut [P]),
+    {
+        let mut r = Raster::with_clear(width, height);
+        for (y, row) in r.rows_mut(()).enumerate() {
+            f(y as i32, row);
+        }
+        r
+    }
+
+    /// Construct a `Raster` from rows of pixels.
+    ///
+    /// * `rows` Slice of rows, from top to bottom.
+    ///
+    /// The width is the length of the first row.
+    ///
+    /// # Panics
+    ///
+    /// Panics if rows have different lengths, or the dimensions are too big.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::gray::SGray8;
+    /// use pix::Raster;
+    ///
+    /// let (a, b) = (SGray8::new(0x00), SGray8::new(0xFF));
+    /// let r = Raster::with_2d(&[[a, b, a], [b, a, b]]);
+    /// assert_eq!(r.width(), 3);
+    /// assert_eq!(r.height(), 2);
+    /// assert_eq!(r.pixel(1, 0), b);
+    /// ```
+    pub fn with_2d<R>(rows: &[R]) -> Self
+    where
+        R: AsRef<[P]>,
+    {
+        let width = rows.first().map_or(0, |row| row.as_ref().len());
+        let mut pixels = Vec::with_capacity(width * rows.len());
+        for row in rows {
+            let row = row.as_ref();
+            assert_eq!(row.len(), width, "Row lengths differ");
+            pixels.extend_from_slice(row);
+        }
+        let width = u32::try_from(width).expect(WIDTH_TOO_BIG);
+        let height = u32::try_from(rows.len()).expect(HEIGHT_TOO_BIG);
+        Self::with_pixels(width, height, pixels)
+    }
+
+    /// Construct a `Raster` from an `Iterator` of rows.
+    ///
+    /// * `width` Width of `Raster`.
+    /// * `height` Height of `Raster`.
+    /// * `rows` Rows of pixels, from top to bottom (such as `Vec<P>` or
+    ///   `&[P]`).
+    ///
+    /// This is useful for streaming from a scanline decoder.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any row length is not `width`, the number of rows is not
+    /// `height`, or the dimensions are too big.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::gray::SGray8;
+    /// use pix::Raster;
+    ///
+    /// let rows = (0..4).map(|y| vec![SGray8::new(y * 16); 8]);
+    /// let r = Raster::with_rows(8, 4, rows);
+    /// assert_eq!(r.pixel(5, 2), SGray8::new(32));
+    /// ```
+    pub fn with_rows<I>(width: u32, height: u32, rows: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[P]>,
+    {
+        let w = i32::try_from(width).expect(WIDTH_TOO_BIG) as usize;
+        let h = i32::try_from(height).expect(HEIGHT_TOO_BIG) as usize;
+        let mut pixels = Vec::with_capacity(w * h);
+        let mut count = 0;
+        for row in rows {
+            let row = row.as_ref();
+            assert_eq!(row.len(), w, "Row length must equal width");
+            pixels.extend_from_slice(row);
+            count += 1;
+        }
+        assert_eq!(count, h, "Row count must equal height");
+        Self::with_pixels(width, height, pixels)
+    }
+
+    /// Construct a `Raster` from an `Iterator` of RGBA tuples.
+    ///
+    /// * `width` Width of `Raster`.
+    /// * `height` Height of `Raster`.
+    /// * `iter` `Iterator` of (*red*, *green*, *blue*, *alpha*) tuples.
+    ///
+    /// The tuples are [SRgba8] values (*sRGB* gamma, *straight* alpha), which
+    /// are converted to the `Raster` pixel format.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `iter` does not yield exactly `width` * `height` items, or
+    /// the dimensions are too big.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::SRgb8;
+    /// use pix::Raster;
+    ///
+    /// let data = [(0xFF, 0, 0, 0xFF), (0, 0xFF, 0, 0xFF)];
+    /// let r = Raster::<SRgb8>::with_rgba_iter(2, 1, data.into_iter());
+    /// assert_eq!(r.pixel(1, 0), SRgb8::new(0, 0xFF, 0));
+    /// ```
+    /// [SRgba8]: rgb/type.SRgba8.html
+    pub fn with_rgba_iter<I>(width: u32, height: u32, iter: I) -> Self
+    where
+        I: Iterator<Item = (u8, u8, u8, u8)>,
+        P::Chan: From<Ch8>,
+    {
+        let pixels = iter
+            .map(|(r, g, b, a)| SRgba8::new::<u8>(r, g, b, a).convert())
+            .collect::<Vec<P>>();
+        Self::with_pixels(width, height, pixels)
+    }
+
+    /// Convert pixels into an existing `Raster`.
+    ///
+    /// This is like [with_raster](#method.with_raster), but avoids allocating
+    /// a new `Raster` each time.
+    ///
+    /// * `D` `Pixel` format of destination `Raster`.
+    /// * `dst` Destination `Raster`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dst` dimensions do not match `self`.
+    ///
+    /// ### Convert from SRgb8 to SRgba16
+    /// ```
+    /// use pix::rgb::{SRgb8, SRgba16};
+    /// use pix::Raster;
+    ///
+    /// let r0 = Raster::<SRgb8>::with_clear(50, 50);
+    /// let mut r1 = Raster::<SRgba16>::with_clear(50, 50);
+    /// // in a render loop
+    /// r0.convert_into(&mut r1);
+    /// ```
+    pub fn convert_into<D>(&self, dst: &mut Raster<D>)
+    where
+        D: Pixel,
+        D::Chan: From<P::Chan>,
+    {
+        assert_eq!(self.width, dst.width);
+        assert_eq!(self.height, dst.height);
+        for (d, s) in dst.pixels.iter_mut().zip(self.pixels.iter()) {
+            *d = s.convert();
+        }
+    }
+
+    /// Construct a `Raster` from packed `u32` pixels (0xAARRGGBB).
+    ///
+    /// * `width` Width of `Raster`.
+    /// * `height` Height of `Raster`.
+    /// * `argb` Pixel data, with *alpha* in the most significant byte.
+    ///
+    /// This format is used by windowing libraries such as `minifb` and
+    /// `softbuffer`.  Byte order is independent of host endianness.
+    ///
+    /// # Panics
+    ///
+    /// * If `width` or `height` is greater than `std::i32::MAX`
+    /// * If `argb` length is not equal to `width` * `height`
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::bgr::SBgra8;
+    /// use pix::Raster;
+    ///
+    /// let buf = vec![0xFF00_80FF; 4 * 4];
+    /// let r = Raster::<SBgra8>::from_u32_argb(4, 4, &buf);
+    /// assert_eq!(r.pixel(0, 0), SBgra8::new(0xFF, 0x80, 0x00, 0xFF));
+    /// ```
+    pub fn from_u32_argb(width: u32, height: u32, argb: &[u32]) -> Self
+    where
+        P: Pixel<Chan = Ch8>,
+    {
+        let mut r = Raster::with_clear(width, height);
+        assert_eq!(r.pixels.len(), argb.len());
+        for (p, v) in r.pixels.iter_mut().zip(argb) {
+            let red = (v >> 16) as u8;
+            let green = (v >> 8) as u8;
+            let blue = *v as u8;
+            let alpha = (v >> 24) as u8;
+            *p = PixRgba::<P>::new(red, green, blue, alpha).convert();
+        }
+        r
+    }
+
+    /// Construct a `Raster` from run-length encoded data.
+    ///
+    /// * `width` Width of `Raster`.
+    /// * `height` Height of `Raster`.
+    /// * `rle` Data encoded with [to_rle](#method.to_rle).
+    ///
+    /// # Panics
+    ///
+    /// * If `width` or `height` is greater than `std::i32::MAX`
+    /// * If `rle` is not valid for the given dimensions
+    pub fn from_rle(width: u32, height: u32, rle: &[u8]) -> Self
+    where
+        P: Pixel<Chan = Ch8>,
+    {
+        let mut r = Raster::with_clear(width, height);
+        let size = std::mem::size_of::<P>();
+        let len = width as usize * size;
+        let mut pos = 0;
+        if len > 0 {
+            for row in r.as_u8_slice_mut().chunks_exact_mut(len) {
+                let mut x = 0;
+                while x < len {
+                    let count = usize::from(*rle.get(pos).expect(INVALID_RLE));
+                    let px =
+                        rle.get(pos + 1..pos + 1 + size).expect(INVALID_RLE);
+                    assert!(
+                        count > 0 && x + count * size <= len,
+                        "{INVALID_RLE}"
+                    );
+                    for d in row[x..x + count * size].chunks_exact_mut(size) {
+                        d.copy_from_slice(px);
+                    }
+                    x += count * size;
+                    pos += 1 + size;
+                }
+            }
+        }
+        assert_eq!(pos, rle.len(), "{INVALID_RLE}");
+        r
+    }
+
+    /// Create a `Raster` from pixels in Morton (Z-order) layout.
+    ///
+    /// This is the inverse of [to_morton_order](#method.to_morton_order).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` or `height` is not a power of two, or the length of
+    /// `pixels` is not `width` * `height`.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::gray::SGray8;
+    /// use pix::Raster;
+    ///
+    /// let p = [1, 2, 3, 4].map(SGray8::new);
+    /// let r = Raster::from_morton_order(2, 2, &p);
+    /// assert_eq!(r.pixels(), &p);
+    /// ```
+    pub fn from_morton_order(width: u32, height: u32, pixels: &[P]) -> Self {
+        assert!(width.is_power_of_two(), "{NOT_POW2}");
+        assert!(height.is_power_of_two(), "{NOT_POW2}");
+        let mut r = Raster::with_clear(width, height);
+        assert_eq!(pixels.len(), r.pixels.len());
+        let mut i = 0;
+        for y in 0..height {
+            for x in 0..width {
+                r.pixels[i] = pixels[morton_index(x, y, width, height)];
+                i += 1;
+            }
+        }
+        r
+    }
+
+    /// Borrow a mutable slice of pixels as a raster.
+    ///
+    /// * `width` Width of raster.
+    /// * `height` Height of raster.
+    /// * `pixels` Pixel data, such as a window's back buffer.
+    ///
+    /// The returned [BorrowedRaster](struct.BorrowedRaster.html) has the same
+    /// drawing methods as `Raster`, but does not own the pixel data.  This
+    /// allows rendering directly into a buffer without copying.
+    ///
+    /// # Panics
+    ///
+    /// * If `width` or `height` is greater than `std::i32::MAX`
+    /// * If `pixels` length is not equal to `width` * `height`
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::SRgb8;
+    /// use pix::Raster;
+    ///
+    /// let mut buf = vec![SRgb8::default(); 100 * 100];
+    /// let mut r = Raster::from_slice_mut(100, 100, &mut buf);
+    /// r.copy_color((20, 20, 10, 10), SRgb8::new(0xFF, 0xFF, 0x00));
+    /// assert_eq!(buf[25 * 100 + 25], SRgb8::new(0xFF, 0xFF, 0x00));
+    /// ```
+    pub fn from_slice_mut(
+        width: u32,
+        height: u32,
+        pixels: &mut [P],
+    ) -> BorrowedRaster<'_, P> {
+        BorrowedRaster::new(width, height, pixels)
+    }
+
     /// Get width of `Raster`.
     pub fn width(&self) -> u32 {
         self.width as u32
@@ -391,6 +854,62 @@ impl<P: Pixel> Raster<P> {
         &mut self.pixels[i]
     }
 
+    /// Get one pixel, or `None` if coordinates are outside the `Raster`.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::gray::Gray8;
+    /// use pix::Raster;
+    ///
+    /// let r = Raster::<Gray8>::with_clear(4, 4);
+    /// assert_eq!(r.get_pixel(3, 3), Some(Gray8::new(0)));
+    /// assert_eq!(r.get_pixel(-1, 0), None);
+    /// assert_eq!(r.get_pixel(4, 0), None);
+    /// ```
+    pub fn get_pixel(&self, x: i32, y: i32) -> Option<P> {
+        self.pixel_index(x, y).map(|i| self.pixels[i])
+    }
+
+    /// Get a mutable pixel, or `None` if coordinates are outside the
+    /// `Raster`.
+    pub fn get_pixel_mut(&mut self, x: i32, y: i32) -> Option<&mut P> {
+        self.pixel_index(x, y).map(|i| &mut self.pixels[i])
+    }
+
+    /// Get one row of pixels.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::gray::SGray8;
+    /// use pix::Raster;
+    ///
+    /// let r = Raster::with_color(4, 4, SGray8::new(0x80));
+    /// assert_eq!(r.row(2), [SGray8::new(0x80); 4]);
+    /// ```
+    pub fn row(&self, y: i32) -> &[P] {
+        assert!(y >= 0 && y < self.height);
+        let width = self.width as usize;
+        let start = y as usize * width;
+        &self.pixels[start..start + width]
+    }
+
+    /// Get one mutable row of pixels.
+    pub fn row_mut(&mut self, y: i32) -> &mut [P] {
+        assert!(y >= 0 && y < self.height);
+        let width = self.width as usize;
+        let start = y as usize * width;
+        &mut self.pixels[start..start + width]
+    }
+
+    /// Get index of a pixel, if coordinates are within the `Raster`.
+    fn pixel_index(&self, x: i32, y: i32) -> Option<usize> {
+        if (0..self.width).contains(&x) && (0..self.height).contains(&y) {
+            Some((self.width * y + x) as usize)
+        } else {
+            None
+        }
+    }
+
     /// Get a slice of all pixels.
     pub fn pixels(&self) -> &[P] {
         &self.pixels
@@ -404,21 +923,22 @@ impl<P: Pixel> Raster<P> {
     /// Get an `Iterator` of rows within a `Raster`.
     ///
     /// * `reg` Region of the Raster to iterate.
-    pub fn rows<R>(&self, reg: R) -> Rows<P>
+    pub fn rows<R>(&self, reg: R) -> Rows<'_, P>
     where
         R: Into<Region>,
     {
-        Rows::new(self, self.intersection(reg.into()))
+        Rows::new(&self.pixels, self.width, self.intersection(reg.into()))
     }
 
     /// Get an `Iterator` of mutable rows within a `Raster`.
     ///
     /// * `reg` Region of the Raster to iterate.
-    pub fn rows_mut<R>(&mut self, reg: R) -> RowsMut<P>
+    pub fn rows_mut<R>(&mut self, reg: R) -> RowsMut<'_, P>
     where
         R: Into<Region>,
     {
-        RowsMut::new(self, self.intersection(reg.into()))
+        let reg = self.intersection(reg.into());
+        RowsMut::new(&mut self.pixels, self.width, reg)
     }
 
     /// Get `Region` of entire `Raster`.
@@ -435,11 +955,44 @@ impl<P: Pixel> Raster<P> {
         reg.intersection(self.region())
     }
 
+    /// Fill a region of the `Raster` using a closure.
+    ///
+    /// * `reg` Region within `self`.  It can be a `Region` struct, tuple of
+    ///   (*x*, *y*, *width*, *height*) or the unit type `()`.  Using `()` has
+    ///   the same result as `Raster::region()`.
+    /// * `f` Closure called with (*x*, *y*) coordinates of each pixel within
+    ///   the clipped region, returning its new value.
+    ///
+    /// Coordinates are relative to the `Raster`, not the region.
+    ///
+    /// ### Checkerboard
+    /// ```
+    /// use pix::gray::SGray8;
+    /// use pix::Raster;
+    ///
+    /// let mut r = Raster::with_clear(16, 16);
+    /// r.fill_with((), |x, y| SGray8::new(((x + y) & 1) as u8 * 255));
+    /// ```
+    pub fn fill_with<R, F>(&mut self, reg: R, mut f: F)
+    where
+        R: Into<Region>,
+        F: FnMut(i32, i32) -> P,
+    {
+        let reg = self.intersection(reg.into());
+        let (left, top) = (reg.left(), reg.top());
+        for (y, row) in self.rows_mut(reg).enumerate() {
+            let y = top + y as i32;
+            for (x, p) in row.iter_mut().enumerate() {
+                *p = f(left + x as i32, y);
+            }
+        }
+    }
+
     /// Copy a color to a region of the `Raster`.
     ///
     /// * `reg` Region within `self`.  It can be a `Region` struct, tuple of
-    ///         (*x*, *y*, *width*, *height*) or the unit type `()`.  Using
-    ///         `()` has the same result as `Raster::region()`.
+    ///   (*x*, *y*, *width*, *height*) or the unit type `()`.  Using `()` has
+    ///   the same result as `Raster::region()`.
     /// * `clr` Source `Pixel` color.
     ///
     /// ### Copy a color to a rectangle region
@@ -506,12 +1059,7 @@ impl<P: Pixel> Raster<P> {
         R0: Into<Region>,
         R1: Into<Region>,
     {
-        let (to, from) = self.clip_regions(to, src, from);
-        let srows = src.rows(from);
-        let drows = self.rows_mut(to);
-        for (drow, srow) in drows.zip(srows) {
-            P::copy_slice(drow, srow);
-        }
+        access::copy_raster(self, to.into(), src, from.into());
     }
 
     /// Clip `to` / `from` regions for source / destination rasters
@@ -526,585 +1074,4381 @@ impl<P: Pixel> Raster<P> {
         R1: Into<Region>,
         Q: Pixel,
     {
-        let (to, from) = (to.into(), from.into());
-        let (tx, ty) = (to.x.min(0).abs(), to.y.min(0).abs());
-        let (fx, fy) = (from.x.min(0).abs(), from.y.min(0).abs());
-        let to = self.intersection(to);
-        let from = src.intersection(from);
-        let width = to.width().min(from.width());
-        let height = to.height().min(from.height());
-        let to = Region::new(to.x + fx, to.y + fy, width, height);
-        let from = Region::new(from.x + tx, from.y + ty, width, height);
-        (to, from)
+        clip_regions(self.region(), to.into(), src.region(), from.into())
     }
 
-    /// Get view of pixels as a `u8` slice.
-    pub fn as_u8_slice(&self) -> &[u8] {
-        unsafe {
-            let (prefix, v, suffix) = self.pixels.align_to::<u8>();
-            debug_assert!(prefix.is_empty());
-            debug_assert!(suffix.is_empty());
-            v
+    /// Sample a pixel using bilinear interpolation.
+    ///
+    /// Coordinates are clamped to the `Raster` edges, so it must not be empty.
+    fn sample_bilinear(&self, x: f32, y: f32) -> P {
+        let x = x.clamp(0.0, (self.width - 1) as f32);
+        let y = y.clamp(0.0, (self.height - 1) as f32);
+        let x0 = x.floor() as i32;
+        let y0 = y.floor() as i32;
+        let x1 = (x0 + 1).min(self.width - 1);
+        let y1 = (y0 + 1).min(self.height - 1);
+        let tx = P::Chan::from(x - x0 as f32);
+        let ty = P::Chan::from(y - y0 as f32);
+        let p01 = self.pixel(x0, y1);
+        let p10 = self.pixel(x1, y0);
+        let p11 = self.pixel(x1, y1);
+        let mut p = self.pixel(x0, y0);
+        for (i, c) in p.channels_mut().iter_mut().enumerate() {
+            let top = c.lerp(p10.channels()[i], tx);
+            let bottom = p01.channels()[i].lerp(p11.channels()[i], tx);
+            *c = top.lerp(bottom, ty);
         }
+        p
     }
 
-    /// Get view of pixels as a mutable `u8` slice.
-    pub fn as_u8_slice_mut(&mut self) -> &mut [u8] {
-        unsafe {
-            let (prefix, v, suffix) = self.pixels.align_to_mut::<u8>();
-            debug_assert!(prefix.is_empty());
-            debug_assert!(suffix.is_empty());
-            v
-        }
-    }
-}
+    /// Get view of pixels as a `u8` slice.
+    ///
+    /// This is valid for all channel types: pixels are `repr(C)` arrays of
+    /// channels with no padding, and each channel is a transparent wrapper
+    /// of `u8`, `u16`, `f32` or `f64`.  Multi-byte channels are in
+    /// native-endian byte order.  The slice length is always the number of
+    /// pixels times `std::mem::size_of::<P>()`.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::SRgba32;
+    /// use pix::Raster;
+    ///
+    /// let r = Raster::<SRgba32>::with_clear(4, 4);
+    /// assert_eq!(r.as_u8_slice().len(), 4 * 4 * 16);
+    /// ```
+    pub fn as_u8_slice(&self) -> &[u8] {
+        let len = std::mem::size_of_val(&self.pixels[..]);
+        // SAFETY: u8 has alignment 1, and pixel types contain no padding
+        let (prefix, v, suffix) = unsafe { self.pixels.align_to::<u8>() };
+        assert!(prefix.is_empty() && suffix.is_empty() && v.len() == len);
+        v
+    }
 
-impl<P> Raster<P>
-where
-    P: Pixel<Alpha = Premultiplied, Gamma = Linear>,
-{
-    /// Composite a source color to a region of the `Raster`.
+    /// Get view of pixels as a mutable `u8` slice.
     ///
-    /// * `reg` Region within `self`.  It can be a `Region` struct, tuple of
-    ///         (*x*, *y*, *width*, *height*) or the unit type `()`.  Using
-    ///         `()` has the same result as `Raster::region()`.
-    /// * `clr` Source `Pixel` color.
-    /// * `op` Compositing operation.
+    /// See [as_u8_slice](#method.as_u8_slice) for layout details.  For `f32`
+    /// channels, values written must be between 0.0 and 1.0 (inclusive).
+    pub fn as_u8_slice_mut(&mut self) -> &mut [u8] {
+        let len = std::mem::size_of_val(&self.pixels[..]);
+        // SAFETY: u8 has alignment 1, and pixel types contain no padding
+        let (prefix, v, suffix) = unsafe { self.pixels.align_to_mut::<u8>() };
+        assert!(prefix.is_empty() && suffix.is_empty() && v.len() == len);
+        v
+    }
+
+    /// Get view of pixels as a `u16` slice.
+    ///
+    /// The slice contains every channel of every pixel, in native-endian
+    /// byte order.  This is the inverse of
+    /// [with_u16_buffer](#method.with_u16_buffer).
     ///
     /// ### Example
     /// ```
-    /// use pix::ops::SrcOver;
-    /// use pix::bgr::Bgra8p;
+    /// use pix::gray::SGraya16;
     /// use pix::Raster;
     ///
-    /// let mut r = Raster::with_color(100, 100, Bgra8p::new(99, 0, 99, 255));
-    /// let clr = Bgra8p::new(200, 200, 0, 128);
-    /// r.composite_color((20, 40, 25, 50), clr, SrcOver);
+    /// let r = Raster::with_color(2, 2, SGraya16::new(0x1234, 0xFFFF));
+    /// assert_eq!(r.as_u16_slice()[..2], [0x1234, 0xFFFF]);
     /// ```
-    pub fn composite_color<R, O>(&mut self, reg: R, clr: P, op: O)
+    pub fn as_u16_slice(&self) -> &[u16]
     where
-        R: Into<Region>,
-        O: Blend,
+        P: Pixel<Chan = Ch16>,
     {
-        let reg = self.intersection(reg.into());
-        let width = reg.width();
-        let height = reg.height();
-        if width > 0 && height > 0 {
-            let drows = self.rows_mut(reg);
-            for drow in drows {
-                P::composite_color(drow, &clr, op);
-            }
-        }
+        // SAFETY: Ch16 is a transparent wrapper of u16
+        let (prefix, v, suffix) = unsafe { self.pixels.align_to::<u16>() };
+        assert!(prefix.is_empty() && suffix.is_empty());
+        v
     }
 
-    /// Composite from a matte `Raster` and color.
+    /// Get view of pixels as a mutable `u16` slice.
     ///
-    /// * `to` Region within `self` (destination).
-    /// * `src` Source `Raster` matte.
-    /// * `from` Region within source `Raster`.
-    /// * `clr` Color to apply to the matte.
-    /// * `op` Compositing operation.
+    /// This allows decoding 16-bit image data directly into a `Raster`,
+    /// without a separate buffer.
+    pub fn as_u16_slice_mut(&mut self) -> &mut [u16]
+    where
+        P: Pixel<Chan = Ch16>,
+    {
+        // SAFETY: Ch16 is a transparent wrapper of u16
+        let (prefix, v, suffix) = unsafe { self.pixels.align_to_mut::<u16>() };
+        assert!(prefix.is_empty() && suffix.is_empty());
+        v
+    }
+
+    /// Get an iterator of pixels which differ from another `Raster`.
     ///
-    /// `to` / `from` can be `Region` structs, tuples of (*x*, *y*, *width*,
-    /// *height*) or the unit type `()`.  Using `()` has the same result as
-    /// `Raster::region()`.
+    /// * `other` `Raster` to compare; must have the same dimensions.
+    ///
+    /// Each item is a tuple of (*x*, *y*, *pixel*), where *pixel* is the value
+    /// from `other`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `other` dimensions do not match `self`.
     ///
     /// ### Example
     /// ```
-    /// use pix::matte::Matte8;
-    /// use pix::ops::SrcOver;
-    /// use pix::rgb::Rgba8p;
+    /// use pix::rgb::SRgb8;
     /// use pix::Raster;
     ///
-    /// let mut r0 = Raster::with_clear(100, 100);
-    /// let r1 = Raster::with_color(10, 10, Matte8::new(37));
-    /// // ... load image data
-    /// let clr = Rgba8p::new(50, 100, 150, 200);
-    /// r0.composite_matte((30, 50, 10, 10), &r1, (), clr, SrcOver);
+    /// let r0 = Raster::<SRgb8>::with_clear(10, 10);
+    /// let mut r1 = r0.clone();
+    /// *r1.pixel_mut(3, 7) = SRgb8::new(0x80, 0x80, 0x80);
+    /// let changed: Vec<_> = r0.changed_pixels(&r1).collect();
+    /// assert_eq!(changed, vec![(3, 7, SRgb8::new(0x80, 0x80, 0x80))]);
     /// ```
-    pub fn composite_matte<R0, R1, M, O>(
-        &mut self,
-        to: R0,
-        src: &Raster<M>,
-        from: R1,
-        clr: P,
-        op: O,
-    ) where
-        R0: Into<Region>,
-        R1: Into<Region>,
-        M: Pixel<Chan = P::Chan, Model = Matte, Gamma = P::Gamma>,
-        O: Blend,
-    {
-        let (to, from) = self.clip_regions(to, src, from);
-        let srows = src.rows(from);
-        let drows = self.rows_mut(to);
-        for (drow, srow) in drows.zip(srows) {
-            P::composite_matte(drow, srow, &clr, op);
-        }
+    pub fn changed_pixels<'a>(
+        &'a self,
+        other: &'a Raster<P>,
+    ) -> impl Iterator<Item = (i32, i32, P)> + 'a {
+        assert_eq!(self.width, other.width);
+        assert_eq!(self.height, other.height);
+        let width = self.width as usize;
+        self.pixels
+            .iter()
+            .zip(other.pixels.iter())
+            .enumerate()
+            .filter(|(_, (p0, p1))| p0 != p1)
+            .map(move |(i, (_, p1))| {
+                ((i % width) as i32, (i / width) as i32, *p1)
+            })
     }
 
-    /// Composite from a source `Raster`.
+    /// Get an `Iterator` of all pixels with their coordinates.
     ///
-    /// * `to` Region within `self` (destination).
-    /// * `src` Source `Raster`.
-    /// * `from` Region within source `Raster`.
-    /// * `op` Compositing operation.
+    /// Each item is a tuple of `x`, `y` and the pixel, in row-major order.
     ///
-    /// `to` / `from` can be `Region` structs, tuples of (*x*, *y*, *width*,
-    /// *height*) or the unit type `()`.  Using `()` has the same result as
-    /// `Raster::region()`.
+    /// ### Example
+    /// ```
+    /// use pix::gray::SGray8;
+    /// use pix::Raster;
     ///
-    /// ```bob
-    /// *------------+      *-------------+
-    /// |            |      |    *------+ |
-    /// | *------+   |      |    |      | |
-    /// | |      |   |      |    | from | |
-    /// | |  to  |   | <--- |    +------+ |
-    /// | +------+   |      |             |
-    /// |            |      |     src     |
-    /// |    self    |      +-------------+
-    /// +------------+
+    /// let r = Raster::<SGray8>::with_clear(3, 2);
+    /// let (x, y, _) = r.pixels_with_coords().last().unwrap();
+    /// assert_eq!((x, y), (2, 1));
     /// ```
-    /// The composited `Region` is clamped to the smaller of `to` and `from` in
-    /// both `X` and `Y` dimensions.  Also, `to` and `from` are clipped to
-    /// their respective `Raster` dimensions.
+    pub fn pixels_with_coords(
+        &self,
+    ) -> impl Iterator<Item = (i32, i32, P)> + '_ {
+        let width = self.width as usize;
+        self.pixels
+            .iter()
+            .enumerate()
+            .map(move |(i, p)| ((i % width) as i32, (i / width) as i32, *p))
+    }
+
+    /// Get an `Iterator` of all mutable pixels with their coordinates.
     ///
-    /// ### Blend one `Raster` onto another
+    /// Each item is a tuple of `x`, `y` and the pixel, in row-major order.
+    ///
+    /// ### Example
     /// ```
-    /// use pix::ops::SrcOver;
-    /// use pix::rgb::Rgba8p;
+    /// use pix::gray::SGray8;
     /// use pix::Raster;
     ///
-    /// let mut r0 = Raster::with_clear(100, 100);
-    /// let r1 = Raster::with_color(5, 5, Rgba8p::new(80, 0, 80, 200));
-    /// // ... load image data
-    /// r0.composite_raster((40, 40), &r1, (), SrcOver);
+    /// let mut r = Raster::<SGray8>::with_clear(16, 16);
+    /// for (x, y, p) in r.pixels_with_coords_mut() {
+    ///     *p = SGray8::new((x * y) as u8);
+    /// }
     /// ```
-    pub fn composite_raster<R0, R1, O>(
+    pub fn pixels_with_coords_mut(
         &mut self,
-        to: R0,
-        src: &Raster<P>,
-        from: R1,
-        op: O,
-    ) where
-        R0: Into<Region>,
-        R1: Into<Region>,
-        O: Blend,
-    {
-        let (to, from) = self.clip_regions(to, src, from);
-        let srows = src.rows(from);
-        let drows = self.rows_mut(to);
-        for (drow, srow) in drows.zip(srows) {
-            P::composite_slice(drow, srow, op);
-        }
-    }
-}
-
-impl<'a, P: Pixel> Rows<'a, P> {
-    /// Create a new row `Iterator`.
-    fn new(raster: &'a Raster<P>, reg: Region) -> Self {
-        let width = raster.width() as usize;
-        let start = reg.y as usize * width;
-        let end = reg.bottom() as usize * width;
-        let slice = &raster.pixels[start..end];
-        let chunks = slice.chunks_exact(width);
-        let x = reg.x as usize;
-        let w = reg.width as usize;
-        let columns = x..x + w;
-        Rows { chunks, columns }
+    ) -> impl Iterator<Item = (i32, i32, &mut P)> + '_ {
+        let width = self.width as usize;
+        self.pixels
+            .iter_mut()
+            .enumerate()
+            .map(move |(i, p)| ((i % width) as i32, (i / width) as i32, p))
     }
-}
-
-impl<'a, P: Pixel> Iterator for Rows<'a, P> {
-    type Item = &'a [P];
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.chunks.next().map(|s| &s[self.columns.clone()])
+    /// Get an `Iterator` of pixels in serpentine (boustrophedon) order.
+    ///
+    /// Even rows (starting with row 0) are traversed left to right, and odd
+    /// rows right to left.  Each item is a tuple of `x`, `y` and the pixel.
+    /// Serpentine scanning improves the quality of error-diffusion
+    /// dithering.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::gray::SGray8;
+    /// use pix::Raster;
+    ///
+    /// let r = Raster::<SGray8>::with_clear(3, 2);
+    /// let xy: Vec<_> =
+    ///     r.pixels_boustrophedon().map(|(x, y, _)| (x, y)).collect();
+    /// assert_eq!(xy, [(0, 0), (1, 0), (2, 0), (2, 1), (1, 1), (0, 1)]);
+    /// ```
+    pub fn pixels_boustrophedon(
+        &self,
+    ) -> impl Iterator<Item = (i32, i32, P)> + '_ {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| {
+            (0..width).map(move |i| {
+                let x = if y % 2 == 0 { i } else { width - 1 - i };
+                (x, y, self.pixels[(y * width + x) as usize])
+            })
+        })
     }
-}
 
-impl<'a, P: Pixel> RowsMut<'a, P> {
-    /// Create a new mutable row `Iterator`.
-    fn new(raster: &'a mut Raster<P>, reg: Region) -> Self {
-        let width = raster.width() as usize;
-        let start = reg.y as usize * width;
-        let end = reg.bottom() as usize * width;
-        let slice = &mut raster.pixels[start..end];
-        let chunks = slice.chunks_exact_mut(width);
-        let x = reg.x as usize;
-        let w = reg.width as usize;
-        let columns = x..x + w;
-        RowsMut { chunks, columns }
+    /// Check whether another raster is approximately equal.
+    ///
+    /// * `other` Raster to compare.
+    /// * `tol` Tolerance for each channel, from 0 to 1.
+    ///
+    /// Returns `true` if the dimensions are equal, and every channel differs
+    /// by no more than `tol`.  This is useful for comparing float formats
+    /// after lossy operations.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::Rgb32;
+    /// use pix::Raster;
+    ///
+    /// let r0 = Raster::with_color(4, 4, Rgb32::new(0.5, 0.25, 0.125));
+    /// let r1 = Raster::with_color(4, 4, Rgb32::new(0.5, 0.2501, 0.125));
+    /// assert!(r0.approx_eq(&r1, 0.001));
+    /// assert!(!r0.approx_eq(&r1, 0.0));
+    /// ```
+    pub fn approx_eq(&self, other: &Raster<P>, tol: f32) -> bool {
+        self.width == other.width
+            && self.height == other.height
+            && self
+                .pixels
+                .iter()
+                .zip(other.pixels.iter())
+                .all(|(p0, p1)| within_tol(p0, p1, tol))
     }
-}
-
-impl<'a, P: Pixel> Iterator for RowsMut<'a, P> {
-    type Item = &'a mut [P];
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.chunks.next().map(|s| &mut s[self.columns.clone()])
+    /// Get pixels as packed `u32` values (0xAARRGGBB).
+    ///
+    /// Byte order is independent of host endianness.  Pixels without *alpha*
+    /// are packed as fully opaque.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::SRgb8;
+    /// use pix::Raster;
+    ///
+    /// let r = Raster::with_color(4, 4, SRgb8::new(0x12, 0x34, 0x56));
+    /// assert_eq!(r.to_u32_argb(), vec![0xFF12_3456; 16]);
+    /// ```
+    pub fn to_u32_argb(&self) -> Vec<u32>
+    where
+        P: Pixel<Chan = Ch8>,
+    {
+        self.pixels
+            .iter()
+            .map(|p| {
+                let rgba: PixRgba<P> = p.convert();
+                let chan = rgba.channels();
+                let red = u32::from(u8::from(chan[0]));
+                let green = u32::from(u8::from(chan[1]));
+                let blue = u32::from(u8::from(chan[2]));
+                let alpha = u32::from(u8::from(chan[3]));
+                alpha << 24 | red << 16 | green << 8 | blue
+            })
+            .collect()
     }
-}
 
-impl From<(i32, i32, u32, u32)> for Region {
-    fn from(r: (i32, i32, u32, u32)) -> Self {
-        Region::new(r.0, r.1, r.2, r.3)
+    /// Convert to [SRgb8], for display.
+    ///
+    /// This is shorthand for [with_raster](#method.with_raster), converting
+    /// color model, gamma and bit depth.  Any *alpha* channel is discarded;
+    /// use [to_srgba8](#method.to_srgba8) to keep it.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::{Rgb32, SRgb8};
+    /// use pix::Raster;
+    ///
+    /// let r = Raster::with_color(4, 4, Rgb32::new(0.5, 0.5, 0.5));
+    /// let s = r.to_srgb8();
+    /// assert_eq!(s.pixel(0, 0), SRgb8::new(188, 188, 188));
+    /// ```
+    /// [SRgb8]: rgb/type.SRgb8.html
+    pub fn to_srgb8(&self) -> Raster<SRgb8>
+    where
+        Ch8: From<P::Chan>,
+    {
+        Raster::with_raster(self)
     }
-}
 
-impl From<(i32, i32)> for Region {
-    fn from(r: (i32, i32)) -> Self {
-        const MAX: u32 = i32::MAX as u32;
-        Region::new(r.0, r.1, MAX, MAX)
+    /// Convert to [SRgba8], for display.
+    ///
+    /// This is shorthand for [with_raster](#method.with_raster), converting
+    /// color model, gamma, *alpha* mode and bit depth.
+    ///
+    /// [SRgba8]: rgb/type.SRgba8.html
+    pub fn to_srgba8(&self) -> Raster<SRgba8>
+    where
+        Ch8: From<P::Chan>,
+    {
+        Raster::with_raster(self)
     }
-}
 
-impl From<()> for Region {
-    fn from(_: ()) -> Self {
-        const MAX: u32 = i32::MAX as u32;
-        Region::new(0, 0, MAX, MAX)
+    /// Quantize to the 216-color web-safe palette.
+    ///
+    /// Pixels are converted to [SRgb8], then each channel is rounded to the
+    /// nearest of six levels: 0, 51, 102, 153, 204 or 255.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::SRgb8;
+    /// use pix::Raster;
+    ///
+    /// let r = Raster::with_color(4, 4, SRgb8::new(0x12, 0x80, 0xF0));
+    /// let w = r.to_web_safe();
+    /// assert_eq!(w.pixel(0, 0), SRgb8::new(0x00, 0x99, 0xFF));
+    /// ```
+    /// [SRgb8]: rgb/type.SRgb8.html
+    pub fn to_web_safe(&self) -> Raster<SRgb8>
+    where
+        Ch8: From<P::Chan>,
+    {
+        let level = |c: Ch8| ((u16::from(u8::from(c)) + 25) / 51 * 51) as u8;
+        let pixels = self
+            .pixels
+            .iter()
+            .map(|p| {
+                let rgb: SRgb8 = p.convert();
+                SRgb8::new::<u8>(
+                    level(Rgb::red(rgb)),
+                    level(Rgb::green(rgb)),
+                    level(Rgb::blue(rgb)),
+                )
+            })
+            .collect::<Vec<_>>();
+        Raster::with_pixels(self.width(), self.height(), pixels)
     }
-}
 
-impl Region {
-    /// Create a new `Region`
-    pub fn new(x: i32, y: i32, width: u32, height: u32) -> Self {
-        let width = i32::try_from(width).expect(WIDTH_TOO_BIG);
-        let height = i32::try_from(height).expect(HEIGHT_TOO_BIG);
-        Region {
-            x,
-            y,
-            width,
-            height,
+    /// Encode pixels using simple run-length encoding.
+    ///
+    /// Each row is encoded as a series of runs, with a count byte (1 to 255)
+    /// followed by the bytes of one pixel.  Runs do not span rows.  This is
+    /// compact for images with large areas of flat color, such as sprites or
+    /// UI elements.  The dimensions are not included, and must be provided to
+    /// [from_rle](#method.from_rle) for decoding.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::SRgba8;
+    /// use pix::Raster;
+    ///
+    /// let r = Raster::with_color(100, 20, SRgba8::new(0xFF, 0, 0, 0x80));
+    /// let rle = r.to_rle();
+    /// assert_eq!(rle.len(), 20 * 5);
+    /// let r1 = Raster::<SRgba8>::from_rle(100, 20, &rle);
+    /// assert_eq!(r1.pixels(), r.pixels());
+    /// ```
+    pub fn to_rle(&self) -> Vec<u8>
+    where
+        P: Pixel<Chan = Ch8>,
+    {
+        let size = std::mem::size_of::<P>();
+        let bytes = self.as_u8_slice();
+        let mut rle = Vec::new();
+        for (y, row) in self.rows(()).enumerate() {
+            let mut x = 0;
+            while x < row.len() {
+                let count = row[x..]
+                    .iter()
+                    .take(255)
+                    .take_while(|p| **p == row[x])
+                    .count();
+                let i = (y * row.len() + x) * size;
+                rle.push(count as u8);
+                rle.extend_from_slice(&bytes[i..i + size]);
+                x += count;
+            }
         }
+        rle
     }
 
-    /// Get intersection with another `Region`
-    pub fn intersection<R>(self, rhs: R) -> Self
+    /// Get pixels reordered into Morton (Z-order) layout.
+    ///
+    /// Pixel coordinate bits are interleaved, so that nearby pixels are close
+    /// together in memory.  This is the layout of swizzled textures expected
+    /// by some graphics hardware.  For rectangular rasters, the remaining
+    /// bits of the larger dimension are most significant.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` or `height` is not a power of two.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::gray::SGray8;
+    /// use pix::Raster;
+    ///
+    /// let p = [1, 2, 3, 4, 5, 6, 7, 8].map(SGray8::new);
+    /// let r = Raster::with_pixels(4, 2, p);
+    /// let v = [1, 2, 5, 6, 3, 4, 7, 8].map(SGray8::new);
+    /// assert_eq!(r.to_morton_order(), v);
+    /// ```
+    pub fn to_morton_order(&self) -> Vec<P> {
+        let (width, height) = (self.width(), self.height());
+        assert!(width.is_power_of_two(), "{NOT_POW2}");
+        assert!(height.is_power_of_two(), "{NOT_POW2}");
+        let mut v = vec![P::default(); self.pixels.len()];
+        let mut i = 0;
+        for y in 0..height {
+            for x in 0..width {
+                v[morton_index(x, y, width, height)] = self.pixels[i];
+                i += 1;
+            }
+        }
+        v
+    }
+
+    /// Compute a hash of each row of pixels.
+    ///
+    /// The hashes are calculated using 64-bit FNV-1a over the bytes of each
+    /// row.  They are fast to compute, but not suitable for cryptographic
+    /// purposes.  Comparing hashes between two versions of a raster can be
+    /// used to skip rows which have not changed.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::SRgb8;
+    /// use pix::Raster;
+    ///
+    /// let mut r = Raster::<SRgb8>::with_clear(10, 10);
+    /// let h0 = r.row_hashes();
+    /// *r.pixel_mut(4, 4) = SRgb8::new(0xFF, 0x80, 0x00);
+    /// let h1 = r.row_hashes();
+    /// assert_ne!(h0[4], h1[4]);
+    /// ```
+    pub fn row_hashes(&self) -> Vec<u64> {
+        let len = self.width() as usize * std::mem::size_of::<P>();
+        if len == 0 {
+            return vec![fnv1a(FNV_OFFSET, &[]); self.height() as usize];
+        }
+        self.as_u8_slice()
+            .chunks_exact(len)
+            .map(|row| fnv1a(FNV_OFFSET, row))
+            .collect()
+    }
+
+    /// Compute a checksum of the raster.
+    ///
+    /// The checksum is calculated using 64-bit FNV-1a over the width and
+    /// height (as little-endian `u32`), followed by the bytes of each channel
+    /// in little-endian order.  It is stable across runs and architectures,
+    /// making it useful for comparing against golden images in tests.  It is
+    /// not suitable for cryptographic purposes.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::SRgb8;
+    /// use pix::Raster;
+    ///
+    /// let r0 = Raster::with_color(10, 10, SRgb8::new(0x11, 0x22, 0x33));
+    /// let r1 = Raster::with_color(10, 10, SRgb8::new(0x11, 0x22, 0x33));
+    /// assert_eq!(r0.checksum(), r1.checksum());
+    /// ```
+    pub fn checksum(&self) -> u64 {
+        let hash = fnv1a(FNV_OFFSET, &self.width().to_le_bytes());
+        let hash = fnv1a(hash, &self.height().to_le_bytes());
+        if cfg!(target_endian = "little") {
+            fnv1a(hash, self.as_u8_slice())
+        } else {
+            let size = std::mem::size_of::<P::Chan>();
+            self.as_u8_slice().chunks_exact(size).fold(hash, |h, c| {
+                c.iter()
+                    .rev()
+                    .fold(h, |h, b| (h ^ u64::from(*b)).wrapping_mul(FNV_PRIME))
+            })
+        }
+    }
+
+    /// Scale up by a factor of two using the [Scale2x] algorithm.
+    ///
+    /// Each pixel is expanded to a 2x2 block, with corners chosen by
+    /// comparing its four neighbors.  This preserves sharp edges in pixel art
+    /// better than nearest-neighbor scaling.  Neighbors past the edges of the
+    /// `Raster` are clamped.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::SRgb8;
+    /// use pix::Raster;
+    ///
+    /// let r = Raster::<SRgb8>::with_clear(16, 16);
+    /// let r2 = r.scale2x();
+    /// assert_eq!((r2.width(), r2.height()), (32, 32));
+    /// ```
+    /// [Scale2x]: https://www.scale2x.it/algorithm
+    pub fn scale2x(&self) -> Self {
+        let mut r = Raster::with_clear(self.width() * 2, self.height() * 2);
+        let xmax = self.width - 1;
+        let ymax = self.height - 1;
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let p = self.pixel(x, y);
+                let a = self.pixel(x, (y - 1).max(0));
+                let b = self.pixel((x + 1).min(xmax), y);
+                let c = self.pixel((x - 1).max(0), y);
+                let d = self.pixel(x, (y + 1).min(ymax));
+                let (x, y) = (x * 2, y * 2);
+                *r.pixel_mut(x, y) =
+                    if c == a && c != d && a != b { a } else { p };
+                *r.pixel_mut(x + 1, y) =
+                    if a == b && a != c && b != d { b } else { p };
+                *r.pixel_mut(x, y + 1) =
+                    if d == c && d != b && c != a { c } else { p };
+                *r.pixel_mut(x + 1, y + 1) =
+                    if b == d && b != a && d != c { d } else { p };
+            }
+        }
+        r
+    }
+
+    /// Compute the average color of all pixels.
+    ///
+    /// The *alpha*-weighted mean is calculated in linear light, so fully
+    /// transparent pixels do not contribute to the color.  The *alpha* of the
+    /// result is the mean *alpha* of all pixels.  If every pixel is
+    /// transparent, the default pixel value is returned.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::SRgba8;
+    /// use pix::Raster;
+    ///
+    /// let r = Raster::with_color(10, 10, SRgba8::new(0x40, 0x80, 0xC0, 0xFF));
+    /// assert_eq!(r.average_color(), SRgba8::new(0x40, 0x80, 0xC0, 0xFF));
+    /// ```
+    pub fn average_color(&self) -> P {
+        let mut sum = [0.0; 4];
+        for p in self.pixels.iter() {
+            let rgba = to_linear_rgba(*p);
+            for (s, v) in sum[..3].iter_mut().zip(&rgba[..3]) {
+                *s += f64::from(*v * rgba[3]);
+            }
+            sum[3] += f64::from(rgba[3]);
+        }
+        if sum[3] > 0.0 {
+            let alpha = sum[3] / self.pixels.len() as f64;
+            let rgba =
+                [sum[0] / sum[3], sum[1] / sum[3], sum[2] / sum[3], alpha];
+            from_linear_rgba(rgba.map(|v| v as f32))
+        } else {
+            P::default()
+        }
+    }
+
+    /// Reduce all pixels to a single value.
+    ///
+    /// * `init` Initial value of the accumulator.
+    /// * `f` Function called with the accumulator and each pixel (in row
+    ///   order), returning the new accumulator.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::{Rgb, SRgb8};
+    /// use pix::Raster;
+    ///
+    /// let r = Raster::with_color(4, 4, SRgb8::new(0x10, 0x20, 0x30));
+    /// let red = r.fold(0, |sum, p| sum + u32::from(u8::from(Rgb::red(p))));
+    /// assert_eq!(red, 0x100);
+    /// ```
+    pub fn fold<B, F>(&self, init: B, f: F) -> B
     where
-        R: Into<Self>,
+        F: FnMut(B, P) -> B,
     {
-        let rhs = rhs.into();
-        let x0 = self.x.max(rhs.x);
-        let x1 = self.right().min(rhs.right());
-        let y0 = self.y.max(rhs.y);
-        let y1 = self.bottom().min(rhs.bottom());
-        if x0 < x1 && y0 < y1 {
-            let w = (x1 - x0) as u32;
-            let h = (y1 - y0) as u32;
-            Region::new(x0, y0, w, h)
-        } else {
-            Region::default()
+        self.pixels.iter().copied().fold(init, f)
+    }
+
+    /// Map each pixel to a new `Raster`, with the same dimensions.
+    ///
+    /// * `Q` `Pixel` format of the new `Raster`.
+    /// * `f` Function called with each pixel (in row order), returning the
+    ///   new pixel.
+    ///
+    /// Unlike [with_raster](#method.with_raster), this allows any per-pixel
+    /// transform, such as swapping channels or color grading.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::{Rgb, SRgb8};
+    /// use pix::Raster;
+    ///
+    /// let r = Raster::with_color(4, 4, SRgb8::new(0x10, 0x20, 0x30));
+    /// let r = r.map(|p| SRgb8::new(Rgb::blue(p), Rgb::green(p), Rgb::red(p)));
+    /// assert_eq!(r.pixel(1, 1), SRgb8::new(0x30, 0x20, 0x10));
+    /// ```
+    pub fn map<Q, F>(&self, f: F) -> Raster<Q>
+    where
+        Q: Pixel,
+        F: FnMut(P) -> Q,
+    {
+        let pixels: Vec<Q> = self.pixels.iter().copied().map(f).collect();
+        Raster::with_pixels(self.width(), self.height(), pixels)
+    }
+
+    /// Combine with another `Raster` pixelwise, into a new `Raster`.
+    ///
+    /// * `other` `Raster` with the same dimensions as `self`.
+    /// * `f` Function called with each pixel of `self` and the pixel at the
+    ///   same location in `other`, returning the new pixel.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `other` has different dimensions than `self`.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::gray::{Gray, SGray8};
+    /// use pix::Raster;
+    ///
+    /// let a = Raster::with_color(4, 4, SGray8::new(0x40));
+    /// let b = Raster::with_color(4, 4, SGray8::new(0x10));
+    /// let r = a.zip_map(&b, |p, q| {
+    ///     SGray8::new(Gray::value(p) - Gray::value(q))
+    /// });
+    /// assert_eq!(r.pixel(0, 0), SGray8::new(0x30));
+    /// ```
+    pub fn zip_map<S, Q, F>(&self, other: &Raster<S>, mut f: F) -> Raster<Q>
+    where
+        S: Pixel,
+        Q: Pixel,
+        F: FnMut(P, S) -> Q,
+    {
+        assert_eq!(self.width(), other.width());
+        assert_eq!(self.height(), other.height());
+        let pixels: Vec<Q> = self
+            .pixels
+            .iter()
+            .zip(other.pixels())
+            .map(|(p, s)| f(*p, *s))
+            .collect();
+        Raster::with_pixels(self.width(), self.height(), pixels)
+    }
+
+    /// Count the values of each channel in 256 bins.
+    ///
+    /// Returns one histogram per channel, in the order of the color model,
+    /// with *alpha* last (if present).  Channels are converted to 8 bits
+    /// for binning, so each histogram's total is the number of pixels.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::SRgba8;
+    /// use pix::Raster;
+    ///
+    /// let r = Raster::with_color(4, 4, SRgba8::new(0xFF, 0x80, 0, 0x40));
+    /// let hist = r.histogram();
+    /// assert_eq!(hist.len(), 4);
+    /// assert_eq!(hist[1][0x80], 16);
+    /// assert_eq!(hist[3][0x40], 16);
+    /// ```
+    pub fn histogram(&self) -> Vec<[u32; 256]>
+    where
+        Ch8: From<P::Chan>,
+    {
+        let channels = P::default().channels().len();
+        let mut hist = vec![[0; 256]; channels];
+        for p in self.pixels.iter() {
+            for (h, c) in hist.iter_mut().zip(p.channels()) {
+                h[usize::from(u8::from(Ch8::from(*c)))] += 1;
+            }
         }
+        hist
     }
 
-    /// Get the left side
-    pub fn left(self) -> i32 {
-        self.x
+    /// Reduce all pixels to a single value in parallel.
+    ///
+    /// * `init` Initial value of each accumulator.
+    /// * `fold` Function called with an accumulator and each pixel of a row,
+    ///   returning the new accumulator.
+    /// * `combine` Associative function to merge two accumulators.
+    ///
+    /// Rows are folded in parallel, each starting from a clone of `init`, and
+    /// the results are then combined.  For this to match [fold], `init` must
+    /// be an identity of `combine`.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::gray::{Gray, SGray8};
+    /// use pix::Raster;
+    ///
+    /// let r = Raster::with_color(64, 64, SGray8::new(2));
+    /// let sum = r.par_fold(
+    ///     0,
+    ///     |sum, p| sum + u32::from(u8::from(Gray::value(p))),
+    ///     |a, b| a + b,
+    /// );
+    /// assert_eq!(sum, 64 * 64 * 2);
+    /// ```
+    /// [fold]: #method.fold
+    #[cfg(feature = "rayon")]
+    pub fn par_fold<B, F, C>(&self, init: B, fold: F, combine: C) -> B
+    where
+        P: Sync,
+        B: Clone + Send + Sync,
+        F: Fn(B, P) -> B + Sync,
+        C: Fn(B, B) -> B + Sync,
+    {
+        use rayon::prelude::*;
+
+        if self.pixels.is_empty() {
+            return init;
+        }
+        self.pixels
+            .par_chunks(self.width() as usize)
+            .map(|row| row.iter().copied().fold(init.clone(), &fold))
+            .reduce(|| init.clone(), &combine)
     }
 
-    /// Get the top side
-    pub fn top(self) -> i32 {
-        self.y
+    /// Get a parallel iterator of mutable rows.
+    ///
+    /// Rows are processed on the `rayon` thread pool, in no particular order.
+    /// Use `enumerate` to get the row index.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::gray::SGray8;
+    /// use pix::Raster;
+    /// use rayon::prelude::*;
+    ///
+    /// let mut r = Raster::<SGray8>::with_clear(64, 64);
+    /// r.par_rows_mut().enumerate().for_each(|(y, row)| {
+    ///     row.fill(SGray8::new(y as u8));
+    /// });
+    /// assert_eq!(r.pixel(5, 9), SGray8::new(9));
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn par_rows_mut(
+        &mut self,
+    ) -> impl rayon::iter::IndexedParallelIterator<Item = &mut [P]>
+    where
+        P: Send,
+    {
+        use rayon::prelude::*;
+
+        let width = (self.width() as usize).max(1);
+        self.pixels.par_chunks_exact_mut(width)
     }
 
-    /// Get the width
-    pub fn width(self) -> u32 {
-        self.width as u32
+    /// Clear all pixels to default value, in parallel.
+    ///
+    /// This is equivalent to [clear](#method.clear), but splits the pixels
+    /// across the `rayon` thread pool.
+    #[cfg(feature = "rayon")]
+    pub fn par_clear(&mut self)
+    where
+        P: Send,
+    {
+        use rayon::prelude::*;
+
+        let len = self.pixels.len();
+        let chunk = len.div_ceil(rayon::current_num_threads()).max(1);
+        self.pixels
+            .par_chunks_mut(chunk)
+            .for_each(|c| c.fill(P::default()));
     }
 
-    /// Get the height
-    pub fn height(self) -> u32 {
-        self.height as u32
+    /// Apply a vignette, darkening pixels toward the corners.
+    ///
+    /// * `strength` Amount of darkening at the corners, from 0 to 1.
+    ///
+    /// Each pixel is multiplied in linear light by `1 - strength × d²`, where
+    /// *d* is its distance from the center, normalized so that corner pixels
+    /// have a distance of 1.  A `strength` of 0 has no effect.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::SRgb8;
+    /// use pix::Raster;
+    ///
+    /// let mut r = Raster::with_color(64, 48, SRgb8::new(0xAA, 0xCC, 0xEE));
+    /// r.vignette(0.6);
+    /// ```
+    pub fn vignette(&mut self, strength: f32) {
+        if strength == 0.0 {
+            return;
+        }
+        let cx = (self.width - 1) as f32 / 2.0;
+        let cy = (self.height - 1) as f32 / 2.0;
+        let rmax = cx.hypot(cy);
+        for (y, row) in self.rows_mut(()).enumerate() {
+            let dy = y as f32 - cy;
+            for (x, p) in row.iter_mut().enumerate() {
+                let dx = x as f32 - cx;
+                let d2 = if rmax > 0.0 {
+                    (dx * dx + dy * dy) / (rmax * rmax)
+                } else {
+                    0.0
+                };
+                let factor = (1.0 - strength * d2).max(0.0);
+                let mut rgba = to_linear_rgba(*p);
+                for v in &mut rgba[..3] {
+                    *v *= factor;
+                }
+                *p = from_linear_rgba(rgba);
+            }
+        }
+    }
+
+    /// Equalize the *luma* histogram, enhancing contrast.
+    ///
+    /// The histogram of *luma* (sRGB encoded, in 256 bins) is remapped
+    /// using its cumulative distribution, so that output *luma* is spread
+    /// roughly uniformly across the full range.  Colors are scaled in linear
+    /// light to the new *luma*, preserving their chromaticity (*hue* and
+    /// *saturation*) except where clipped.  Fully transparent pixels are not
+    /// counted in the histogram.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::SRgb8;
+    /// use pix::Raster;
+    ///
+    /// let mut r = Raster::<SRgb8>::with_clear(64, 64);
+    /// // ... load image data
+    /// r.equalize();
+    /// ```
+    pub fn equalize(&mut self) {
+        const BINS: usize = 256;
+        let luma = |rgba: &[f32; 4]| {
+            rgba[0] * 0.212_6 + rgba[1] * 0.715_2 + rgba[2] * 0.072_2
+        };
+        let bin = |y: f32| {
+            let e: Ch32 = Srgb::from_linear_f32(y);
+            (e.to_f32() * (BINS - 1) as f32).round() as usize
+        };
+        let mut cdf = [0_usize; BINS];
+        for p in self.pixels.iter() {
+            let rgba = to_linear_rgba(*p);
+            if rgba[3] > 0.0 {
+                cdf[bin(luma(&rgba))] += 1;
+            }
+        }
+        for i in 1..BINS {
+            cdf[i] += cdf[i - 1];
+        }
+        let total = cdf[BINS - 1];
+        let cdf_min = cdf.iter().copied().find(|c| *c > 0).unwrap_or(0);
+        if total <= cdf_min {
+            return;
+        }
+        let scale = 1.0 / (total - cdf_min) as f32;
+        for p in self.pixels.iter_mut() {
+            let mut rgba = to_linear_rgba(*p);
+            let y = luma(&rgba);
+            let e = cdf[bin(y)].saturating_sub(cdf_min) as f32 * scale;
+            let target = Srgb::to_linear_f32(Ch32::new(e));
+            if y > 0.0 {
+                let ratio = target / y;
+                for v in &mut rgba[..3] {
+                    *v = (*v * ratio).min(1.0);
+                }
+            } else {
+                rgba[..3].fill(target);
+            }
+            *p = from_linear_rgba(rgba);
+        }
+    }
+
+    /// Stretch contrast, remapping a range of channel values to full range.
+    ///
+    /// * `lo` Channel value mapped to minimum.
+    /// * `hi` Channel value mapped to maximum.
+    ///
+    /// Values are remapped linearly (and clamped) for each *linear* channel of
+    /// the color model, as stored (with no gamma or *alpha* conversion).
+    /// Circular channels (such as *hue*) and *alpha* are unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lo` is not less than `hi`.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::gray::Gray8;
+    /// use pix::Raster;
+    ///
+    /// let mut r = Raster::with_color(4, 4, Gray8::new(0x60));
+    /// r.stretch_contrast(0.25, 0.5);
+    /// assert_eq!(r.pixel(0, 0), Gray8::new(0x81));
+    /// ```
+    pub fn stretch_contrast(&mut self, lo: f32, hi: f32) {
+        assert!(lo < hi, "Invalid contrast range");
+        let scale = 1.0 / (hi - lo);
+        for p in self.pixels.iter_mut() {
+            for c in &mut p.channels_mut()[P::Model::LINEAR] {
+                *c = P::Chan::from((c.to_f32() - lo) * scale);
+            }
+        }
+    }
+
+    /// Stretch contrast to full range automatically.
+    ///
+    /// The minimum and maximum values of all *linear* channels are found, then
+    /// used as the range for [stretch_contrast].  If all values are equal, the
+    /// raster is unchanged.
+    ///
+    /// [stretch_contrast]: #method.stretch_contrast
+    pub fn auto_stretch(&mut self) {
+        let (lo, hi) = self.pixels.iter().fold(
+            (f32::INFINITY, f32::NEG_INFINITY),
+            |(lo, hi), p| {
+                p.channels()[P::Model::LINEAR]
+                    .iter()
+                    .map(|c| c.to_f32())
+                    .fold((lo, hi), |(lo, hi), v| (lo.min(v), hi.max(v)))
+            },
+        );
+        if lo < hi {
+            self.stretch_contrast(lo, hi);
+        }
+    }
+
+    /// Adjust brightness by adding a value to each channel.
+    ///
+    /// * `delta` Amount to add, from -1 to 1.
+    ///
+    /// Like [stretch_contrast](#method.stretch_contrast), this applies to
+    /// *linear* channels of the color model as stored, in the gamma space of
+    /// the pixel format.  Values are clamped to the channel range.  Circular
+    /// channels (such as *hue*) and *alpha* are unchanged.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::SRgba8;
+    /// use pix::Raster;
+    ///
+    /// let mut r = Raster::with_color(4, 4, SRgba8::new(0x40, 0xF0, 0, 0x80));
+    /// r.adjust_brightness(0.25);
+    /// assert_eq!(r.pixel(0, 0), SRgba8::new(0x80, 0xFF, 0x40, 0x80));
+    /// ```
+    pub fn adjust_brightness(&mut self, delta: f32) {
+        for p in self.pixels.iter_mut() {
+            for c in &mut p.channels_mut()[P::Model::LINEAR] {
+                *c = P::Chan::from(c.to_f32() + delta);
+            }
+        }
+    }
+
+    /// Adjust contrast by scaling each channel around the midpoint.
+    ///
+    /// * `factor` Contrast scale; 1 is unchanged, 0 is flat gray.
+    ///
+    /// Each *linear* channel value *v* is replaced with `(v - 0.5) × factor +
+    /// 0.5`, clamped to the channel range.  As with
+    /// [adjust_brightness](#method.adjust_brightness), values are adjusted
+    /// in the gamma space of the pixel format, and circular channels and
+    /// *alpha* are unchanged.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::gray::SGray8;
+    /// use pix::Raster;
+    ///
+    /// let mut r = Raster::with_color(4, 4, SGray8::new(0x60));
+    /// r.adjust_contrast(2.0);
+    /// assert_eq!(r.pixel(0, 0), SGray8::new(0x41));
+    /// ```
+    pub fn adjust_contrast(&mut self, factor: f32) {
+        for p in self.pixels.iter_mut() {
+            for c in &mut p.channels_mut()[P::Model::LINEAR] {
+                *c = P::Chan::from((c.to_f32() - 0.5) * factor + 0.5);
+            }
+        }
+    }
+
+    /// Compute an integral image (summed-area table).
+    ///
+    /// Each entry is the sum of all channel values above and to the left of
+    /// a pixel, inclusive.  The table is arranged like the pixels (row-major),
+    /// with the channels of each pixel interleaved.  Channel values are summed
+    /// as native integers for [Ch8] and [Ch16], while float channels are
+    /// summed as 16-bit integers (0 to 65535).
+    ///
+    /// The sum of any rectangle can be found from the four entries at its
+    /// corners.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::gray::SGray16;
+    /// use pix::Raster;
+    ///
+    /// let r = Raster::with_color(8, 8, SGray16::new(100));
+    /// let sat = r.integral_image();
+    /// assert_eq!(sat[8 * 8 - 1], 100 * 8 * 8);
+    /// ```
+    /// [Ch8]: chan/struct.Ch8.html
+    /// [Ch16]: chan/struct.Ch16.html
+    pub fn integral_image(&self) -> Vec<u64> {
+        self.summed_area(chan_int)
+    }
+
+    /// Compute a summed-area table, with a function to get channel values
+    fn summed_area<T, F>(&self, value: F) -> Vec<T>
+    where
+        T: Copy + Default + Add<Output = T>,
+        F: Fn(P::Chan) -> T,
+    {
+        let n = P::default().channels().len();
+        let stride = self.width() as usize * n;
+        let mut sat = vec![T::default(); self.pixels.len() * n];
+        let mut row_sum = vec![T::default(); n];
+        for (y, row) in self.rows(()).enumerate() {
+            row_sum.fill(T::default());
+            for (x, p) in row.iter().enumerate() {
+                for (c, ch) in p.channels().iter().enumerate() {
+                    row_sum[c] = row_sum[c] + value(*ch);
+                    let i = y * stride + x * n + c;
+                    sat[i] = if y > 0 {
+                        row_sum[c] + sat[i - stride]
+                    } else {
+                        row_sum[c]
+                    };
+                }
+            }
+        }
+        sat
+    }
+
+    /// Blur using a box filter computed from an integral image.
+    ///
+    /// * `radius` Number of pixels on each side of the center pixel.
+    ///
+    /// Each output pixel is the average of a square window of side
+    /// 2 × `radius` + 1, computed in constant time regardless of `radius`.
+    /// Windows are shrunk to the area available at the edges.  Integer
+    /// channels are summed with [integral_image](#method.integral_image),
+    /// while floating point channels are summed as `f64`, keeping full
+    /// precision.  Channels are averaged without gamma conversion, so *linear*
+    /// gamma should be used for best results.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::Rgb8;
+    /// use pix::Raster;
+    ///
+    /// let mut r = Raster::<Rgb8>::with_clear(64, 64);
+    /// r.copy_color((16, 16, 32, 32), Rgb8::new(0xFF, 0xFF, 0xFF));
+    /// let blurred = r.box_blur_fast(4);
+    /// ```
+    pub fn box_blur_fast(&self, radius: u32) -> Self {
+        let n = P::default().channels().len();
+        let w = self.width() as usize;
+        if chan_is_float::<P::Chan>() {
+            let sat = self.summed_area(|c| f64::from(c.to_f32()));
+            let at = |x: i32, y: i32, c: usize| -> f64 {
+                if x < 0 || y < 0 {
+                    0.0
+                } else {
+                    sat[(y as usize * w + x as usize) * n + c]
+                }
+            };
+            return self.box_average(radius, |(x0, y0), (x1, y1), c| {
+                at(x1, y1, c) + at(x0, y0, c) - at(x0, y1, c) - at(x1, y0, c)
+            });
+        }
+        let sat = self.integral_image();
+        let scale = f64::from(chan_int_max::<P::Chan>());
+        let at = |x: i32, y: i32, c: usize| -> u64 {
+            if x < 0 || y < 0 {
+                0
+            } else {
+                sat[(y as usize * w + x as usize) * n + c]
+            }
+        };
+        self.box_average(radius, |(x0, y0), (x1, y1), c| {
+            let sum =
+                at(x1, y1, c) + at(x0, y0, c) - at(x0, y1, c) - at(x1, y0, c);
+            sum as f64 / scale
+        })
+    }
+
+    /// Average square windows, with a function to sum a window channel
+    ///
+    /// The function is called with the corners just outside the window (top
+    /// left) and just inside it (bottom right).
+    fn box_average<F>(&self, radius: u32, window_sum: F) -> Self
+    where
+        F: Fn((i32, i32), (i32, i32), usize) -> f64,
+    {
+        let radius = i32::try_from(radius).unwrap_or(i32::MAX);
+        let mut r = self.clone();
+        let (xmax, ymax) = (self.width - 1, self.height - 1);
+        for (y, row) in r.rows_mut(()).enumerate() {
+            let y = y as i32;
+            let y0 = y.saturating_sub(radius).max(0) - 1;
+            let y1 = y.saturating_add(radius).min(ymax);
+            for (x, p) in row.iter_mut().enumerate() {
+                let x = x as i32;
+                let x0 = x.saturating_sub(radius).max(0) - 1;
+                let x1 = x.saturating_add(radius).min(xmax);
+                let area = f64::from(x1 - x0) * f64::from(y1 - y0);
+                for (c, ch) in p.channels_mut().iter_mut().enumerate() {
+                    let v = window_sum((x0, y0), (x1, y1), c) / area;
+                    *ch = P::Chan::from(v as f32);
+                }
+            }
+        }
+        r
+    }
+
+    /// Blur using a separable Gaussian filter.
+    ///
+    /// * `radius` Extent of the kernel in pixels, on each side of the center
+    ///   pixel (three standard deviations).
+    ///
+    /// A horizontal pass is followed by a vertical pass.  Integer channels
+    /// are converted to 16-bit fixed point and accumulated as integers, with
+    /// normalization only at the end.  Floating point channels are
+    /// accumulated as `f64`, keeping full precision.  Pixels past the edges
+    /// are clamped
+    /// (extended).  Channels are blurred without gamma conversion, so *linear*
+    /// gamma should be used for best results.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::Rgba8;
+    /// use pix::Raster;
+    ///
+    /// let mut r = Raster::<Rgba8>::with_clear(64, 64);
+    /// r.copy_color((16, 16, 32, 32), Rgba8::new(0, 0, 0, 0x80));
+    /// let shadow = r.blur(6.0);
+    /// ```
+    pub fn blur(&self, radius: f32) -> Self {
+        let half = radius.ceil().max(0.0) as i32;
+        if half == 0 || self.pixels.is_empty() {
+            return self.clone();
+        }
+        let sigma = radius / 3.0;
+        let gauss: Vec<f32> = (-half..=half)
+            .map(|i| (-(i * i) as f32 / (2.0 * sigma * sigma)).exp())
+            .collect();
+        let total: f32 = gauss.iter().sum();
+        if chan_is_float::<P::Chan>() {
+            let kernel: Vec<f64> = gauss
+                .iter()
+                .map(|g| f64::from(*g) / f64::from(total))
+                .collect();
+            return self.blur_float(half, &kernel);
+        }
+        let kernel: Vec<u64> = gauss
+            .iter()
+            .map(|g| (g / total * 65536.0).round() as u64)
+            .collect();
+        let ksum: u64 = kernel.iter().sum();
+        let n = P::default().channels().len();
+        let (w, h) = (self.width, self.height);
+        // horizontal pass
+        let mut tmp = vec![0_u64; self.pixels.len() * n];
+        for (y, row) in self.rows(()).enumerate() {
+            for x in 0..w {
+                let i = (y * w as usize + x as usize) * n;
+                for (k, wt) in kernel.iter().enumerate() {
+                    let sx = (x + k as i32 - half).clamp(0, w - 1);
+                    let p = row[sx as usize];
+                    for (c, ch) in p.channels().iter().enumerate() {
+                        tmp[i + c] += wt * u64::from(chan_u16(*ch));
+                    }
+                }
+            }
+        }
+        // vertical pass
+        let scale = (ksum * ksum) as f64 * 65535.0;
+        let mut r = self.clone();
+        for (y, row) in r.rows_mut(()).enumerate() {
+            for (x, p) in row.iter_mut().enumerate() {
+                let mut sum = [0_u64; 5];
+                for (k, wt) in kernel.iter().enumerate() {
+                    let sy = (y as i32 + k as i32 - half).clamp(0, h - 1);
+                    let i = (sy as usize * w as usize + x) * n;
+                    for (s, v) in sum.iter_mut().zip(&tmp[i..i + n]) {
+                        *s += wt * v;
+                    }
+                }
+                for (ch, s) in p.channels_mut().iter_mut().zip(sum) {
+                    *ch = P::Chan::from((s as f64 / scale) as f32);
+                }
+            }
+        }
+        r
+    }
+
+    /// Blur floating point channels with a normalized separable kernel
+    fn blur_float(&self, half: i32, kernel: &[f64]) -> Self {
+        let n = P::default().channels().len();
+        let (w, h) = (self.width, self.height);
+        // horizontal pass
+        let mut tmp = vec![0.0_f64; self.pixels.len() * n];
+        for (y, row) in self.rows(()).enumerate() {
+            for x in 0..w {
+                let i = (y * w as usize + x as usize) * n;
+                for (k, wt) in kernel.iter().enumerate() {
+                    let sx = (x + k as i32 - half).clamp(0, w - 1);
+                    let p = row[sx as usize];
+                    for (c, ch) in p.channels().iter().enumerate() {
+                        tmp[i + c] += wt * f64::from(ch.to_f32());
+                    }
+                }
+            }
+        }
+        // vertical pass
+        let mut r = self.clone();
+        for (y, row) in r.rows_mut(()).enumerate() {
+            for (x, p) in row.iter_mut().enumerate() {
+                let mut sum = [0.0_f64; 5];
+                for (k, wt) in kernel.iter().enumerate() {
+                    let sy = (y as i32 + k as i32 - half).clamp(0, h - 1);
+                    let i = (sy as usize * w as usize + x) * n;
+                    for (s, v) in sum.iter_mut().zip(&tmp[i..i + n]) {
+                        *s += wt * v;
+                    }
+                }
+                for (ch, s) in p.channels_mut().iter_mut().zip(sum) {
+                    *ch = P::Chan::from(s as f32);
+                }
+            }
+        }
+        r
+    }
+
+    /// Apply a convolution kernel.
+    ///
+    /// * `kernel` Kernel weights, in row-major order.
+    /// * `kwidth` Kernel width.
+    /// * `kheight` Kernel height.
+    ///
+    /// The kernel is centered on each pixel, at (`kwidth` / 2, `kheight` / 2).
+    /// Weights are normalized by their sum, unless it is zero (as for edge
+    /// detection kernels).  Pixels past the edges are clamped (extended).
+    /// Each channel is accumulated as `f32`, and results outside the channel
+    /// range are clamped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `kernel` length is not `kwidth` * `kheight`.
+    ///
+    /// ### Sharpen
+    /// ```
+    /// use pix::rgb::Rgb8;
+    /// use pix::Raster;
+    ///
+    /// let r = Raster::<Rgb8>::with_clear(64, 64);
+    /// let kernel = [0.0, -1.0, 0.0, -1.0, 5.0, -1.0, 0.0, -1.0, 0.0];
+    /// let sharp = r.convolve(&kernel, 3, 3);
+    /// ```
+    pub fn convolve(&self, kernel: &[f32], kwidth: u32, kheight: u32) -> Self {
+        assert_eq!(
+            kernel.len() as u64,
+            u64::from(kwidth) * u64::from(kheight),
+            "kernel length must be kwidth * kheight",
+        );
+        let sum: f32 = kernel.iter().sum();
+        let norm = if sum != 0.0 { sum } else { 1.0 };
+        let (cx, cy) = ((kwidth / 2) as i32, (kheight / 2) as i32);
+        let (xmax, ymax) = (self.width - 1, self.height - 1);
+        let mut r = self.clone();
+        for (y, row) in r.rows_mut(()).enumerate() {
+            for (x, p) in row.iter_mut().enumerate() {
+                let mut acc = [0.0_f32; 5];
+                for (k, wt) in kernel.iter().enumerate() {
+                    let kx = (k as u32 % kwidth) as i32 - cx;
+                    let ky = (k as u32 / kwidth) as i32 - cy;
+                    let sx = (x as i32 + kx).clamp(0, xmax);
+                    let sy = (y as i32 + ky).clamp(0, ymax);
+                    let s = self.pixel(sx, sy);
+                    for (a, ch) in acc.iter_mut().zip(s.channels()) {
+                        *a += wt * ch.to_f32();
+                    }
+                }
+                for (ch, a) in p.channels_mut().iter_mut().zip(acc) {
+                    *ch = P::Chan::from((a / norm).clamp(0.0, 1.0));
+                }
+            }
+        }
+        r
+    }
+
+    /// Transform into a new `Raster`, which can have different dimensions.
+    ///
+    /// * `D` `Pixel` format of destination `Raster`.
+    /// * `width` Width of destination `Raster`.
+    /// * `height` Height of destination `Raster`.
+    /// * `f` Closure called with `self` and (*x*, *y*) coordinates for each
+    ///   destination pixel.
+    ///
+    /// This is a general building block for operations such as resizing or
+    /// warping.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` or `height` is greater than `std::i32::MAX`.
+    ///
+    /// ### Decimate by a factor of 2
+    /// ```
+    /// use pix::rgb::SRgb8;
+    /// use pix::Raster;
+    ///
+    /// let r = Raster::<SRgb8>::with_clear(64, 64);
+    /// let half = r.transform(32, 32, |src, x, y| src.pixel(x * 2, y * 2));
+    /// ```
+    pub fn transform<D, F>(&self, width: u32, height: u32, f: F) -> Raster<D>
+    where
+        D: Pixel,
+        F: Fn(&Raster<P>, i32, i32) -> D,
+    {
+        Raster::with_row_fn(width, height, |y, row| {
+            for (x, p) in row.iter_mut().enumerate() {
+                *p = f(self, x as i32, y);
+            }
+        })
+    }
+
+    /// Transpose the `Raster`, reflecting across the main diagonal.
+    ///
+    /// The resulting `Raster` has width and height swapped, with pixel
+    /// (*x*, *y*) taken from (*y*, *x*) of `self`.  This is useful for
+    /// separable filters, which can process columns as rows.  Unlike
+    /// [rotate_quarter](#method.rotate_quarter), this is a reflection: it is
+    /// equivalent to a quarter turn followed by a horizontal flip.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::gray::SGray8;
+    /// use pix::Raster;
+    ///
+    /// let r = Raster::<SGray8>::with_clear(20, 10);
+    /// let t = r.transpose();
+    /// assert_eq!((t.width(), t.height()), (10, 20));
+    /// ```
+    pub fn transpose(&self) -> Self {
+        let mut r = Raster::with_clear(self.height(), self.width());
+        for (y, row) in self.rows(()).enumerate() {
+            for (x, p) in row.iter().enumerate() {
+                *r.pixel_mut(y as i32, x as i32) = *p;
+            }
+        }
+        r
+    }
+
+    /// Resize using bilinear interpolation.
+    ///
+    /// * `width` Width of resized `Raster`.
+    /// * `height` Height of resized `Raster`.
+    ///
+    /// Pixel centers are mapped between the source and destination, with
+    /// coordinates clamped at the edges.  Interpolation uses
+    /// [Channel::lerp](chan/trait.Channel.html#tymethod.lerp), so it is done
+    /// in the native channel type (integer math for 8 and 16-bit channels).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` or `height` is greater than `std::i32::MAX`.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::SRgb8;
+    /// use pix::Raster;
+    ///
+    /// let r = Raster::<SRgb8>::with_clear(64, 48);
+    /// let r = r.resize_bilinear(100, 75);
+    /// assert_eq!((r.width(), r.height()), (100, 75));
+    /// ```
+    pub fn resize_bilinear(&self, width: u32, height: u32) -> Self {
+        if self.pixels.is_empty() {
+            return Raster::with_clear(width, height);
+        }
+        let sx = self.width as f32 / width as f32;
+        let sy = self.height as f32 / height as f32;
+        self.transform(width, height, |src, x, y| {
+            let x = (x as f32 + 0.5) * sx - 0.5;
+            let y = (y as f32 + 0.5) * sy - 0.5;
+            src.sample_bilinear(x, y)
+        })
+    }
+
+    /// Resample a region of a source `Raster` to fit a region of `self`.
+    ///
+    /// * `to` Region within `self` (destination).
+    /// * `src` Source `Raster`, in any format.
+    /// * `from` Region within source `Raster`.
+    /// * `filter` Resampling filter.
+    ///
+    /// Both regions are first clipped to their respective `Raster`
+    /// dimensions, so `()` means the entire `Raster`.  The clipped `from`
+    /// region is then scaled to the size of the clipped `to` region, which can
+    /// be different.  Source pixels are converted to the destination format.
+    ///
+    /// ### Scale a sprite from a sheet
+    /// ```
+    /// use pix::rgb::SRgb8;
+    /// use pix::{Filter, Raster};
+    ///
+    /// let sheet = Raster::<SRgb8>::with_clear(64, 64);
+    /// let mut r = Raster::<SRgb8>::with_clear(100, 100);
+    /// let (to, from) = ((10, 10, 32, 32), (16, 0, 16, 16));
+    /// r.resample_into(to, &sheet, from, Filter::Nearest);
+    /// ```
+    pub fn resample_into<R0, R1, S>(
+        &mut self,
+        to: R0,
+        src: &Raster<S>,
+        from: R1,
+        filter: Filter,
+    ) where
+        R0: Into<Region>,
+        R1: Into<Region>,
+        S: Pixel,
+        P::Chan: From<S::Chan>,
+    {
+        let to = self.intersection(to);
+        let from = src.intersection(from);
+        if to.width == 0 || to.height == 0 {
+            return;
+        }
+        if from.width == 0 || from.height == 0 {
+            return;
+        }
+        let (left, top) = (from.left() as f32, from.top() as f32);
+        let right = (from.right() - 1) as f32;
+        let bottom = (from.bottom() - 1) as f32;
+        let sx = from.width as f32 / to.width as f32;
+        let sy = from.height as f32 / to.height as f32;
+        for (j, row) in self.rows_mut(to).enumerate() {
+            let y = j as f32 + 0.5;
+            for (i, p) in row.iter_mut().enumerate() {
+                let x = i as f32 + 0.5;
+                let s = match filter {
+                    Filter::Nearest => {
+                        let x = (from.x as f32 + x * sx).clamp(left, right);
+                        let y = (from.y as f32 + y * sy).clamp(top, bottom);
+                        src.pixel(x as i32, y as i32)
+                    }
+                    Filter::Bilinear => {
+                        let x = from.x as f32 + x * sx - 0.5;
+                        let y = from.y as f32 + y * sy - 0.5;
+                        src.sample_bilinear(
+                            x.clamp(left, right),
+                            y.clamp(top, bottom),
+                        )
+                    }
+                };
+                *p = s.convert();
+            }
+        }
+    }
+
+    /// Rotate by a multiple of 90 degrees into a new `Raster`.
+    ///
+    /// * `turns` Number of clockwise quarter turns (taken modulo 4).
+    ///
+    /// For an odd number of turns, width and height are swapped.  Pixels are
+    /// copied exactly, with no interpolation.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::gray::SGray8;
+    /// use pix::Raster;
+    ///
+    /// let r = Raster::<SGray8>::with_clear(20, 10);
+    /// let r = r.rotate_quarter(1);
+    /// assert_eq!((r.width(), r.height()), (10, 20));
+    /// ```
+    pub fn rotate_quarter(&self, turns: u8) -> Self {
+        let (w, h) = (self.width, self.height);
+        match turns % 4 {
+            1 => self.transform(self.height(), self.width(), |src, x, y| {
+                src.pixel(y, h - 1 - x)
+            }),
+            2 => self.transform(self.width(), self.height(), |src, x, y| {
+                src.pixel(w - 1 - x, h - 1 - y)
+            }),
+            3 => self.transform(self.height(), self.width(), |src, x, y| {
+                src.pixel(w - 1 - y, x)
+            }),
+            _ => self.clone(),
+        }
+    }
+
+    /// Flip horizontally (mirror left-to-right), in place.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::matte::Matte8;
+    /// use pix::Raster;
+    ///
+    /// let mut r = Raster::<Matte8>::with_clear(3, 1);
+    /// *r.pixel_mut(0, 0) = Matte8::new(0xFF);
+    /// r.flip_horizontal();
+    /// assert_eq!(r.pixel(2, 0), Matte8::new(0xFF));
+    /// ```
+    pub fn flip_horizontal(&mut self) {
+        for row in self.rows_mut(()) {
+            row.reverse();
+        }
+    }
+
+    /// Flip vertically (mirror top-to-bottom), in place.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::matte::Matte8;
+    /// use pix::Raster;
+    ///
+    /// let mut r = Raster::<Matte8>::with_clear(1, 3);
+    /// *r.pixel_mut(0, 0) = Matte8::new(0xFF);
+    /// r.flip_vertical();
+    /// assert_eq!(r.pixel(0, 2), Matte8::new(0xFF));
+    /// ```
+    pub fn flip_vertical(&mut self) {
+        let half = self.height as usize / 2;
+        let mut rows = self.rows_mut(());
+        for _ in 0..half {
+            if let (Some(top), Some(bottom)) = (rows.next(), rows.next_back()) {
+                top.swap_with_slice(bottom);
+            }
+        }
+    }
+
+    /// Rotate by an arbitrary angle into a new `Raster`.
+    ///
+    /// * `radians` Angle of clockwise rotation about the center.
+    /// * `fill` Color of destination pixels outside the rotated image.
+    ///
+    /// The destination dimensions are expanded to the bounding box of the
+    /// rotated image.  Each destination pixel is mapped back through the
+    /// rotation, and sampled from the source using bilinear interpolation.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::SRgba8;
+    /// use pix::Raster;
+    ///
+    /// let r = Raster::with_color(20, 10, SRgba8::new(0xFF, 0, 0, 0xFF));
+    /// let r = r.rotate(std::f32::consts::FRAC_PI_2, SRgba8::default());
+    /// assert_eq!((r.width(), r.height()), (10, 20));
+    /// ```
+    pub fn rotate(&self, radians: f32, fill: P) -> Self {
+        let (sin, cos) = radians.sin_cos();
+        let w = self.width as f32;
+        let h = self.height as f32;
+        // allow for rounding error at multiples of 90 degrees
+        let fit = |v: f32| (v - 1e-3).ceil().max(0.0) as u32;
+        let width = fit(w * cos.abs() + h * sin.abs());
+        let height = fit(w * sin.abs() + h * cos.abs());
+        if self.pixels.is_empty() {
+            return Raster::with_color(width, height, fill);
+        }
+        let dcx = width as f32 / 2.0;
+        let dcy = height as f32 / 2.0;
+        self.transform(width, height, |src, x, y| {
+            let dx = x as f32 + 0.5 - dcx;
+            let dy = y as f32 + 0.5 - dcy;
+            let sx = dx * cos + dy * sin + w / 2.0;
+            let sy = dy * cos - dx * sin + h / 2.0;
+            if (0.0..=w).contains(&sx) && (0.0..=h).contains(&sy) {
+                src.sample_bilinear(sx - 0.5, sy - 0.5)
+            } else {
+                fill
+            }
+        })
+    }
+}
+
+impl<P> Raster<P>
+where
+    P: Pixel<Model = Rgb>,
+{
+    /// Simulate chromatic aberration of a lens.
+    ///
+    /// * `shift` Offset in pixels at the corners.
+    ///
+    /// The *red* and *blue* channels are sampled at radially offset positions
+    /// from *green*, creating color fringes which increase toward the edges.
+    /// *Red* is sampled outward and *blue* inward by up to `shift` pixels.  A
+    /// `shift` of 0 has no effect.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::SRgb8;
+    /// use pix::Raster;
+    ///
+    /// let mut r = Raster::<SRgb8>::with_clear(64, 48);
+    /// // ... load image data
+    /// r.chromatic_aberration(1.5);
+    /// ```
+    pub fn chromatic_aberration(&mut self, shift: f32) {
+        if shift == 0.0 || self.pixels.is_empty() {
+            return;
+        }
+        let src = self.clone();
+        let cx = (self.width - 1) as f32 / 2.0;
+        let cy = (self.height - 1) as f32 / 2.0;
+        let rmax = cx.hypot(cy);
+        if rmax == 0.0 {
+            return;
+        }
+        for (y, row) in self.rows_mut(()).enumerate() {
+            let y = y as f32;
+            let oy = (y - cy) / rmax * shift;
+            for (x, p) in row.iter_mut().enumerate() {
+                let x = x as f32;
+                let ox = (x - cx) / rmax * shift;
+                let red = src.sample_bilinear(x + ox, y + oy);
+                let blue = src.sample_bilinear(x - ox, y - oy);
+                *Rgb::red_mut(p) = Rgb::red(red);
+                *Rgb::blue_mut(p) = Rgb::blue(blue);
+            }
+        }
+    }
+
+    /// Split into *red*, *green*, *blue* and *alpha* planes.
+    ///
+    /// Channel values are copied unchanged into gray rasters, with no gamma
+    /// conversion.  For pixel formats without *alpha*, the *alpha* plane is
+    /// fully opaque.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::gray::SGray8;
+    /// use pix::rgb::SRgba8;
+    /// use pix::Raster;
+    ///
+    /// let r = Raster::with_color(4, 4, SRgba8::new(0x10, 0x20, 0x30, 0x40));
+    /// let (red, _green, _blue, alpha) = r.split_planes::<SGray8>();
+    /// assert_eq!(red.pixel(0, 0), SGray8::new(0x10));
+    /// assert_eq!(alpha.pixel(0, 0), SGray8::new(0x40));
+    /// ```
+    pub fn split_planes<G>(
+        &self,
+    ) -> (Raster<G>, Raster<G>, Raster<G>, Raster<G>)
+    where
+        G: Pixel<Chan = P::Chan, Model = Gray>,
+    {
+        let plane = |f: fn(P) -> P::Chan| {
+            let pixels = self
+                .pixels
+                .iter()
+                .map(|p| G::from_channels(&[f(*p), P::Chan::MAX]))
+                .collect::<Vec<_>>();
+            Raster::with_pixels(self.width(), self.height(), pixels)
+        };
+        (
+            plane(Rgb::red),
+            plane(Rgb::green),
+            plane(Rgb::blue),
+            plane(|p| p.alpha()),
+        )
+    }
+
+    /// Convert to gray, weighting channels by *luminance*.
+    ///
+    /// The Rec. 709 coefficients (0.2126 *red*, 0.7152 *green* and 0.0722
+    /// *blue*) are applied in linear light, then encoded with the gamma of
+    /// the gray format.  In contrast, [convert] applies the coefficients to
+    /// channel values in the gamma of the destination format.  *Alpha* is
+    /// preserved.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::gray::SGray8;
+    /// use pix::rgb::SRgb8;
+    /// use pix::Raster;
+    ///
+    /// let r = Raster::with_color(4, 4, SRgb8::new(0, 0xFF, 0));
+    /// let gray = r.to_luma::<SGray8>();
+    /// assert_eq!(gray.pixel(0, 0), SGray8::new(0xDC));
+    /// ```
+    /// [convert]: el/trait.Pixel.html#method.convert
+    pub fn to_luma<G>(&self) -> Raster<G>
+    where
+        G: Pixel<Chan = P::Chan, Model = Gray>,
+    {
+        let pixels = self
+            .pixels
+            .iter()
+            .map(|p| {
+                let [red, green, blue, alpha] = to_linear_rgba(*p);
+                let y = red * 0.2126 + green * 0.7152 + blue * 0.0722;
+                let a = Ch32::new(alpha);
+                let y = G::Alpha::encode(Ch32::new(y), a).to_f32();
+                G::from_channels(&[
+                    G::Gamma::from_linear_f32(y),
+                    a.to_f32().into(),
+                ])
+            })
+            .collect::<Vec<_>>();
+        Raster::with_pixels(self.width(), self.height(), pixels)
+    }
+
+    /// Composite a color using sub-pixel (LCD) coverage.
+    ///
+    /// * `to` Region within `self` (destination).
+    /// * `src` Source coverage `Raster`.
+    /// * `from` Region within source `Raster`.
+    /// * `clr` Text color.
+    ///
+    /// The *red*, *green* and *blue* channels of `src` contain coverage of
+    /// each sub-pixel, for displays with horizontal RGB stripes.  Each
+    /// destination channel is blended independently (*source over*), with
+    /// the *alpha* of `clr` multiplied by the channel coverage.  The
+    /// destination *alpha* is blended using the maximum coverage.
+    ///
+    /// Regions are clipped the same way as
+    /// [composite_raster](#method.composite_raster).
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::{Rgb8, Rgba8p};
+    /// use pix::Raster;
+    ///
+    /// let mut r = Raster::with_color(32, 32, Rgba8p::new(255, 255, 255, 255));
+    /// let glyph = Raster::with_color(8, 8, Rgb8::new(0x40, 0xFF, 0xC0));
+    /// r.composite_lcd((4, 4), &glyph, (), Rgba8p::new(0, 0, 0, 255));
+    /// assert_eq!(r.pixel(5, 5), Rgba8p::new(0xBF, 0, 0x3F, 255));
+    /// ```
+    pub fn composite_lcd<R0, R1, S>(
+        &mut self,
+        to: R0,
+        src: &Raster<S>,
+        from: R1,
+        clr: P,
+    ) where
+        P: Pixel<Alpha = Premultiplied, Gamma = Linear>,
+        R0: Into<Region>,
+        R1: Into<Region>,
+        S: Pixel<Chan = P::Chan, Model = Rgb, Gamma = Linear>,
+    {
+        let (to, from) =
+            clip_regions(self.region(), to.into(), src.region(), from.into());
+        let max = P::Chan::MAX;
+        let alpha = clr.alpha();
+        let drows = self.rows_mut(to);
+        let srows = src.rows(from);
+        for (drow, srow) in drows.zip(srows) {
+            for (d, s) in drow.iter_mut().zip(srow) {
+                let cov = &s.channels()[..3];
+                let d_chan = &mut d.channels_mut()[..3];
+                for ((dc, c), cv) in
+                    d_chan.iter_mut().zip(clr.channels()).zip(cov)
+                {
+                    *dc = *c * *cv + *dc * (max - alpha * *cv);
+                }
+                let cv = cov.iter().copied().max().unwrap_or(P::Chan::MIN);
+                let da = d.alpha();
+                *d.alpha_mut() = alpha * cv + da * (max - alpha * cv);
+            }
+        }
+    }
+}
+
+impl<P> Raster<P>
+where
+    P: Pixel<Model = Gray>,
+{
+    /// Create a matte `Raster`, interpreting *luma* as coverage.
+    ///
+    /// The *luma* channel values are copied unchanged, with no gamma
+    /// conversion.  Any *alpha* channel is ignored.  This differs from
+    /// [with_raster](#method.with_raster), which converts the color (making
+    /// a fully opaque matte).
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::gray::SGray8;
+    /// use pix::matte::Matte8;
+    /// use pix::Raster;
+    ///
+    /// let r = Raster::with_color(4, 4, SGray8::new(0x80));
+    /// let m = r.to_matte::<Matte8>();
+    /// assert_eq!(m.pixel(2, 2), Matte8::new(0x80));
+    /// ```
+    pub fn to_matte<M>(&self) -> Raster<M>
+    where
+        M: Pixel<Chan = P::Chan, Model = Matte>,
+    {
+        let pixels = self
+            .pixels
+            .iter()
+            .map(|p| M::from_channels(&[Gray::value(*p)]))
+            .collect::<Vec<_>>();
+        Raster::with_pixels(self.width(), self.height(), pixels)
+    }
+
+    /// Create a normal map, treating *luma* as height.
+    ///
+    /// * `strength` Scale of height gradient; larger values exaggerate the
+    ///   slope.
+    ///
+    /// The gradient is calculated using a [Sobel] operator, with pixels
+    /// beyond the edges clamped.  Each unit normal is encoded into *red*
+    /// (*X*), *green* (*Y*, toward the top) and *blue* (*Z*) as 0.5 × (*n* +
+    /// 1), so that a flat surface is (128, 128, 255).  Channel values are
+    /// not gamma encoded.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::gray::SGray8;
+    /// use pix::rgb::SRgb8;
+    /// use pix::Raster;
+    ///
+    /// let r = Raster::with_color(16, 16, SGray8::new(0x80));
+    /// let n = r.to_normal_map(2.0);
+    /// assert_eq!(n.pixel(5, 5), SRgb8::new(128, 128, 255));
+    /// ```
+    /// [Sobel]: https://en.wikipedia.org/wiki/Sobel_operator
+    pub fn to_normal_map(&self, strength: f32) -> Raster<SRgb8> {
+        let (width, height) = (self.width, self.height);
+        let h = |x: i32, y: i32| {
+            let x = x.clamp(0, width - 1);
+            let y = y.clamp(0, height - 1);
+            Gray::value(self.pixels[(y * width + x) as usize]).to_f32()
+        };
+        let encode = |v: f32| ((v + 1.0) * 0.5 * 255.0).round() as u8;
+        Raster::with_row_fn(self.width(), self.height(), |y, row| {
+            for (x, p) in row.iter_mut().enumerate() {
+                let x = x as i32;
+                let right =
+                    h(x + 1, y - 1) + 2.0 * h(x + 1, y) + h(x + 1, y + 1);
+                let left =
+                    h(x - 1, y - 1) + 2.0 * h(x - 1, y) + h(x - 1, y + 1);
+                let below =
+                    h(x - 1, y + 1) + 2.0 * h(x, y + 1) + h(x + 1, y + 1);
+                let above =
+                    h(x - 1, y - 1) + 2.0 * h(x, y - 1) + h(x + 1, y - 1);
+                let dx = right - left;
+                let dy = below - above;
+                let nx = -dx * strength;
+                let ny = dy * strength;
+                let len = (nx * nx + ny * ny + 1.0).sqrt();
+                *p = SRgb8::new::<u8>(
+                    encode(nx / len),
+                    encode(ny / len),
+                    encode(1.0 / len),
+                );
+            }
+        })
+    }
+}
+
+impl<P> Raster<P>
+where
+    P: Pixel<Model = Matte>,
+{
+    /// Create an opaque gray `Raster`, interpreting coverage as *luma*.
+    ///
+    /// The *alpha* channel values are copied unchanged, with no gamma
+    /// conversion.  This is the inverse of [to_matte](#method.to_matte).
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::gray::SGray8;
+    /// use pix::matte::Matte8;
+    /// use pix::Raster;
+    ///
+    /// let m = Raster::with_color(4, 4, Matte8::new(0x80));
+    /// let r = m.to_gray::<SGray8>();
+    /// assert_eq!(r.pixel(2, 2), SGray8::new(0x80));
+    /// ```
+    pub fn to_gray<G>(&self) -> Raster<G>
+    where
+        G: Pixel<Chan = P::Chan, Model = Gray>,
+    {
+        let pixels = self
+            .pixels
+            .iter()
+            .map(|p| G::from_channels(&[p.alpha(), P::Chan::MAX]))
+            .collect::<Vec<_>>();
+        Raster::with_pixels(self.width(), self.height(), pixels)
+    }
+
+    /// Combine a source matte `Raster` using union (maximum coverage).
+    ///
+    /// * `to` Region within `self` (destination).
+    /// * `src` Source `Raster`.
+    /// * `from` Region within source `Raster`.
+    ///
+    /// Regions are clipped the same way as
+    /// [composite_raster](#method.composite_raster).  This is useful for
+    /// building up complex clip shapes.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::matte::Matte8;
+    /// use pix::Raster;
+    ///
+    /// let mut r0 = Raster::<Matte8>::with_clear(100, 100);
+    /// let r1 = Raster::with_color(50, 50, Matte8::new(0x80));
+    /// r0.composite_matte_union((20, 20), &r1, ());
+    /// ```
+    pub fn composite_matte_union<R0, R1>(
+        &mut self,
+        to: R0,
+        src: &Raster<P>,
+        from: R1,
+    ) where
+        R0: Into<Region>,
+        R1: Into<Region>,
+    {
+        let (to, from) = self.clip_regions(to, src, from);
+        let srows = src.rows(from);
+        let drows = self.rows_mut(to);
+        for (drow, srow) in drows.zip(srows) {
+            for (d, s) in drow.iter_mut().zip(srow) {
+                *d.alpha_mut() = d.alpha().max(s.alpha());
+            }
+        }
+    }
+
+    /// Combine a source matte `Raster` using intersection (minimum coverage).
+    ///
+    /// * `to` Region within `self` (destination).
+    /// * `src` Source `Raster`.
+    /// * `from` Region within source `Raster`.
+    ///
+    /// Regions are clipped the same way as
+    /// [composite_raster](#method.composite_raster).  Pixels outside of the
+    /// clipped region are not affected.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::matte::Matte8;
+    /// use pix::Raster;
+    ///
+    /// let mut r0 = Raster::with_color(100, 100, Matte8::new(0xFF));
+    /// let r1 = Raster::with_color(50, 50, Matte8::new(0x80));
+    /// r0.composite_matte_intersect((20, 20), &r1, ());
+    /// ```
+    pub fn composite_matte_intersect<R0, R1>(
+        &mut self,
+        to: R0,
+        src: &Raster<P>,
+        from: R1,
+    ) where
+        R0: Into<Region>,
+        R1: Into<Region>,
+    {
+        let (to, from) = self.clip_regions(to, src, from);
+        let srows = src.rows(from);
+        let drows = self.rows_mut(to);
+        for (drow, srow) in drows.zip(srows) {
+            for (d, s) in drow.iter_mut().zip(srow) {
+                *d.alpha_mut() = d.alpha().min(s.alpha());
+            }
+        }
+    }
+}
+
+impl<P> Raster<P>
+where
+    P: Pixel<Gamma = Srgb>,
+{
+    /// Decode *sRGB* gamma to *linear* intensity, in place.
+    ///
+    /// Only *linear* channels are decoded; *alpha* and circular channels
+    /// (*hue*) are left untouched.  The pixel type is not changed, so this
+    /// should be paired with [to_srgb](#method.to_srgb) after operations which
+    /// need *linear* values, such as [blur](#method.blur) or
+    /// [resize_bilinear](#method.resize_bilinear).
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::SRgba8;
+    /// use pix::Raster;
+    ///
+    /// let mut r = Raster::with_color(4, 4, SRgba8::new(0x80, 0, 0, 0x80));
+    /// r.to_linear();
+    /// assert_eq!(r.pixel(0, 0), SRgba8::new(0x37, 0, 0, 0x80));
+    /// let mut r = r.blur(1.0);
+    /// r.to_srgb();
+    /// ```
+    pub fn to_linear(&mut self) {
+        for p in self.pixels.iter_mut() {
+            for c in p.channels_mut()[P::Model::LINEAR].iter_mut() {
+                *c = Srgb::to_linear(*c);
+            }
+        }
+    }
+
+    /// Encode *linear* intensity to *sRGB* gamma, in place.
+    ///
+    /// This is the inverse of [to_linear](#method.to_linear); *alpha* and
+    /// circular channels are left untouched.
+    pub fn to_srgb(&mut self) {
+        for p in self.pixels.iter_mut() {
+            for c in p.channels_mut()[P::Model::LINEAR].iter_mut() {
+                *c = Srgb::from_linear(*c);
+            }
+        }
+    }
+}
+
+impl<P> Raster<P>
+where
+    P: Pixel<Alpha = Straight>,
+{
+    /// Multiply channels by *alpha*, in place.
+    ///
+    /// Only *linear* channels are multiplied; *alpha* and circular channels
+    /// (*hue*) are left untouched.  The pixel type is not changed, so this
+    /// should be paired with [unpremultiply](#method.unpremultiply).  For a
+    /// conversion which changes the pixel type, use
+    /// [with_raster](#method.with_raster).
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::SRgba8;
+    /// use pix::Raster;
+    ///
+    /// let mut r = Raster::with_color(4, 4, SRgba8::new(0xFF, 0x80, 0, 0x80));
+    /// r.premultiply();
+    /// assert_eq!(r.pixel(0, 0), SRgba8::new(0x80, 0x40, 0, 0x80));
+    /// ```
+    pub fn premultiply(&mut self) {
+        for p in self.pixels.iter_mut() {
+            let alpha = p.alpha();
+            for c in p.channels_mut()[P::Model::LINEAR].iter_mut() {
+                *c = Premultiplied::encode(*c, alpha);
+            }
+        }
+    }
+
+    /// Divide channels by *alpha*, in place.
+    ///
+    /// This is the inverse of [premultiply](#method.premultiply).  Where
+    /// *alpha* is zero, channels are set to zero.
+    pub fn unpremultiply(&mut self) {
+        for p in self.pixels.iter_mut() {
+            let alpha = p.alpha();
+            for c in p.channels_mut()[P::Model::LINEAR].iter_mut() {
+                *c = Premultiplied::decode(*c, alpha);
+            }
+        }
+    }
+}
+
+impl<P> Raster<P>
+where
+    P: Pixel<Alpha = Straight, Gamma = Linear>,
+{
+    /// Composite from a source `Raster` with *straight* alpha.
+    ///
+    /// * `to` Region within `self` (destination).
+    /// * `src` Source `Raster`.
+    /// * `from` Region within source `Raster`.
+    /// * `op` Compositing operation.
+    ///
+    /// This is like [composite_raster](#method.composite_raster), but for
+    /// *straight* alpha formats.  Each pair of pixels is premultiplied,
+    /// blended, and then converted back to *straight* alpha.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::ops::SrcOver;
+    /// use pix::rgb::Rgba8;
+    /// use pix::Raster;
+    ///
+    /// let mut r0 = Raster::with_color(10, 10, Rgba8::new(0, 0, 0xFF, 0xFF));
+    /// let r1 = Raster::with_color(5, 5, Rgba8::new(0xFF, 0, 0, 0x80));
+    /// r0.composite_raster_straight((), &r1, (), SrcOver);
+    /// assert_eq!(r0.pixel(2, 2), Rgba8::new(0x80, 0, 0x7F, 0xFF));
+    /// ```
+    pub fn composite_raster_straight<R0, R1, O>(
+        &mut self,
+        to: R0,
+        src: &Raster<P>,
+        from: R1,
+        op: O,
+    ) where
+        R0: Into<Region>,
+        R1: Into<Region>,
+        O: Blend,
+    {
+        let premultiply = |mut p: P| {
+            let alpha = p.alpha();
+            for c in p.channels_mut()[P::Model::LINEAR].iter_mut() {
+                *c = Premultiplied::encode(*c, alpha);
+            }
+            p
+        };
+        let (to, from) = self.clip_regions(to, src, from);
+        let srows = src.rows(from);
+        let drows = self.rows_mut(to);
+        for (drow, srow) in drows.zip(srows) {
+            for (d, s) in drow.iter_mut().zip(srow) {
+                let mut p = premultiply(*d);
+                composite_premultiplied(&mut p, &premultiply(*s), op);
+                let alpha = p.alpha();
+                for c in p.channels_mut()[P::Model::LINEAR].iter_mut() {
+                    *c = Premultiplied::decode(*c, alpha);
+                }
+                *d = p;
+            }
+        }
+    }
+}
+
+impl<P> Raster<P>
+where
+    P: Pixel<Alpha = Premultiplied>,
+{
+    /// Clamp invalid *premultiplied* pixels.
+    ///
+    /// A *premultiplied* color channel should never be greater than *alpha*.
+    /// Any such channels are clamped to the *alpha* value, so that converting
+    /// to *straight* alpha stays in range.  *Circular* channels, such as hue,
+    /// are not premultiplied and are left unchanged.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::Rgba8p;
+    /// use pix::Raster;
+    ///
+    /// let mut r = Raster::with_color(4, 4, Rgba8p::new(0xC0, 0x20, 0, 0x80));
+    /// r.clamp_premultiplied();
+    /// assert_eq!(r.pixel(0, 0), Rgba8p::new(0x80, 0x20, 0, 0x80));
+    /// ```
+    pub fn clamp_premultiplied(&mut self) {
+        for p in self.pixels.iter_mut() {
+            let alpha = p.alpha();
+            for c in p.channels_mut()[P::Model::LINEAR].iter_mut() {
+                *c = (*c).min(alpha);
+            }
+        }
+    }
+}
+
+impl<P> Raster<P>
+where
+    P: Pixel<Alpha = Premultiplied, Gamma = Linear>,
+{
+    /// Composite a source color to a region of the `Raster`.
+    ///
+    /// * `reg` Region within `self`.  It can be a `Region` struct, tuple of
+    ///   (*x*, *y*, *width*, *height*) or the unit type `()`.  Using `()` has
+    ///   the same result as `Raster::region()`.
+    /// * `clr` Source `Pixel` color.
+    /// * `op` Compositing operation.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::ops::SrcOver;
+    /// use pix::bgr::Bgra8p;
+    /// use pix::Raster;
+    ///
+    /// let mut r = Raster::with_color(100, 100, Bgra8p::new(99, 0, 99, 255));
+    /// let clr = Bgra8p::new(200, 200, 0, 128);
+    /// r.composite_color((20, 40, 25, 50), clr, SrcOver);
+    /// ```
+    pub fn composite_color<R, O>(&mut self, reg: R, clr: P, op: O)
+    where
+        R: Into<Region>,
+        O: Blend,
+    {
+        access::composite_color(self, reg.into(), clr, op);
+    }
+
+    /// Composite a color to a region, with coverage from a function.
+    ///
+    /// * `reg` Region within `self`.
+    /// * `clr` Source color.
+    /// * `coverage` Function of pixel position (`x`, `y`), returning coverage
+    ///   from 0 to 1.
+    /// * `op` Compositing operation.
+    ///
+    /// Coverage is used in the same way as the *alpha* of a matte in
+    /// [composite_matte](#method.composite_matte).  This allows analytic
+    /// shapes, such as radial gradients, to be painted without allocating a
+    /// matte raster.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::ops::SrcOver;
+    /// use pix::rgb::Rgba8p;
+    /// use pix::Raster;
+    ///
+    /// let mut r = Raster::with_clear(100, 100);
+    /// let clr = Rgba8p::new(0xFF, 0xFF, 0xFF, 0xFF);
+    /// r.composite_color_coverage((), clr, |x, y| {
+    ///     let d = ((x - 50) as f32).hypot((y - 50) as f32);
+    ///     1.0 - d / 50.0
+    /// }, SrcOver);
+    /// ```
+    pub fn composite_color_coverage<R, F, O>(
+        &mut self,
+        reg: R,
+        clr: P,
+        coverage: F,
+        op: O,
+    ) where
+        R: Into<Region>,
+        F: Fn(i32, i32) -> f32,
+        O: Blend,
+    {
+        let reg = self.intersection(reg.into());
+        for (j, drow) in self.rows_mut(reg).enumerate() {
+            let y = reg.top() + j as i32;
+            for (i, d) in drow.iter_mut().enumerate() {
+                let x = reg.left() + i as i32;
+                let alpha = P::Chan::from(coverage(x, y));
+                d.composite_channels_alpha(&clr, op, &alpha);
+            }
+        }
+    }
+
+    /// Composite from a matte `Raster` and color.
+    ///
+    /// * `to` Region within `self` (destination).
+    /// * `src` Source `Raster` matte.
+    /// * `from` Region within source `Raster`.
+    /// * `clr` Color to apply to the matte.
+    /// * `op` Compositing operation.
+    ///
+    /// `to` / `from` can be `Region` structs, tuples of (*x*, *y*, *width*,
+    /// *height*) or the unit type `()`.  Using `()` has the same result as
+    /// `Raster::region()`.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::matte::Matte8;
+    /// use pix::ops::SrcOver;
+    /// use pix::rgb::Rgba8p;
+    /// use pix::Raster;
+    ///
+    /// let mut r0 = Raster::with_clear(100, 100);
+    /// let r1 = Raster::with_color(10, 10, Matte8::new(37));
+    /// // ... load image data
+    /// let clr = Rgba8p::new(50, 100, 150, 200);
+    /// r0.composite_matte((30, 50, 10, 10), &r1, (), clr, SrcOver);
+    /// ```
+    pub fn composite_matte<R0, R1, M, O>(
+        &mut self,
+        to: R0,
+        src: &Raster<M>,
+        from: R1,
+        clr: P,
+        op: O,
+    ) where
+        R0: Into<Region>,
+        R1: Into<Region>,
+        M: Pixel<Chan = P::Chan, Model = Matte, Gamma = P::Gamma>,
+        O: Blend,
+    {
+        access::composite_matte(self, to.into(), src, from.into(), clr, op);
+    }
+
+    /// Composite from a source `Raster`.
+    ///
+    /// * `to` Region within `self` (destination).
+    /// * `src` Source `Raster`.
+    /// * `from` Region within source `Raster`.
+    /// * `op` Compositing operation.
+    ///
+    /// `to` / `from` can be `Region` structs, tuples of (*x*, *y*, *width*,
+    /// *height*) or the unit type `()`.  Using `()` has the same result as
+    /// `Raster::region()`.
+    ///
+    /// ```bob
+    /// *------------+      *-------------+
+    /// |            |      |    *------+ |
+    /// | *------+   |      |    |      | |
+    /// | |      |   |      |    | from | |
+    /// | |  to  |   | <--- |    +------+ |
+    /// | +------+   |      |             |
+    /// |            |      |     src     |
+    /// |    self    |      +-------------+
+    /// +------------+
+    /// ```
+    /// The composited `Region` is clamped to the smaller of `to` and `from` in
+    /// both `X` and `Y` dimensions.  Also, `to` and `from` are clipped to
+    /// their respective `Raster` dimensions.
+    ///
+    /// For [Matte](matte/struct.Matte.html) rasters, the result follows the
+    /// blending operation, so `SrcOver` coverage is *a* + *b* - *ab*.  Use
+    /// [composite_matte_union](#method.composite_matte_union) or
+    /// [composite_matte_intersect](#method.composite_matte_intersect) for
+    /// maximum / minimum coverage.
+    ///
+    /// For *straight* alpha formats, use
+    /// [composite_raster_straight](#method.composite_raster_straight).
+    ///
+    /// ### Blend one `Raster` onto another
+    /// ```
+    /// use pix::ops::SrcOver;
+    /// use pix::rgb::Rgba8p;
+    /// use pix::Raster;
+    ///
+    /// let mut r0 = Raster::with_clear(100, 100);
+    /// let r1 = Raster::with_color(5, 5, Rgba8p::new(80, 0, 80, 200));
+    /// // ... load image data
+    /// r0.composite_raster((40, 40), &r1, (), SrcOver);
+    /// ```
+    pub fn composite_raster<R0, R1, O>(
+        &mut self,
+        to: R0,
+        src: &Raster<P>,
+        from: R1,
+        op: O,
+    ) where
+        R0: Into<Region>,
+        R1: Into<Region>,
+        O: Blend,
+    {
+        access::composite_raster(self, to.into(), src, from.into(), op);
+    }
+
+    /// Composite from a source `Raster`, skipping pixels matching a key color.
+    ///
+    /// * `to` Region within `self` (destination).
+    /// * `src` Source `Raster`.
+    /// * `from` Region within source `Raster`.
+    /// * `key` Color key; source pixels matching this are not composited.
+    /// * `tol` Tolerance for each channel when matching `key`, from 0 to 1.
+    /// * `op` Compositing operation.
+    ///
+    /// This is classic color-key blitting, which does not require a separate
+    /// matte.  A source pixel matches the key if every channel differs by no
+    /// more than `tol`, so a small tolerance also skips keys altered by lossy
+    /// compression.  Regions are clipped the same way as
+    /// [composite_raster](#method.composite_raster).
+    ///
+    /// ### Blit a sprite with a magenta key
+    /// ```
+    /// use pix::ops::SrcOver;
+    /// use pix::rgb::Rgba8p;
+    /// use pix::Raster;
+    ///
+    /// let magenta = Rgba8p::new(0xFF, 0x00, 0xFF, 0xFF);
+    /// let mut r0 = Raster::with_clear(100, 100);
+    /// let r1 = Raster::with_color(16, 16, magenta);
+    /// // ... load sprite image data
+    /// r0.composite_raster_keyed((40, 40), &r1, (), magenta, 0.0, SrcOver);
+    /// ```
+    pub fn composite_raster_keyed<R0, R1, O>(
+        &mut self,
+        to: R0,
+        src: &Raster<P>,
+        from: R1,
+        key: P,
+        tol: f32,
+        op: O,
+    ) where
+        R0: Into<Region>,
+        R1: Into<Region>,
+        O: Blend,
+    {
+        let (to, from) = self.clip_regions(to, src, from);
+        let srows = src.rows(from);
+        let drows = self.rows_mut(to);
+        for (drow, srow) in drows.zip(srows) {
+            for (d, s) in drow.iter_mut().zip(srow) {
+                if !within_tol(s, &key, tol) {
+                    d.composite_channels(s, op);
+                }
+            }
+        }
+    }
+
+    /// Composite a source `Raster` centered on `self`.
+    ///
+    /// * `src` Source `Raster`, in any format.
+    ///
+    /// Each source row is converted to the format of `self`, then blended
+    /// using [SrcOver](ops/struct.SrcOver.html), as with
+    /// [composite_raster](#method.composite_raster).  If the source is larger
+    /// than `self`, the offset is negative and it is clipped on all sides.
+    ///
+    /// ### Center a thumbnail
+    /// ```
+    /// use pix::rgb::Rgba8p;
+    /// use pix::Raster;
+    ///
+    /// let mut r0 = Raster::<Rgba8p>::with_clear(100, 100);
+    /// let r1 = Raster::with_color(20, 20, Rgba8p::new(80, 0, 80, 200));
+    /// r0.composite_centered(&r1);
+    /// ```
+    pub fn composite_centered<S>(&mut self, src: &Raster<S>)
+    where
+        S: Pixel,
+        P::Chan: From<S::Chan>,
+    {
+        let x = (self.width - src.width) / 2;
+        let y = (self.height - src.height) / 2;
+        let (to, from) = self.clip_regions((x, y), src, ());
+        let srows = src.rows(from);
+        let drows = self.rows_mut(to);
+        let mut row = Vec::with_capacity(from.width() as usize);
+        for (drow, srow) in drows.zip(srows) {
+            row.clear();
+            row.extend(srow.iter().map(|s| s.convert::<P>()));
+            P::composite_slice(drow, &row, SrcOver);
+        }
+    }
+
+    /// Composite a source `Raster` over `self` ([SrcOver]).
+    ///
+    /// This is shorthand for `composite_raster((), src, (), SrcOver)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `src` dimensions do not match `self`.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::rgb::Rgba8p;
+    /// use pix::Raster;
+    ///
+    /// let mut r0 = Raster::with_color(10, 10, Rgba8p::new(0, 0, 0xFF, 0xFF));
+    /// let r1 = Raster::with_color(10, 10, Rgba8p::new(0x80, 0, 0, 0x80));
+    /// r0.over(&r1);
+    /// assert_eq!(r0.pixel(0, 0), Rgba8p::new(0x80, 0, 0x7F, 0xFF));
+    /// ```
+    /// [SrcOver]: ops/struct.SrcOver.html
+    pub fn over(&mut self, src: &Raster<P>) {
+        self.composite_full(src, SrcOver);
+    }
+
+    /// Add a source `Raster` to `self` ([Plus]).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `src` dimensions do not match `self`.
+    ///
+    /// [Plus]: ops/struct.Plus.html
+    pub fn add(&mut self, src: &Raster<P>) {
+        self.composite_full(src, Plus);
+    }
+
+    /// Multiply `self` by a source `Raster` ([Multiply]).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `src` dimensions do not match `self`.
+    ///
+    /// [Multiply]: ops/struct.Multiply.html
+    pub fn multiply(&mut self, src: &Raster<P>) {
+        self.composite_full(src, Multiply);
+    }
+
+    /// Subtract a source `Raster` from `self` ([Subtract]).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `src` dimensions do not match `self`.
+    ///
+    /// [Subtract]: ops/struct.Subtract.html
+    pub fn subtract(&mut self, src: &Raster<P>) {
+        self.composite_full(src, Subtract);
+    }
+
+    /// Composite an entire source `Raster` with matching dimensions.
+    fn composite_full<O: Blend>(&mut self, src: &Raster<P>, op: O) {
+        assert_eq!(self.width, src.width);
+        assert_eq!(self.height, src.height);
+        self.composite_raster((), src, (), op);
+    }
+}
+
+/// Clip `to` / `from` regions for source / destination rasters
+///
+/// * `dst` Region of entire destination raster.
+/// * `src` Region of entire source raster.
+pub(crate) fn clip_regions(
+    dst: Region,
+    to: Region,
+    src: Region,
+    from: Region,
+) -> (Region, Region) {
+    let (tx, ty) = (to.x.min(0).abs(), to.y.min(0).abs());
+    let (fx, fy) = (from.x.min(0).abs(), from.y.min(0).abs());
+    let to = to.intersection(dst);
+    let from = from.intersection(src);
+    let width = to.width().min(from.width());
+    let height = to.height().min(from.height());
+    let to = Region::new(to.x + fx, to.y + fy, width, height);
+    let from = Region::new(from.x + tx, from.y + ty, width, height);
+    (to, from)
+}
+
+impl<'a, P: Pixel> Rows<'a, P> {
+    /// Create a new row `Iterator`.
+    ///
+    /// * `pixels` All pixels of a raster.
+    /// * `width` Width of the raster.
+    /// * `reg` Region within the raster (already clipped).
+    pub(crate) fn new(pixels: &'a [P], width: i32, reg: Region) -> Self {
+        let width = width.max(1) as usize;
+        let start = reg.y as usize * width;
+        let end = reg.bottom() as usize * width;
+        let slice = &pixels[start..end];
+        let chunks = slice.chunks_exact(width);
+        let x = reg.x as usize;
+        let w = reg.width as usize;
+        let columns = x..x + w;
+        Rows { chunks, columns }
+    }
+}
+
+impl<'a, P: Pixel> Iterator for Rows<'a, P> {
+    type Item = &'a [P];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.chunks.next().map(|s| &s[self.columns.clone()])
+    }
+}
+
+impl<'a, P: Pixel> RowsMut<'a, P> {
+    /// Create a new mutable row `Iterator`.
+    ///
+    /// * `pixels` All pixels of a raster.
+    /// * `width` Width of the raster.
+    /// * `reg` Region within the raster (already clipped).
+    pub(crate) fn new(pixels: &'a mut [P], width: i32, reg: Region) -> Self {
+        let width = width.max(1) as usize;
+        let start = reg.y as usize * width;
+        let end = reg.bottom() as usize * width;
+        let slice = &mut pixels[start..end];
+        let chunks = slice.chunks_exact_mut(width);
+        let x = reg.x as usize;
+        let w = reg.width as usize;
+        let columns = x..x + w;
+        RowsMut { chunks, columns }
+    }
+}
+
+impl<'a, P: Pixel> Iterator for RowsMut<'a, P> {
+    type Item = &'a mut [P];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.chunks.next().map(|s| &mut s[self.columns.clone()])
+    }
+}
+
+impl<P: Pixel> DoubleEndedIterator for RowsMut<'_, P> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.chunks
+            .next_back()
+            .map(|s| &mut s[self.columns.clone()])
+    }
+}
+
+impl From<(i32, i32, u32, u32)> for Region {
+    fn from(r: (i32, i32, u32, u32)) -> Self {
+        Region::new(r.0, r.1, r.2, r.3)
+    }
+}
+
+impl From<(i32, i32)> for Region {
+    fn from(r: (i32, i32)) -> Self {
+        const MAX: u32 = i32::MAX as u32;
+        Region::new(r.0, r.1, MAX, MAX)
+    }
+}
+
+impl From<()> for Region {
+    fn from(_: ()) -> Self {
+        const MAX: u32 = i32::MAX as u32;
+        Region::new(0, 0, MAX, MAX)
+    }
+}
+
+impl Region {
+    /// Create a new `Region`
+    pub fn new(x: i32, y: i32, width: u32, height: u32) -> Self {
+        let width = i32::try_from(width).expect(WIDTH_TOO_BIG);
+        let height = i32::try_from(height).expect(HEIGHT_TOO_BIG);
+        Region {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Get intersection with another `Region`
+    pub fn intersection<R>(self, rhs: R) -> Self
+    where
+        R: Into<Self>,
+    {
+        let rhs = rhs.into();
+        let x0 = self.x.max(rhs.x);
+        let x1 = self.right().min(rhs.right());
+        let y0 = self.y.max(rhs.y);
+        let y1 = self.bottom().min(rhs.bottom());
+        if x0 < x1 && y0 < y1 {
+            let w = (x1 - x0) as u32;
+            let h = (y1 - y0) as u32;
+            Region::new(x0, y0, w, h)
+        } else {
+            Region::default()
+        }
+    }
+
+    /// Get union with another `Region`
+    ///
+    /// The result is the smallest `Region` covering both.  A `Region` with
+    /// zero width or height is ignored.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::Region;
+    ///
+    /// let dirty = Region::new(0, 0, 10, 10).union((20, 5, 10, 10));
+    /// assert_eq!(dirty, Region::new(0, 0, 30, 15));
+    /// ```
+    pub fn union<R>(self, rhs: R) -> Self
+    where
+        R: Into<Self>,
+    {
+        let rhs = rhs.into();
+        if rhs.width <= 0 || rhs.height <= 0 {
+            return self;
+        }
+        if self.width <= 0 || self.height <= 0 {
+            return rhs;
+        }
+        let x0 = self.x.min(rhs.x);
+        let x1 = self.right().max(rhs.right());
+        let y0 = self.y.min(rhs.y);
+        let y1 = self.bottom().max(rhs.bottom());
+        let w = x1.saturating_sub(x0) as u32;
+        let h = y1.saturating_sub(y0) as u32;
+        Region::new(x0, y0, w, h)
+    }
+
+    /// Get the *x* coordinate (same as [left](#method.left))
+    pub fn x(self) -> i32 {
+        self.x
+    }
+
+    /// Get the *y* coordinate (same as [top](#method.top))
+    pub fn y(self) -> i32 {
+        self.y
+    }
+
+    /// Get the left side (*x* coordinate)
+    pub fn left(self) -> i32 {
+        self.x
+    }
+
+    /// Get the top side (*y* coordinate)
+    pub fn top(self) -> i32 {
+        self.y
+    }
+
+    /// Get the width
+    pub fn width(self) -> u32 {
+        self.width as u32
+    }
+
+    /// Get the height
+    pub fn height(self) -> u32 {
+        self.height as u32
+    }
+
+    /// Get right side
+    pub fn right(self) -> i32 {
+        self.x.saturating_add(self.width)
+    }
+
+    /// Get bottom side
+    pub fn bottom(self) -> i32 {
+        self.y.saturating_add(self.height)
+    }
+
+    /// Check if a point is within the `Region`.
+    ///
+    /// Points on the right or bottom side are outside.
+    pub fn contains_point(self, x: i32, y: i32) -> bool {
+        x >= self.left()
+            && x < self.right()
+            && y >= self.top()
+            && y < self.bottom()
+    }
+
+    /// Check if another `Region` is entirely within this one.
+    ///
+    /// A `Region` with zero width or height contains nothing.
+    pub fn contains(self, other: Region) -> bool {
+        self.width > 0
+            && self.height > 0
+            && other.left() >= self.left()
+            && other.top() >= self.top()
+            && other.right() <= self.right()
+            && other.bottom() <= self.bottom()
+    }
+
+    /// Get an `Iterator` of all coordinates within the `Region`.
+    ///
+    /// Coordinates are (*x*, *y*) tuples, in row-major order.
+    ///
+    /// ### Example
+    /// ```
+    /// use pix::Region;
+    ///
+    /// let c: Vec<_> = Region::new(2, 3, 2, 2).coords().collect();
+    /// assert_eq!(c, [(2, 3), (3, 3), (2, 4), (3, 4)]);
+    /// ```
+    pub fn coords(self) -> impl Iterator<Item = (i32, i32)> {
+        let (left, right) = (self.left(), self.right());
+        (self.top()..self.bottom())
+            .flat_map(move |y| (left..right).map(move |x| (x, y)))
+    }
+}
+
+#[cfg(test)]
+#[rustfmt::skip]
+mod test {
+    use crate::bgr::*;
+    use crate::el::Pixel;
+    use crate::gray::*;
+    use crate::hwb::*;
+    use crate::matte::*;
+    use crate::ops::*;
+    use crate::rgb::*;
+    use crate::*;
+
+    #[test]
+    fn region_size() {
+        assert_eq!(std::mem::size_of::<Region>(), 16);
+    }
+
+    #[test]
+    fn buffers() {
+        let buf = vec![0x80; 64];
+        let mut raster = Raster::<Rgba8>::with_u8_buffer(4, 4, buf);
+        *raster.pixel_mut(1, 1) = Rgba8::new(0x40, 0x60, 0x80, 0xA0);
+        let slice: Box<[u8]> = raster.into();
+        let v: Vec<u8> = slice.into();
+        assert_eq!(v.len(), 64);
+        let buf = vec![0xA0; 16];
+        let mut raster = Raster::<Gray8>::with_u8_buffer(4, 4, buf);
+        *raster.pixel_mut(1, 1) = Gray8::new(0xFF);
+        let slice: Box<[u8]> = raster.into();
+        let v: Vec<u8> = slice.into();
+        let b = vec![
+            0xA0, 0xA0, 0xA0, 0xA0,
+            0xA0, 0xFF, 0xA0, 0xA0,
+            0xA0, 0xA0, 0xA0, 0xA0,
+            0xA0, 0xA0, 0xA0, 0xA0,
+        ];
+        assert_eq!(v, b);
+    }
+
+    #[test]
+    fn intersect() -> Result<(), ()> {
+        let r = Region::new(0, 0, 5, 5);
+        assert_eq!(r, Region::new(0, 0, 5, 5));
+        assert_eq!(r, r.intersection(Region::new(0, 0, 10, 10)));
+        assert_eq!(r, r.intersection(Region::new(-5, -5, 10, 10)));
+        assert_eq!(Region::new(2, 2, 3, 3), r.intersection((2, 2)));
+        assert_eq!(Region::new(0, 0, 4, 4), r.intersection((-1, -1, 5, 5)));
+        assert_eq!(Region::new(1, 2, 1, 3), r.intersection((1, 2, 1, 100)));
+        assert_eq!(Region::new(2, 1, 3, 1), r.intersection((2, 1, 100, 1)));
+        Ok(())
+    }
+
+    #[test]
+    fn region_accessors() {
+        let r = Raster::<Gray8>::with_clear(10, 8);
+        let reg = r.intersection((-2, 5, 6, 6));
+        assert_eq!(reg.left(), 0);
+        assert_eq!(reg.top(), 5);
+        assert_eq!((reg.x(), reg.y()), (0, 5));
+        assert_eq!(reg.width(), 4);
+        assert_eq!(reg.height(), 3);
+        assert_eq!(reg.right(), 4);
+        assert_eq!(reg.bottom(), 8);
+        let reg = Region::from(());
+        assert_eq!((reg.right(), reg.bottom()), (i32::MAX, i32::MAX));
+    }
+
+    #[test]
+    fn region_contains() {
+        let r = Region::new(2, 3, 4, 5);
+        assert!(r.contains_point(2, 3));
+        assert!(r.contains_point(5, 7));
+        assert!(!r.contains_point(6, 7));
+        assert!(!r.contains_point(5, 8));
+        assert!(!r.contains_point(1, 3));
+        assert!(!r.contains_point(2, 2));
+        assert!(r.contains(r));
+        assert!(r.contains(Region::new(3, 4, 3, 4)));
+        assert!(!r.contains(Region::new(3, 4, 4, 4)));
+        assert!(!r.contains(Region::new(1, 4, 2, 2)));
+        let empty = Region::new(2, 3, 0, 5);
+        assert!(!empty.contains_point(2, 3));
+        assert!(!empty.contains(empty));
+        let all = Region::from(());
+        assert!(all.contains_point(i32::MAX - 1, 0));
+        assert!(all.contains(r));
+    }
+
+    #[test]
+    fn region_union() {
+        let r = Region::new(2, 3, 4, 5);
+        assert_eq!(r.union(r), r);
+        assert_eq!(r.union((4, 4, 4, 2)), Region::new(2, 3, 6, 5));
+        assert_eq!(r.union((3, 4, 1, 1)), r);
+        assert_eq!(r.union((-8, 20, 2, 2)), Region::new(-8, 3, 14, 19));
+        assert_eq!(r.union(Region::default()), r);
+        assert_eq!(r.union((50, 50, 0, 10)), r);
+        assert_eq!(Region::default().union(r), r);
+        let all = Region::from(());
+        let u = Region::new(-10, -10, 5, 5).union(all);
+        assert_eq!((u.left(), u.width()), (-10, i32::MAX as u32));
+    }
+
+    #[test]
+    fn region_coords() {
+        let c: Vec<_> = Region::new(2, 3, 2, 2).coords().collect();
+        assert_eq!(c, [(2, 3), (3, 3), (2, 4), (3, 4)]);
+        let c: Vec<_> = Region::new(-1, 0, 3, 1).coords().collect();
+        assert_eq!(c, [(-1, 0), (0, 0), (1, 0)]);
+        assert_eq!(Region::new(5, 5, 0, 3).coords().count(), 0);
+        assert_eq!(Region::new(5, 5, 3, 0).coords().count(), 0);
+    }
+
+    #[test]
+    fn with_buffer_rgb8() {
+        let b = vec![
+            0xAA,0x00,0x00, 0x00,0x11,0x22, 0x33,0x44,0x55,
+            0x00,0xBB,0x00, 0x66,0x77,0x88, 0x99,0xAA,0xBB,
+            0x00,0x00,0xCC, 0xCC,0xDD,0xEE, 0xFF,0x00,0x11,
+        ];
+        let r = Raster::<SRgb8>::with_u8_buffer(3, 3, b);
+        let v = vec![
+            SRgb8::new(0xAA, 0x00, 0x00), SRgb8::new(0x00, 0x11, 0x22),
+            SRgb8::new(0x33, 0x44, 0x55),
+            SRgb8::new(0x00, 0xBB, 0x00), SRgb8::new(0x66, 0x77, 0x88),
+            SRgb8::new(0x99, 0xAA, 0xBB),
+            SRgb8::new(0x00, 0x00, 0xCC), SRgb8::new(0xCC, 0xDD, 0xEE),
+            SRgb8::new(0xFF, 0x00, 0x11),
+        ];
+        assert_eq!(r.pixels(), &v[..]);
+    }
+
+    #[test]
+    fn with_buffer_graya16() {
+        let b = vec![
+            0x1001,0x5005, 0x1000,0x3002, 0x5004,0x7006,
+            0x2002,0x6006, 0x9008,0xB00A, 0xD00C,0xF00E,
+            0x3003,0x7007, 0xE00F,0xC00D, 0xA00B,0x8009,
+        ];
+        let r = Raster::<SGraya16>::with_u16_buffer(3, 3, b);
+        let v = vec![
+            SGraya16::new(0x1001, 0x5005), SGraya16::new(0x1000, 0x3002),
+            SGraya16::new(0x5004, 0x7006),
+            SGraya16::new(0x2002, 0x6006), SGraya16::new(0x9008, 0xB00A),
+            SGraya16::new(0xD00C, 0xF00E),
+            SGraya16::new(0x3003, 0x7007), SGraya16::new(0xE00F, 0xC00D),
+            SGraya16::new(0xA00B, 0x8009),
+        ];
+        assert_eq!(r.pixels(), &v[..]);
+    }
+
+    #[test]
+    fn with_pixels_matte32() {
+        let p = vec![
+            Matte32::new(0.25), Matte32::new(0.5), Matte32::new(0.75),
+            Matte32::new(0.5), Matte32::new(0.6), Matte32::new(0.7),
+            Matte32::new(0.85), Matte32::new(0.65), Matte32::new(0.45),
+        ];
+        let r = Raster::with_pixels(3, 3, p.clone());
+        assert_eq!(r.pixels(), &p[..]);
+    }
+
+    #[test]
+    fn pixel_mut_matte8() {
+        let mut r = Raster::<Matte8>::with_clear(3, 3);
+        *r.pixel_mut(0, 0) = Matte8::new(0xFF);
+        *r.pixel_mut(2, 0) = Matte8::new(0x12);
+        *r.pixel_mut(1, 1) = Matte8::new(0x34);
+        *r.pixel_mut(0, 2) = Matte8::new(0x56);
+        *r.pixel_mut(2, 2) = Matte8::new(0x78);
+        let v = vec![
+            Matte8::new(0xFF), Matte8::new(0x00), Matte8::new(0x12),
+            Matte8::new(0x00), Matte8::new(0x34), Matte8::new(0x00),
+            Matte8::new(0x56), Matte8::new(0x00), Matte8::new(0x78),
+        ];
+        assert_eq!(r.pixels(), &v[..]);
+    }
+
+    #[test]
+    fn pixel_mut_matte16() {
+        let mut r = Raster::<Matte16>::with_clear(3, 3);
+        *r.pixel_mut(2, 0) = Matte16::new(0x9ABC);
+        *r.pixel_mut(1, 1) = Matte16::new(0x5678);
+        *r.pixel_mut(0, 2) = Matte16::new(0x1234);
+        *r.pixel_mut(0, 0) = Matte16::new(1.0);
+        *r.pixel_mut(2, 2) = Matte16::new(0x8080);
+        let v = vec![
+            Matte16::new(0xFFFF), Matte16::new(0x0000), Matte16::new(0x9ABC),
+            Matte16::new(0x0000), Matte16::new(0x5678), Matte16::new(0x0000),
+            Matte16::new(0x1234), Matte16::new(0x0000), Matte16::new(0x8080),
+        ];
+        assert_eq!(r.pixels(), &v[..]);
+    }
+
+    #[test]
+    fn invalid_rows() {
+        let r = Raster::<Matte8>::with_clear(10, 10);
+        let mut rows = r.rows((0, 20, 0, 0));
+        assert_eq!(rows.next(), None);
+    }
+
+    #[test]
+    fn raster_with_color() {
+        let r = Raster::with_color(3, 3, Hwb8::new(0x80, 0, 0));
+        let v = [Hwb8::new(0x80, 0, 0); 9];
+        assert_eq!(r.pixels(), &v[..]);
+    }
+
+    #[test]
+    fn copy_color_gray8() {
+        let mut r = Raster::<SGray8>::with_clear(3, 3);
+        r.copy_color((0, 0, 1, 1), SGray8::new(0x23));
+        r.copy_color((10, 10, 1, 1), SGray8::new(0x45));
+        r.copy_color((1, 1, 10, 10), SGray8::new(0xBB));
+        let v = vec![
+            SGray8::new(0x23), SGray8::new(0), SGray8::new(0),
+            SGray8::new(0), SGray8::new(0xBB), SGray8::new(0xBB),
+            SGray8::new(0), SGray8::new(0xBB), SGray8::new(0xBB),
+        ];
+        assert_eq!(r.pixels(), &v[..]);
+    }
+
+    #[test]
+    fn copy_color_srgb8() {
+        let mut r = Raster::<SRgb8>::with_clear(3, 3);
+        r.copy_color((2, -1, 3, 4), SRgb8::new(0xCC, 0xAA, 0xBB));
+        let v = vec![
+            SRgb8::new(0, 0, 0), SRgb8::new(0, 0, 0),
+            SRgb8::new(0xCC, 0xAA, 0xBB),
+            SRgb8::new(0, 0, 0), SRgb8::new(0, 0, 0),
+            SRgb8::new(0xCC, 0xAA, 0xBB),
+            SRgb8::new(0, 0, 0), SRgb8::new(0, 0, 0),
+            SRgb8::new(0xCC, 0xAA, 0xBB),
+        ];
+        assert_eq!(r.pixels(), &v[..]);
+    }
+
+    #[test]
+    fn copy_raster_gray() {
+        let mut g0 = Raster::<Gray8>::with_clear(3, 3);
+        let g1 = Raster::<Gray8>::with_color(3, 3, Gray8::new(0x40));
+        let g2 = Raster::<Gray8>::with_color(3, 3, Gray8::new(0x60));
+        let g3 = Raster::<Gray8>::with_color(3, 3, Gray8::new(0x80));
+        g0.copy_raster((-1, 2, 3, 3), &g1, ());
+        g0.copy_raster((2, -1, 3, 3), &g2, ());
+        g0.copy_raster((-2, -2, 3, 3), &g3, ());
+        let v = vec![
+            Gray8::new(0x80), Gray8::new(0x00), Gray8::new(0x60),
+            Gray8::new(0x00), Gray8::new(0x00), Gray8::new(0x60),
+            Gray8::new(0x40), Gray8::new(0x40), Gray8::new(0x00),
+        ];
+        assert_eq!(g0.pixels(), &v[..]);
+    }
+
+    #[test]
+    fn composite_color_graya8_over() {
+        let clr = Graya8p::new(0x20, 0x40);
+        let mut r = Raster::<Graya8p>::with_color(2, 2, clr);
+        r.composite_color((0, 0, 3, 1), Graya8p::new(0x60, 0xA0), SrcOver);
+        r.composite_color((1, 1, 4, 4), Graya8p::new(0x80, 0x80), SrcOver);
+        let v = [
+            Graya8p::new(0x6B, 0xB7), Graya8p::new(0x6B, 0xB7),
+            Graya8p::new(0x20, 0x40), Graya8p::new(0x8F, 0x9F),
+        ];
+        assert_eq!(r.pixels(), &v[..]);
+    }
+
+    #[test]
+    fn composite_raster_rgb() {
+        let mut rgb = Raster::<Rgba8p>::with_clear(3, 3);
+        let gray = Raster::with_color(3, 3, Gray16::new(0x8000));
+        let r = Raster::with_raster(&gray);
+        rgb.composite_raster((), &r, (0, 1), Src);
+        let mut v = vec![Rgba8p::new(0x80, 0x80, 0x80, 0xFF); 6];
+        v.extend_from_slice(&[Rgba8p::new(0, 0, 0, 0); 3]);
+        assert_eq!(rgb.pixels(), &v[..]);
+    }
+
+    #[test]
+    fn composite_raster_clear() {
+        let clr = Rgba8p::new(0x20, 0x40, 0x60, 0x80);
+        let mut r = Raster::with_color(4, 4, clr);
+        let src = Raster::with_color(2, 2, Rgba8p::new(0xFF, 0, 0, 0xFF));
+        r.composite_raster((1, 1, 2, 2), &src, (), Clear);
+        for y in 0..4 {
+            for x in 0..4 {
+                let inside = (1..3).contains(&x) && (1..3).contains(&y);
+                let p = if inside { Rgba8p::default() } else { clr };
+                assert_eq!(r.pixel(x, y), p);
+            }
+        }
+    }
+
+    #[test]
+    fn composite_raster_over_clear() {
+        let src = Raster::with_pixels(
+            2,
+            1,
+            vec![
+                Rgba8p::new(0x10, 0x20, 0x30, 0x40),
+                Rgba8p::new(0x80, 0x40, 0x00, 0xC0),
+            ],
+        );
+        let mut over = Raster::<Rgba8p>::with_clear(2, 1);
+        over.composite_raster((), &src, (), SrcOver);
+        let mut copy = Raster::<Rgba8p>::with_clear(2, 1);
+        copy.composite_raster((), &src, (), Src);
+        assert_eq!(over.pixels(), src.pixels());
+        assert_eq!(over.pixels(), copy.pixels());
+    }
+
+    #[test]
+    fn composite_raster_straight() {
+        let src = Raster::with_pixels(2, 1, vec![
+            Rgba16::new(0x8000, 0x2000, 0x1234, 0x4000),
+            Rgba16::new(0xFFFF, 0x8000, 0, 0xC000),
+        ]);
+        let clr = Rgba16::new(0x1000, 0xF000, 0x8000, 0x8000);
+        let mut r = Raster::with_color(3, 1, clr);
+        r.composite_raster_straight((1, 0), &src, (), SrcOver);
+        // same as blending after conversion to premultiplied
+        let mut rp = Raster::<Rgba16p>::with_color(3, 1, clr.convert());
+        let sp = Raster::<Rgba16p>::with_raster(&src);
+        rp.composite_raster((1, 0), &sp, (), SrcOver);
+        let v: Vec<Rgba16> = rp.pixels().iter().map(|p| p.convert()).collect();
+        assert_eq!(r.pixels(), &v[..]);
+        assert_eq!(r.pixel(0, 0), clr);
+        r.composite_raster_straight((), &src, (), Clear);
+        assert_eq!(r.pixel(0, 0), Rgba16::default());
+        assert_eq!(r.pixel(2, 0), v[2]);
+    }
+
+    #[test]
+    fn composite_matte_full() {
+        let mut r = Raster::<Rgba8p>::with_clear(2, 2);
+        let mut m = Raster::<Matte8>::with_clear(2, 2);
+        *m.pixel_mut(0, 0) = Matte8::new(0xFF);
+        *m.pixel_mut(1, 1) = Matte8::new(0x80);
+        let v = [
+            Matte8::new(0xFF), Matte8::new(0),
+            Matte8::new(0), Matte8::new(0x80),
+        ];
+        assert_eq!(m.pixels(), &v);
+        let c = Rgba8p::new(0xFF, 0x80, 0x40, 0xFF);
+        r.composite_matte((), &m, (), c, SrcOver);
+        let v = [
+            Rgba8p::new(0xFF, 0x80, 0x40, 0xFF), Rgba8p::new(0, 0, 0, 0),
+            Rgba8p::new(0, 0, 0, 0), Rgba8p::new(0x80, 0x40, 0x20, 0x80),
+        ];
+        assert_eq!(r.pixels(), &v);
+    }
+
+    #[test]
+    fn composite_matte_smaller() {
+        let mut r = Raster::<Rgba8p>::with_clear(3, 3);
+        let m = vec![
+            Matte8::new(0xFF), Matte8::new(0x80),
+            Matte8::new(0x40), Matte8::new(0x20),
+        ];
+        let m = Raster::<Matte8>::with_pixels(2, 2, m);
+        let c = Rgba8p::new(0x40, 0x80, 0x60, 0x80);
+        r.composite_matte((1, 1, 4, 4), &m, (), c, SrcOver);
+        let v = [
+            Rgba8p::new(0, 0, 0, 0),
+            Rgba8p::new(0, 0, 0, 0),
+            Rgba8p::new(0, 0, 0, 0),
+
+            Rgba8p::new(0, 0, 0, 0),
+            Rgba8p::new(0x40, 0x80, 0x60, 0x80),
+            Rgba8p::new(0x20, 0x40, 0x30, 0x40),
+
+            Rgba8p::new(0, 0, 0, 0),
+            Rgba8p::new(0x10, 0x20, 0x18, 0x20),
+            Rgba8p::new(0x08, 0x10, 0x0C, 0x10),
+        ];
+        assert_eq!(r.pixels(), &v);
+    }
+
+    #[test]
+    fn composite_matte_gray() {
+        let mut g0 = Raster::<Graya8p>::with_clear(2, 2);
+        let g1 = Raster::<Matte8>::with_color(3, 3, Matte8::new(0x40));
+        let g2 = Raster::<Matte8>::with_color(3, 3, Matte8::new(0x60));
+        let g3 = Raster::<Matte8>::with_color(3, 3, Matte8::new(0x80));
+        let clr = Graya8p::new(0xFF, 0xFF);
+        g0.composite_matte((1, 1, 3, 3), &g1, (), clr, SrcOver);
+        g0.composite_matte((1, -2, 3, 3), &g2, (), clr, SrcOver);
+        g0.composite_matte((-2, -2, 3, 3), &g3, (), clr, SrcOver);
+        let v = [
+            Graya8p::new(0x80, 0x80), Graya8p::new(0x60, 0x60),
+            Graya8p::new(0x00, 0x00), Graya8p::new(0x40, 0x40),
+        ];
+        assert_eq!(g0.pixels(), &v[..]);
+    }
+
+    #[test]
+    fn composite_centered() {
+        let mut r = Raster::<Rgba8p>::with_clear(4, 4);
+        let c = Rgba8p::new(0x40, 0x80, 0x60, 0xFF);
+        r.composite_centered(&Raster::with_color(2, 2, c));
+        let z = Rgba8p::new(0, 0, 0, 0);
+        let v = [
+            z, z, z, z,
+            z, c, c, z,
+            z, c, c, z,
+            z, z, z, z,
+        ];
+        assert_eq!(r.pixels(), &v);
+        let mut r = Raster::<Rgba8p>::with_clear(2, 2);
+        r.composite_centered(&Raster::with_color(4, 4, c));
+        assert_eq!(r.pixels(), &[c; 4]);
+        // source in a different format
+        let mut r = Raster::<Rgba8p>::with_clear(3, 1);
+        let red = SRgba8::new(0xFF, 0, 0, 0xFF);
+        r.composite_centered(&Raster::with_color(1, 1, red));
+        assert_eq!(r.pixel(1, 0), Rgba8p::new(0xFF, 0, 0, 0xFF));
+        assert_eq!(r.pixel(0, 0), z);
+    }
+
+    #[test]
+    fn composite_raster_keyed() {
+        let k = Rgba8p::new(0xFF, 0x00, 0xFF, 0xFF);
+        let g = Rgba8p::new(0x00, 0x80, 0x00, 0xFF);
+        let b = Rgba8p::new(0x00, 0x00, 0x40, 0xFF);
+        let mut r = Raster::with_color(3, 3, b);
+        let mut s = Raster::with_color(2, 2, k);
+        *s.pixel_mut(0, 0) = g;
+        *s.pixel_mut(1, 1) = g;
+        *s.pixel_mut(1, 0) = Rgba8p::new(0xFD, 0x02, 0xFF, 0xFF);
+        let mut r0 = r.clone();
+        r.composite_raster_keyed((1, 1), &s, (), k, 0.01, SrcOver);
+        let v = [
+            b, b, b,
+            b, g, b,
+            b, b, g,
+        ];
+        assert_eq!(r.pixels(), &v);
+        // near-key pixel is composited with no tolerance
+        r0.composite_raster_keyed((1, 1), &s, (), k, 0.0, SrcOver);
+        assert_eq!(r0.pixel(2, 1), s.pixel(1, 0));
+        assert_eq!(r0.pixel(1, 2), b);
+    }
+
+    #[test]
+    fn transpose() {
+        let r = Raster::<SGray8>::with_pixels(2, 3, vec![
+            SGray8::new(1), SGray8::new(2),
+            SGray8::new(3), SGray8::new(4),
+            SGray8::new(5), SGray8::new(6),
+        ]);
+        let t = r.transpose();
+        assert_eq!(t.width(), 3);
+        assert_eq!(t.height(), 2);
+        let v = [
+            SGray8::new(1), SGray8::new(3), SGray8::new(5),
+            SGray8::new(2), SGray8::new(4), SGray8::new(6),
+        ];
+        assert_eq!(t.pixels(), &v);
+        assert_eq!(t.transpose().pixels(), r.pixels());
+        let mut q = r.rotate_quarter(1);
+        q.flip_horizontal();
+        assert_eq!(q.pixels(), t.pixels());
+    }
+
+    #[test]
+    fn vignette() {
+        let mut r = Raster::with_color(5, 3, Rgb32::new(1.0, 0.5, 0.25));
+        r.vignette(0.5);
+        assert_eq!(r.pixel(2, 1), Rgb32::new(1.0, 0.5, 0.25));
+        assert_eq!(r.pixel(0, 0), Rgb32::new(0.5, 0.25, 0.125));
+        assert_eq!(r.pixel(4, 2), Rgb32::new(0.5, 0.25, 0.125));
+        let mut r = Raster::with_color(5, 3, SRgb8::new(0xFF, 0x80, 0x40));
+        r.vignette(0.5);
+        assert_eq!(r.pixel(2, 1), SRgb8::new(0xFF, 0x80, 0x40));
+        assert_eq!(r.pixel(4, 0), SRgb8::new(0xBC, 0x5C, 0x2C));
+        let mut r = Raster::with_color(5, 3, SRgb8::new(0xFF, 0x80, 0x40));
+        r.vignette(0.0);
+        assert_eq!(r.pixels(), &[SRgb8::new(0xFF, 0x80, 0x40); 15]);
+    }
+
+    #[test]
+    fn chromatic_aberration() {
+        let k = Rgb32::new(0.0, 0.0, 0.0);
+        let w = Rgb32::new(1.0, 1.0, 1.0);
+        let mut r = Raster::with_pixels(9, 1, vec![k, k, k, k, k, k, w, w, w]);
+        r.chromatic_aberration(0.0);
+        assert_eq!(r.pixels(), &[k, k, k, k, k, k, w, w, w]);
+        r.chromatic_aberration(2.0);
+        // red fringe on the dark side
+        assert_eq!(r.pixel(5, 0), Rgb32::new(0.5, 0.0, 0.0));
+        // blue fringe (missing blue) on the light side
+        assert_eq!(r.pixel(6, 0), Rgb32::new(1.0, 1.0, 0.0));
+        assert_eq!(r.pixel(4, 0), k);
+        assert_eq!(r.pixel(8, 0), w);
+    }
+
+    #[test]
+    fn scale2x() {
+        let a = SGray8::new(0x00);
+        let b = SGray8::new(0xFF);
+        let r = Raster::with_pixels(2, 2, vec![a, b, b, b]);
+        let r = r.scale2x();
+        assert_eq!(r.width(), 4);
+        assert_eq!(r.height(), 4);
+        let v = [
+            a, a, b, b,
+            a, b, b, b,
+            b, b, b, b,
+            b, b, b, b,
+        ];
+        assert_eq!(r.pixels(), &v);
+    }
+
+    #[test]
+    fn average_color() {
+        let mut r = Raster::<SRgb8>::with_color(4, 4, SRgb8::new(0xFF, 0, 0));
+        r.copy_color((2, 0, 2, 4), SRgb8::new(0, 0, 0xFF));
+        assert_eq!(r.average_color(), SRgb8::new(0xBC, 0, 0xBC));
+        let mut r = Raster::<SRgba8>::with_clear(2, 2);
+        assert_eq!(r.average_color(), SRgba8::new(0, 0, 0, 0));
+        *r.pixel_mut(1, 1) = SRgba8::new(0x20, 0x40, 0x60, 0xFF);
+        assert_eq!(r.average_color(), SRgba8::new(0x20, 0x40, 0x60, 0x40));
+    }
+
+    #[test]
+    fn integral_image() {
+        let r = Raster::with_color(3, 2, SGray8::new(0x10));
+        let sat = r.integral_image();
+        assert_eq!(sat.len(), 6);
+        for y in 0..2 {
+            for x in 0..3 {
+                let area = (x as u64 + 1) * (y as u64 + 1);
+                assert_eq!(sat[y * 3 + x], 0x10 * area);
+            }
+        }
+        let r = Raster::with_color(2, 2, SRgba16::new(1, 2, 3, 4));
+        assert_eq!(r.integral_image()[12..], [4, 8, 12, 16]);
+        let r = Raster::with_color(2, 2, SGray32::new(0.5));
+        assert_eq!(r.integral_image()[3], 32768 * 4);
+    }
+
+    #[test]
+    fn box_blur_fast() {
+        let mut r = Raster::<Gray8>::with_clear(7, 5);
+        for (i, p) in r.pixels_mut().iter_mut().enumerate() {
+            *p = Gray8::new((i * 97 % 256) as u8);
+        }
+        for radius in 0..4 {
+            let b = r.box_blur_fast(radius as u32);
+            for y in 0..5 {
+                for x in 0..7 {
+                    let mut sum = 0;
+                    let mut count = 0;
+                    for yy in (y - radius).max(0)..=(y + radius).min(4) {
+                        for xx in (x - radius).max(0)..=(x + radius).min(6) {
+                            let v = Gray::value(r.pixel(xx, yy));
+                            sum += i32::from(u8::from(v));
+                            count += 1;
+                        }
+                    }
+                    let v = i32::from(u8::from(Gray::value(b.pixel(x, y))));
+                    assert!((v - sum / count).abs() <= 1);
+                }
+            }
+        }
+        assert_eq!(r.box_blur_fast(0).pixels(), r.pixels());
+    }
+
+    #[test]
+    fn box_blur_fast_float() {
+        // values far below one 16-bit step must not be quantized away
+        let r = Raster::with_color(6, 4, Gray32::new(2.0e-6));
+        for p in r.box_blur_fast(1).pixels() {
+            let v = f32::from(Gray::value(*p));
+            assert!((v - 2.0e-6).abs() < 1.0e-9, "{v}");
+        }
+        let mut r = Raster::with_clear(3, 1);
+        *r.pixel_mut(0, 0) = Gray32::new(0.3);
+        let b = r.box_blur_fast(1);
+        assert!((f32::from(Gray::value(b.pixel(0, 0))) - 0.15).abs() < 1e-6);
+        assert!((f32::from(Gray::value(b.pixel(1, 0))) - 0.1).abs() < 1e-6);
+        assert_eq!(b.pixel(2, 0), Gray32::new(0.0));
+    }
+
+    #[test]
+    fn composite_matte_union() {
+        let mut r = Raster::with_color(3, 1, Matte8::new(0x40));
+        *r.pixel_mut(2, 0) = Matte8::new(0xC0);
+        let s = Raster::with_color(2, 1, Matte8::new(0x80));
+        r.composite_matte_union((1, 0), &s, ());
+        let v = [Matte8::new(0x40), Matte8::new(0x80), Matte8::new(0xC0)];
+        assert_eq!(r.pixels(), &v);
+    }
+
+    #[test]
+    fn composite_matte_intersect() {
+        let mut r = Raster::with_color(3, 1, Matte8::new(0x40));
+        *r.pixel_mut(2, 0) = Matte8::new(0xC0);
+        let s = Raster::with_color(2, 1, Matte8::new(0x80));
+        r.composite_matte_intersect((1, 0), &s, ());
+        let v = [Matte8::new(0x40), Matte8::new(0x40), Matte8::new(0x80)];
+        assert_eq!(r.pixels(), &v);
+    }
+
+    #[test]
+    fn changed_pixels() {
+        let r0 = Raster::<SGray8>::with_clear(4, 3);
+        let mut r1 = r0.clone();
+        assert_eq!(r0.changed_pixels(&r1).count(), 0);
+        *r1.pixel_mut(3, 0) = SGray8::new(0x10);
+        *r1.pixel_mut(1, 2) = SGray8::new(0x20);
+        let v: Vec<_> = r0.changed_pixels(&r1).collect();
+        assert_eq!(v, [(3, 0, SGray8::new(0x10)), (1, 2, SGray8::new(0x20))]);
+    }
+
+    #[test]
+    fn rle_round_trip() {
+        let mut r = Raster::<SRgb8>::with_color(300, 4, SRgb8::new(1, 2, 3));
+        r.copy_color((10, 1, 50, 2), SRgb8::new(0xFF, 0x80, 0));
+        *r.pixel_mut(299, 3) = SRgb8::new(9, 8, 7);
+        let rle = r.to_rle();
+        assert!(rle.len() < r.as_u8_slice().len());
+        assert_eq!(&rle[..8], &[255, 1, 2, 3, 45, 1, 2, 3]);
+        let r1 = Raster::<SRgb8>::from_rle(300, 4, &rle);
+        assert_eq!(r1.as_u8_slice(), r.as_u8_slice());
+    }
+
+    #[test]
+    #[should_panic]
+    fn rle_invalid() {
+        let rle = [3, 1, 2, 3];
+        Raster::<SRgb8>::from_rle(2, 1, &rle);
+    }
+
+    #[test]
+    fn transform_nearest() {
+        let mut r = Raster::<SGray8>::with_clear(4, 2);
+        for (i, p) in r.pixels_mut().iter_mut().enumerate() {
+            *p = SGray8::new(i as u8);
+        }
+        let t = r.transform(2, 4, |src, x, y| {
+            let sx = x * src.width() as i32 / 2;
+            let sy = y * src.height() as i32 / 4;
+            src.pixel(sx, sy)
+        });
+        let v = [
+            SGray8::new(0), SGray8::new(2),
+            SGray8::new(0), SGray8::new(2),
+            SGray8::new(4), SGray8::new(6),
+            SGray8::new(4), SGray8::new(6),
+        ];
+        assert_eq!(t.pixels(), &v);
+        let t: Raster<SGray16> = r.transform(4, 2, |src, x, y| {
+            src.pixel(x, y).convert()
+        });
+        assert_eq!(t.pixels(), Raster::<SGray16>::with_raster(&r).pixels());
+    }
+
+    #[test]
+    fn u32_argb_round_trip() {
+        let argb = [0x8012_3456, 0xFFAB_CDEF, 0x0000_0000, 0x7F7F_7F7F];
+        let r = Raster::<SRgba8>::from_u32_argb(2, 2, &argb);
+        assert_eq!(r.pixel(0, 0), SRgba8::new(0x12, 0x34, 0x56, 0x80));
+        assert_eq!(r.to_u32_argb(), argb);
+        let r = Raster::<SBgra8>::from_u32_argb(2, 2, &argb);
+        assert_eq!(r.pixel(1, 0), SBgra8::new(0xEF, 0xCD, 0xAB, 0xFF));
+        assert_eq!(r.to_u32_argb(), argb);
+        let r = Raster::with_color(1, 1, SRgb8::new(1, 2, 3));
+        assert_eq!(r.to_u32_argb(), [0xFF01_0203]);
+    }
+
+    #[test]
+    fn empty_rows() {
+        let r = Raster::<SGray8>::with_clear(0, 5);
+        assert_eq!(r.rows(()).count(), 0);
+        let r = Raster::<SGray8>::with_row_fn(0, 5, |_y, _row| ());
+        assert_eq!(r.pixels().len(), 0);
+    }
+
+    #[test]
+    fn fold() {
+        let mut r = Raster::<SGray8>::with_color(3, 3, SGray8::new(0x40));
+        *r.pixel_mut(2, 1) = SGray8::new(0xD0);
+        *r.pixel_mut(0, 2) = SGray8::new(0x90);
+        let max = r.fold(0, |m, p| m.max(u8::from(Gray::value(p))));
+        assert_eq!(max, 0xD0);
+    }
+
+    #[test]
+    fn map() {
+        let r = Raster::<SRgb8>::with_row_fn(4, 3, |y, row| {
+            for (x, p) in row.iter_mut().enumerate() {
+                *p = SRgb8::new(x as u8 * 0x20, y as u8 * 0x30, 0x28);
+            }
+        });
+        let g = r.map(|p| {
+            let v = Rgb::red(p).max(Rgb::green(p)).max(Rgb::blue(p));
+            SGray8::new(v)
+        });
+        assert_eq!(g.width(), 4);
+        assert_eq!(g.height(), 3);
+        assert_eq!(g.pixel(0, 0), SGray8::new(0x28));
+        assert_eq!(g.pixel(1, 1), SGray8::new(0x30));
+        assert_eq!(g.pixel(3, 0), SGray8::new(0x60));
+        assert_eq!(g.pixel(0, 2), SGray8::new(0x60));
+    }
+
+    #[test]
+    fn zip_map() {
+        let a = Raster::<SGray8>::with_row_fn(5, 4, |y, row| {
+            for (x, p) in row.iter_mut().enumerate() {
+                *p = SGray8::new(x as u8 * 0x30 + y as u8);
+            }
+        });
+        let b = Raster::with_color(5, 4, SGray8::new(0x50));
+        let diff = |p: SGray8, q: SGray8| {
+            let (p, q) = (u8::from(Gray::value(p)), u8::from(Gray::value(q)));
+            SGray8::new(p.abs_diff(q))
+        };
+        let d = a.zip_map(&a, diff);
+        assert!(d.pixels().iter().all(|p| *p == SGray8::new(0)));
+        let d = a.zip_map(&b, diff);
+        assert_eq!(d.pixel(0, 1), SGray8::new(0x4F));
+        assert_eq!(d.pixel(3, 2), SGray8::new(0x42));
+    }
+
+    #[test]
+    #[should_panic]
+    fn zip_map_size() {
+        let a = Raster::<SGray8>::with_clear(5, 4);
+        let b = Raster::<SGray8>::with_clear(4, 5);
+        a.zip_map(&b, |p, _q| p);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_fold() {
+        let r = Raster::<SRgb16>::with_row_fn(37, 23, |y, row| {
+            for (x, p) in row.iter_mut().enumerate() {
+                *p = SRgb16::new(x as u16 * 97, y as u16 * 31, 5);
+            }
+        });
+        let sum = |s: u64, p: SRgb16| s + u64::from(u16::from(Rgb::red(p)));
+        assert_eq!(r.par_fold(0, sum, |a, b| a + b), r.fold(0, sum));
+        let r = Raster::<SRgb16>::with_clear(0, 4);
+        assert_eq!(r.par_fold(7, sum, |a, b| a + b), 7);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_rows_mut() {
+        use rayon::prelude::*;
+        let mut r = Raster::<SGray16>::with_clear(19, 41);
+        r.par_rows_mut().enumerate().for_each(|(y, row)| {
+            row.fill(SGray16::new(y as u16));
+        });
+        for (y, row) in r.rows(()).enumerate() {
+            assert!(row.iter().all(|p| *p == SGray16::new(y as u16)));
+        }
+        r.par_clear();
+        assert!(r.pixels().iter().all(|p| *p == SGray16::default()));
+        let mut r = Raster::<SGray16>::with_clear(0, 3);
+        assert_eq!(r.par_rows_mut().count(), 0);
+        r.par_clear();
+    }
+
+    #[test]
+    fn gray_matte() {
+        let mut r = Raster::<SGray8>::with_clear(3, 2);
+        *r.pixel_mut(1, 0) = SGray8::new(0x40);
+        *r.pixel_mut(2, 1) = SGray8::new(0xC0);
+        let m: Raster<Matte8> = r.to_matte();
+        let v = [0x00, 0x40, 0x00, 0x00, 0x00, 0xC0].map(Matte8::new);
+        assert_eq!(m.pixels(), &v);
+        let g: Raster<SGray8> = m.to_gray();
+        assert_eq!(g.pixels(), r.pixels());
+        let g: Raster<Graya8> = m.to_gray();
+        assert_eq!(g.pixel(2, 1), Graya8::new(0xC0, 0xFF));
+        let m: Raster<Matte8> = g.to_matte();
+        assert_eq!(m.pixels(), &v);
+    }
+
+    #[test]
+    fn to_web_safe() {
+        let r = Raster::with_color(2, 2, SRgb8::new(0x19, 0x1A, 0x7F));
+        let w = r.to_web_safe();
+        assert_eq!(w.pixels(), &[SRgb8::new(0x00, 0x33, 0x66); 4]);
+        let safe = [0x00, 0x33, 0x66, 0x99, 0xCC, 0xFF];
+        let r = Raster::with_row_fn(6, 6, |y, row: &mut [SRgb8]| {
+            for (x, p) in row.iter_mut().enumerate() {
+                *p = SRgb8::new(safe[x], safe[y as usize], safe[5 - x]);
+            }
+        });
+        assert_eq!(r.to_web_safe().pixels(), r.pixels());
+        let r = Raster::with_color(1, 1, SRgba8::new(0xFF, 0x80, 0, 0x40));
+        let w = r.to_web_safe();
+        assert_eq!(w.pixel(0, 0), SRgb8::new(0xFF, 0x99, 0x00));
+    }
+
+    #[test]
+    fn morton_order() {
+        let r = Raster::with_row_fn(4, 4, |y, row: &mut [SGray8]| {
+            for (x, p) in row.iter_mut().enumerate() {
+                *p = SGray8::new(y as u8 * 4 + x as u8);
+            }
+        });
+        let m = r.to_morton_order();
+        let v = [0, 1, 4, 5, 2, 3, 6, 7, 8, 9, 12, 13, 10, 11, 14, 15];
+        assert_eq!(m, v.map(SGray8::new));
+        assert_eq!(Raster::from_morton_order(4, 4, &m).pixels(), r.pixels());
+        let r = Raster::with_row_fn(2, 8, |y, row: &mut [SGray8]| {
+            for (x, p) in row.iter_mut().enumerate() {
+                *p = SGray8::new(y as u8 * 2 + x as u8);
+            }
+        });
+        let m = r.to_morton_order();
+        assert_eq!(Raster::from_morton_order(2, 8, &m).pixels(), r.pixels());
+    }
+
+    #[test]
+    #[should_panic]
+    fn morton_not_pow2() {
+        Raster::<SGray8>::with_clear(3, 4).to_morton_order();
+    }
+
+    #[test]
+    fn composite_color_coverage() {
+        let mut r = Raster::<Rgba8p>::with_clear(9, 9);
+        let clr = Rgba8p::new(0xFF, 0xFF, 0xFF, 0xFF);
+        r.composite_color_coverage((), clr, |x, y| {
+            1.0 - ((x - 4) as f32).hypot((y - 4) as f32) / 4.0
+        }, SrcOver);
+        assert_eq!(r.pixel(4, 4), clr);
+        assert_eq!(r.pixel(1, 4), Rgba8p::new(0x40, 0x40, 0x40, 0x40));
+        assert_eq!(r.pixel(4, 6), Rgba8p::new(0x80, 0x80, 0x80, 0x80));
+        assert_eq!(r.pixel(0, 0), Rgba8p::default());
+    }
+
+    #[test]
+    fn approx_eq() {
+        let r0 = Raster::with_color(3, 3, Rgba32::new(0.5, 0.5, 0.5, 1.0));
+        let mut r1 = r0.clone();
+        *r1.pixel_mut(1, 2) = Rgba32::new(0.5, 0.500_001, 0.5, 1.0);
+        assert!(r0.approx_eq(&r1, 1e-4));
+        assert!(!r0.approx_eq(&r1, 0.0));
+        assert!(r0.approx_eq(&r0, 0.0));
+        let r2 = Raster::with_color(3, 2, Rgba32::new(0.5, 0.5, 0.5, 1.0));
+        assert!(!r0.approx_eq(&r2, 1.0));
+    }
+
+    #[test]
+    fn to_normal_map() {
+        let r = Raster::with_color(4, 4, SGray8::new(0x55));
+        let n = r.to_normal_map(4.0);
+        assert_eq!(n.pixels(), &[SRgb8::new(128, 128, 255); 16]);
+        let r = Raster::with_row_fn(4, 4, |_y, row: &mut [SGray8]| {
+            for (x, p) in row.iter_mut().enumerate() {
+                *p = SGray8::new(x as u8 * 0x20);
+            }
+        });
+        let n = r.to_normal_map(1.0);
+        let p = n.pixel(1, 1);
+        assert!(u8::from(Rgb::red(p)) < 128);
+        assert_eq!(u8::from(Rgb::green(p)), 128);
+        assert!(u8::from(Rgb::blue(p)) < 255);
+        let n = r.transpose().to_normal_map(1.0);
+        let p = n.pixel(1, 1);
+        assert_eq!(u8::from(Rgb::red(p)), 128);
+        assert!(u8::from(Rgb::green(p)) > 128);
+    }
+
+    #[test]
+    fn pixels_boustrophedon() {
+        let r = Raster::with_row_fn(4, 3, |y, row: &mut [SGray8]| {
+            for (x, p) in row.iter_mut().enumerate() {
+                *p = SGray8::new(y as u8 * 4 + x as u8);
+            }
+        });
+        let v: Vec<_> = r.pixels_boustrophedon().collect();
+        assert_eq!(v.len(), 12);
+        for (x, y, p) in &v {
+            assert_eq!(*p, r.pixel(*x, *y));
+        }
+        let x: Vec<_> = v.iter().map(|(x, _, _)| *x).collect();
+        assert_eq!(x, [0, 1, 2, 3, 3, 2, 1, 0, 0, 1, 2, 3]);
+        let y: Vec<_> = v.iter().map(|(_, y, _)| *y).collect();
+        assert_eq!(y, [0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2]);
+    }
+
+    #[test]
+    fn composite_lcd() {
+        let mut r = Raster::with_color(3, 2, Rgba8p::new(0, 0, 0, 0));
+        let mut cov = Raster::with_clear(3, 2);
+        *cov.pixel_mut(1, 0) = Rgb8::new(0xFF, 0x80, 0x00);
+        *cov.pixel_mut(2, 1) = Rgb8::new(0x40, 0x40, 0x40);
+        let clr = Rgba8p::new(0xFF, 0xFF, 0xFF, 0xFF);
+        r.composite_lcd((), &cov, (), clr);
+        assert_eq!(r.pixel(0, 0), Rgba8p::new(0, 0, 0, 0));
+        assert_eq!(r.pixel(1, 0), Rgba8p::new(0xFF, 0x80, 0x00, 0xFF));
+        assert_eq!(r.pixel(2, 1), Rgba8p::new(0x40, 0x40, 0x40, 0x40));
+        let mut r = Raster::with_color(1, 1, Rgba8p::new(0x80, 0, 0, 0xFF));
+        let cov = Raster::with_color(1, 1, Rgb8::new(0x80, 0x80, 0x00));
+        r.composite_lcd((), &cov, (), Rgba8p::new(0, 0x80, 0x80, 0x80));
+        assert_eq!(r.pixel(0, 0), Rgba8p::new(0x60, 0x40, 0, 0xFF));
+    }
+
+    #[test]
+    fn checksum() {
+        let r0 = Raster::with_color(4, 3, Rgba16::new(0x1234, 0, 0xFFFF, 1));
+        let mut r1 = r0.clone();
+        assert_eq!(r0.checksum(), r1.checksum());
+        *r1.pixel_mut(3, 2) = Rgba16::new(0x1234, 0, 0xFFFF, 2);
+        assert_ne!(r0.checksum(), r1.checksum());
+        let r2 = Raster::with_color(3, 4, Rgba16::new(0x1234, 0, 0xFFFF, 1));
+        assert_ne!(r0.checksum(), r2.checksum());
+        let r = Raster::with_color(1, 1, Gray16::new(0x0102));
+        assert_eq!(r.checksum(), 0x2DCB_15FB_8B9E_581C);
+    }
+
+    #[test]
+    fn stretch_contrast() {
+        let mut r = Raster::with_row_fn(8, 1, |_y, row: &mut [Gray8]| {
+            for (x, p) in row.iter_mut().enumerate() {
+                *p = Gray8::new(0x70 + x as u8 * 4);
+            }
+        });
+        let mut r1 = r.clone();
+        r.auto_stretch();
+        assert_eq!(r.pixel(0, 0), Gray8::new(0));
+        assert_eq!(r.pixel(7, 0), Gray8::new(0xFF));
+        assert!(Gray::value(r.pixel(3, 0)) > Gray::value(Gray8::new(0x60)));
+        r1.stretch_contrast(0x74 as f32 / 255.0, 0x78 as f32 / 255.0);
+        assert_eq!(r1.pixel(0, 0), Gray8::new(0));
+        assert_eq!(r1.pixel(1, 0), Gray8::new(0));
+        assert_eq!(r1.pixel(2, 0), Gray8::new(0xFF));
+        let mut r = Raster::with_color(2, 2, Graya8::new(0x40, 0x40));
+        r.stretch_contrast(0.0, 0.5);
+        assert_eq!(r.pixel(1, 1), Graya8::new(0x80, 0x40));
+        r.auto_stretch();
+        assert_eq!(r.pixel(1, 1), Graya8::new(0x80, 0x40));
+    }
+
+    #[test]
+    fn with_2d() {
+        let a = SRgb8::new(1, 2, 3);
+        let b = SRgb8::new(4, 5, 6);
+        let r = Raster::with_2d(&[[a, b, a], [b, b, a]]);
+        let r1 = Raster::with_pixels(3, 2, vec![a, b, a, b, b, a]);
+        assert_eq!(r.pixels(), r1.pixels());
+        let rows: [&[SRgb8]; 2] = [&[a, b], &[b, a]];
+        let r = Raster::with_2d(&rows);
+        assert_eq!(r.pixels(), &[a, b, b, a]);
+        let r = Raster::<SRgb8>::with_2d::<[SRgb8; 0]>(&[]);
+        assert_eq!((r.width(), r.height()), (0, 0));
+    }
+
+    #[test]
+    fn with_rows() {
+        let a = SRgb8::new(1, 2, 3);
+        let b = SRgb8::new(4, 5, 6);
+        let rows = vec![vec![a, b, a], vec![b, b, a]];
+        let r = Raster::with_rows(3, 2, rows);
+        let r1 = Raster::with_pixels(3, 2, vec![a, b, a, b, b, a]);
+        assert_eq!(r.pixels(), r1.pixels());
+        let rows: [&[SRgb8]; 2] = [&[a], &[b]];
+        let r = Raster::with_rows(1, 2, rows);
+        assert_eq!(r.pixels(), &[a, b]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Row length")]
+    fn with_rows_bad_width() {
+        let rows: [&[SGray8]; 2] = [&[SGray8::new(1); 3], &[SGray8::new(2); 2]];
+        Raster::with_rows(3, 2, rows);
+    }
+
+    #[test]
+    #[should_panic(expected = "Row count")]
+    fn with_rows_bad_height() {
+        Raster::with_rows(3, 2, vec![vec![SGray8::new(1); 3]; 3]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn with_2d_ragged() {
+        let rows: [&[SGray8]; 2] = [&[SGray8::new(1); 3], &[SGray8::new(2); 2]];
+        Raster::with_2d(&rows);
+    }
+
+    #[test]
+    fn split_planes() {
+        let r = Raster::with_2d(&[
+            [Rgba8::new(1, 2, 3, 4), Rgba8::new(5, 6, 7, 8)],
+            [Rgba8::new(9, 10, 11, 12), Rgba8::new(13, 14, 15, 16)],
+        ]);
+        let (red, green, blue, alpha) = r.split_planes::<SGray8>();
+        assert_eq!(red.pixels(), &[1, 5, 9, 13].map(SGray8::new));
+        assert_eq!(green.pixels(), &[2, 6, 10, 14].map(SGray8::new));
+        assert_eq!(blue.pixels(), &[3, 7, 11, 15].map(SGray8::new));
+        assert_eq!(alpha.pixels(), &[4, 8, 12, 16].map(SGray8::new));
+        let r = Raster::with_color(2, 1, SRgb16::new(1, 2, 3));
+        let (_, _, blue, alpha) = r.split_planes::<Gray16>();
+        assert_eq!(blue.pixels(), &[Gray16::new(3); 2]);
+        assert_eq!(alpha.pixels(), &[Gray16::new(0xFFFF); 2]);
+    }
+
+    #[test]
+    fn rotate() {
+        let red = SRgba8::new(0xFF, 0, 0, 0xFF);
+        let fill = SRgba8::new(0, 0, 0xFF, 0x80);
+        let r = Raster::with_color(8, 8, red);
+        let r45 = r.rotate(std::f32::consts::FRAC_PI_4, fill);
+        assert_eq!((r45.width(), r45.height()), (12, 12));
+        assert_eq!(r45.pixel(0, 0), fill);
+        assert_eq!(r45.pixel(11, 0), fill);
+        assert_eq!(r45.pixel(0, 11), fill);
+        assert_eq!(r45.pixel(11, 11), fill);
+        assert_eq!(r45.pixel(6, 6), red);
+        assert_eq!(r45.pixel(6, 1), red);
+        let r = Raster::with_row_fn(3, 2, |y, row: &mut [SGray8]| {
+            for (x, p) in row.iter_mut().enumerate() {
+                *p = SGray8::new(y as u8 * 3 + x as u8);
+            }
+        });
+        let r90 = r.rotate(std::f32::consts::FRAC_PI_2, SGray8::new(0xFF));
+        let v = [3, 0, 4, 1, 5, 2].map(SGray8::new);
+        assert_eq!(r90.pixels(), &v);
+    }
+
+    #[test]
+    fn shorthand_ops() {
+        let r0 = Raster::with_color(3, 2, Rgba8p::new(0x80, 0x40, 0xFF, 0xFF));
+        let r1 = Raster::with_color(3, 2, Rgba8p::new(0x40, 0x40, 0, 0x80));
+        let mut a = r0.clone();
+        a.over(&r1);
+        let mut b = r0.clone();
+        b.composite_raster((), &r1, (), SrcOver);
+        assert_eq!(a.pixels(), b.pixels());
+        let mut a = r0.clone();
+        a.multiply(&r1);
+        let mut b = r0.clone();
+        b.composite_raster((), &r1, (), Multiply);
+        assert_eq!(a.pixels(), b.pixels());
+        assert_eq!(a.pixel(0, 0), Rgba8p::new(0x5F, 0x2F, 0x7F, 0xFF));
+        let mut a = r0.clone();
+        a.add(&r1);
+        assert_eq!(a.pixel(2, 1), Rgba8p::new(0xC0, 0x80, 0xFF, 0xFF));
+        a.subtract(&r1);
+        assert_eq!(a.pixel(2, 1), Rgba8p::new(0x80, 0x40, 0xFF, 0x7F));
+    }
+
+    #[test]
+    #[should_panic]
+    fn shorthand_size_mismatch() {
+        let mut r0 = Raster::<Rgba8p>::with_clear(3, 2);
+        r0.over(&Raster::with_clear(2, 3));
+    }
+
+    #[test]
+    fn equalize() {
+        let mut r = Raster::with_row_fn(16, 1, |_y, row: &mut [SGray8]| {
+            for (x, p) in row.iter_mut().enumerate() {
+                *p = SGray8::new(0x60 + x as u8);
+            }
+        });
+        r.equalize();
+        let v: Vec<u8> =
+            r.pixels().iter().map(|p| u8::from(Gray::value(*p))).collect();
+        assert_eq!(v[0], 0);
+        assert_eq!(v[15], 0xFF);
+        for (i, w) in v.windows(2).enumerate() {
+            assert!((16..=18).contains(&(w[1] - w[0])), "{i}: {w:?}");
+        }
+        let mut r = Raster::with_color(2, 1, SRgb8::new(0x40, 0x20, 0x10));
+        *r.pixel_mut(1, 0) = SRgb8::new(0x20, 0x10, 0x08);
+        r.equalize();
+        assert_eq!(r.pixel(0, 0), SRgb8::new(0xFF, 0xD5, 0x86));
+        assert_eq!(r.pixel(1, 0), SRgb8::new(0, 0, 0));
+    }
+
+    #[test]
+    fn clamp_premultiplied() {
+        let clr = Rgba8p::new(0xC0, 0x20, 0xFF, 0x80);
+        let mut r = Raster::with_color(2, 2, clr);
+        *r.pixel_mut(1, 1) = Rgba8p::new(0x10, 0x20, 0x30, 0x40);
+        r.clamp_premultiplied();
+        assert_eq!(r.pixel(0, 0), Rgba8p::new(0x80, 0x20, 0x80, 0x80));
+        assert_eq!(r.pixel(1, 1), Rgba8p::new(0x10, 0x20, 0x30, 0x40));
+        let s: Rgba8 = r.pixel(0, 0).convert();
+        assert_eq!(s, Rgba8::new(0xFF, 0x40, 0xFF, 0x80));
+        let mut r = Raster::with_color(1, 1, Graya16p::new(0x9000, 0x8000));
+        r.clamp_premultiplied();
+        assert_eq!(r.pixel(0, 0), Graya16p::new(0x8000, 0x8000));
+        let clr = Hwba8p::new(0xC0, 0x90, 0x20, 0x80);
+        let mut r = Raster::with_color(1, 1, clr);
+        r.clamp_premultiplied();
+        assert_eq!(r.pixel(0, 0), Hwba8p::new(0xC0, 0x80, 0x20, 0x80));
+    }
+
+    #[test]
+    fn with_rgba_iter() {
+        let data = (0..6).map(|i| (i * 8, i * 16, 0x80, 0xFF));
+        let r = Raster::<SRgb8>::with_rgba_iter(3, 2, data);
+        let mut r1 = Raster::with_clear(3, 2);
+        for i in 0..6 {
+            *r1.pixel_mut(i % 3, i / 3) =
+                SRgb8::new(i as u8 * 8, i as u8 * 16, 0x80);
+        }
+        assert_eq!(r.pixels(), r1.pixels());
+        let data = [(0xFF, 0xFF, 0xFF, 0x80)].into_iter();
+        let r = Raster::<Rgba8p>::with_rgba_iter(1, 1, data);
+        assert_eq!(r.pixel(0, 0), Rgba8p::new(0x80, 0x80, 0x80, 0x80));
+    }
+
+    #[test]
+    #[should_panic]
+    fn with_rgba_iter_short() {
+        let data = [(0, 0, 0, 0xFF); 5].into_iter();
+        Raster::<SRgb8>::with_rgba_iter(3, 2, data);
+    }
+
+    #[test]
+    fn to_srgb8() {
+        let r = Raster::with_color(2, 2, Rgb32::new(0.5, 0.5, 0.5));
+        assert_eq!(r.to_srgb8().pixels(), &[SRgb8::new(188, 188, 188); 4]);
+        let r = Raster::with_color(2, 1, Rgba16p::new(0x8000, 0, 0, 0x8000));
+        let s = r.to_srgba8();
+        assert_eq!(s.pixels(), &[SRgba8::new(0xFF, 0, 0, 0x80); 2]);
+    }
+
+    #[test]
+    fn resize_bilinear() {
+        let v = [SGray8::new(0), SGray8::new(0xFF)];
+        let r = Raster::<SGray8>::with_pixels(2, 1, v.to_vec());
+        let r = r.resize_bilinear(4, 1);
+        assert_eq!((r.width(), r.height()), (4, 1));
+        let g: Vec<u8> =
+            r.pixels().iter().map(|p| Gray::value(*p).into()).collect();
+        assert_eq!(g, [0x00, 0x40, 0xBF, 0xFF]);
+        let v = [Gray32::new(0.0), Gray32::new(1.0)];
+        let r = Raster::<Gray32>::with_pixels(2, 1, v.to_vec());
+        let r = r.resize_bilinear(4, 1);
+        assert_eq!(r.pixel(1, 0), Gray32::new(0.25));
+        assert_eq!(r.pixel(2, 0), Gray32::new(0.75));
+        let r = Raster::<Gray32>::with_clear(0, 0).resize_bilinear(3, 2);
+        assert_eq!(r.pixels(), &[Gray32::default(); 6]);
+    }
+
+    #[test]
+    fn blur() {
+        let mut r = Raster::<Gray8>::with_clear(9, 9);
+        *r.pixel_mut(4, 4) = Gray8::new(0xFF);
+        let b = r.blur(3.0);
+        let v = |x, y| u8::from(Gray::value(b.pixel(x, y)));
+        let center = v(4, 4);
+        assert!(center > 0 && center < 0xFF);
+        for y in 0..9 {
+            for x in 0..9 {
+                if (x, y) != (4, 4) {
+                    assert!(v(x, y) < center);
+                }
+                assert_eq!(v(x, y), v(8 - x, y));
+                assert_eq!(v(x, y), v(x, 8 - y));
+                assert_eq!(v(x, y), v(y, x));
+            }
+        }
+        for d in 1..4 {
+            assert!(v(4 + d, 4) < v(3 + d, 4));
+        }
+        let flat = Raster::with_color(5, 3, Rgba16::new(100, 200, 300, 400));
+        assert_eq!(flat.blur(2.5).pixels(), flat.pixels());
+        assert_eq!(r.blur(0.0).pixels(), r.pixels());
+    }
+
+    #[test]
+    fn blur_float() {
+        // values far below one 16-bit step must not be quantized away
+        let r = Raster::with_color(6, 4, Gray32::new(2.0e-6));
+        for p in r.blur(2.0).pixels() {
+            let v = f32::from(Gray::value(*p));
+            assert!((v - 2.0e-6).abs() < 1.0e-9, "{v}");
+        }
+        let r = Raster::with_color(6, 4, Gray32::new(0.3));
+        for p in r.blur(2.0).pixels() {
+            let v = f32::from(Gray::value(*p));
+            assert!((v - 0.3).abs() < 1.0e-6, "{v}");
+        }
+    }
+
+    #[test]
+    fn convolve() {
+        let sharpen = [0.0, -1.0, 0.0, -1.0, 5.0, -1.0, 0.0, -1.0, 0.0];
+        let r = Raster::with_color(4, 3, Rgb8::new(0x80, 0x80, 0x80));
+        assert_eq!(r.convolve(&sharpen, 3, 3).pixels(), r.pixels());
+        let r = Raster::with_color(4, 3, Gray32::new(0.5));
+        assert_eq!(r.convolve(&sharpen, 3, 3).pixels(), r.pixels());
+        let edge = [-1.0, -1.0, -1.0, -1.0, 8.0, -1.0, -1.0, -1.0, -1.0];
+        let mut r = Raster::<Gray8>::with_clear(5, 5);
+        *r.pixel_mut(2, 2) = Gray8::new(0x40);
+        let e = r.convolve(&edge, 3, 3);
+        assert_eq!(e.pixel(2, 2), Gray8::new(0xFF));
+        assert_eq!(e.pixel(1, 1), Gray8::new(0));
+        assert_eq!(e.pixel(0, 0), Gray8::new(0));
+        let shift = [1.0, 0.0, 0.0];
+        let s = r.convolve(&shift, 3, 1);
+        assert_eq!(s.pixel(3, 2), Gray8::new(0x40));
+    }
+
+    #[test]
+    #[should_panic(expected = "kernel length")]
+    fn convolve_bad_kernel() {
+        let r = Raster::<Gray8>::with_clear(5, 5);
+        r.convolve(&[1.0; 8], 3, 3);
+    }
+
+    #[test]
+    fn get_pixel() {
+        let mut r = Raster::<Gray8>::with_clear(3, 2);
+        *r.pixel_mut(2, 1) = Gray8::new(0x55);
+        assert_eq!(r.get_pixel(2, 1), Some(Gray8::new(0x55)));
+        assert_eq!(r.get_pixel(0, 0), Some(Gray8::new(0)));
+        assert_eq!(r.get_pixel(-1, 0), None);
+        assert_eq!(r.get_pixel(0, -1), None);
+        assert_eq!(r.get_pixel(3, 0), None);
+        assert_eq!(r.get_pixel(0, 2), None);
+        assert_eq!(r.get_pixel(i32::MIN, i32::MAX), None);
+        *r.get_pixel_mut(0, 1).unwrap() = Gray8::new(0x22);
+        assert_eq!(r.pixel(0, 1), Gray8::new(0x22));
+        assert!(r.get_pixel_mut(3, 1).is_none());
+        assert!(r.get_pixel_mut(-1, -1).is_none());
     }
 
-    /// Get right side
-    pub fn right(self) -> i32 {
-        self.x.saturating_add(self.width)
+    #[test]
+    fn as_u8_slice_ch32() {
+        let clr = SRgba32::new(0.25, 0.5, 0.75, 1.0);
+        let r = Raster::with_color(3, 2, clr);
+        let bytes = r.as_u8_slice();
+        assert_eq!(bytes.len(), 3 * 2 * size_of::<SRgba32>());
+        assert_eq!(size_of::<SRgba32>(), 4 * 4);
+        assert_eq!(bytes[..4], 0.25_f32.to_ne_bytes());
+        assert_eq!(bytes[12..16], 1.0_f32.to_ne_bytes());
+        let r = Raster::<Gray16>::with_clear(5, 1);
+        assert_eq!(r.as_u8_slice().len(), 10);
     }
 
-    /// Get bottom side
-    pub fn bottom(self) -> i32 {
-        self.y.saturating_add(self.height)
+    #[test]
+    fn as_u16_slice() {
+        let buf = vec![0x1234, 0xFFFF, 0x0000, 0x8000, 0xABCD, 0x0101];
+        let r = Raster::<SGraya16>::with_u16_buffer(3, 1, buf.clone());
+        assert_eq!(r.as_u16_slice(), &buf[..]);
+        assert_eq!(r.pixel(2, 0), SGraya16::new(0xABCD, 0x0101));
+        let r = Raster::with_color(4, 3, Rgb16::new(1, 2, 3));
+        assert_eq!(r.as_u16_slice().len(), 4 * 3 * 3);
     }
-}
 
-#[cfg(test)]
-#[rustfmt::skip]
-mod test {
-    use crate::gray::*;
-    use crate::hwb::*;
-    use crate::matte::*;
-    use crate::ops::*;
-    use crate::rgb::*;
-    use crate::*;
+    #[test]
+    fn slice_mut_decode() {
+        let mut r = Raster::<SRgba8>::with_clear(2, 2);
+        for (i, b) in r.as_u8_slice_mut().iter_mut().enumerate() {
+            *b = i as u8;
+        }
+        assert_eq!(r.pixel(0, 0), SRgba8::new(0, 1, 2, 3));
+        assert_eq!(r.pixel(1, 0), SRgba8::new(4, 5, 6, 7));
+        assert_eq!(r.pixel(0, 1), SRgba8::new(8, 9, 10, 11));
+        let r2 = Raster::<SRgba8>::with_u8_buffer(2, 2, r.as_u8_slice());
+        assert_eq!(r2.pixels(), r.pixels());
+        let mut r = Raster::<Gray16>::with_clear(3, 1);
+        r.as_u16_slice_mut().copy_from_slice(&[0x0100, 0x8000, 0xFFFF]);
+        assert_eq!(r.pixel(1, 0), Gray16::new(0x8000));
+        assert_eq!(r.pixel(2, 0), Gray16::new(0xFFFF));
+    }
 
     #[test]
-    fn region_size() {
-        assert_eq!(std::mem::size_of::<Region>(), 16);
+    fn pixels_with_coords() {
+        let mut r = Raster::<Gray8>::with_clear(3, 2);
+        for (x, y, p) in r.pixels_with_coords_mut() {
+            *p = Gray8::new((y * 10 + x) as u8);
+        }
+        let v: Vec<_> = r.pixels_with_coords().collect();
+        assert_eq!(v.len(), 6);
+        assert_eq!(v[1], (1, 0, Gray8::new(1)));
+        assert_eq!(v[3], (0, 1, Gray8::new(10)));
+        assert_eq!(v[5], (2, 1, Gray8::new(12)));
+        assert_eq!(r.pixel(2, 1), Gray8::new(12));
+        let r = Raster::<Gray8>::with_clear(0, 2);
+        assert_eq!(r.pixels_with_coords().count(), 0);
     }
 
     #[test]
-    fn buffers() {
-        let buf = vec![0x80; 64];
-        let mut raster = Raster::<Rgba8>::with_u8_buffer(4, 4, buf);
-        *raster.pixel_mut(1, 1) = Rgba8::new(0x40, 0x60, 0x80, 0xA0);
-        let slice: Box<[u8]> = raster.into();
-        let v: Vec<u8> = slice.into();
-        assert_eq!(v.len(), 64);
-        let buf = vec![0xA0; 16];
-        let mut raster = Raster::<Gray8>::with_u8_buffer(4, 4, buf);
-        *raster.pixel_mut(1, 1) = Gray8::new(0xFF);
-        let slice: Box<[u8]> = raster.into();
-        let v: Vec<u8> = slice.into();
-        let b = vec![
-            0xA0, 0xA0, 0xA0, 0xA0,
-            0xA0, 0xFF, 0xA0, 0xA0,
-            0xA0, 0xA0, 0xA0, 0xA0,
-            0xA0, 0xA0, 0xA0, 0xA0,
-        ];
-        assert_eq!(v, b);
+    fn fill_with() {
+        let mut r = Raster::<Gray8>::with_clear(8, 8);
+        r.fill_with((), |x, y| Gray8::new((x ^ y) as u8));
+        assert_eq!(r.pixel(0, 0), Gray8::new(0));
+        assert_eq!(r.pixel(5, 3), Gray8::new(6));
+        assert_eq!(r.pixel(7, 1), Gray8::new(6));
+        assert_eq!(r.pixel(7, 7), Gray8::new(0));
+        let mut r = Raster::<Gray8>::with_clear(4, 4);
+        r.fill_with((-2, -1, 4, 3), |x, y| Gray8::new((x * 10 + y) as u8));
+        assert_eq!(r.pixel(0, 0), Gray8::new(0));
+        assert_eq!(r.pixel(1, 1), Gray8::new(11));
+        assert_eq!(r.pixel(2, 0), Gray8::new(0));
+        assert_eq!(r.pixel(0, 2), Gray8::new(0));
     }
 
     #[test]
-    fn intersect() -> Result<(), ()> {
-        let r = Region::new(0, 0, 5, 5);
-        assert_eq!(r, Region::new(0, 0, 5, 5));
-        assert_eq!(r, r.intersection(Region::new(0, 0, 10, 10)));
-        assert_eq!(r, r.intersection(Region::new(-5, -5, 10, 10)));
-        assert_eq!(Region::new(2, 2, 3, 3), r.intersection((2, 2)));
-        assert_eq!(Region::new(0, 0, 4, 4), r.intersection((-1, -1, 5, 5)));
-        assert_eq!(Region::new(1, 2, 1, 3), r.intersection((1, 2, 1, 100)));
-        assert_eq!(Region::new(2, 1, 3, 1), r.intersection((2, 1, 100, 1)));
-        Ok(())
+    fn to_linear_alpha() {
+        let clr = SRgba8::new(0x80, 0x40, 0xFF, 0x80);
+        let mut r = Raster::with_color(3, 2, clr);
+        r.to_linear();
+        let p = r.pixel(2, 1);
+        assert_eq!(u8::from(p.alpha()), 0x80);
+        assert_eq!(p, SRgba8::new(0x37, 0x0D, 0xFF, 0x80));
+        r.to_srgb();
+        assert_eq!(r.pixel(0, 0), SRgba8::new(0x80, 0x40, 0xFF, 0x80));
+        let mut r = Raster::with_color(1, 1, SGraya16::new(0x8000, 0x1234));
+        r.to_linear();
+        assert_eq!(u16::from(r.pixel(0, 0).alpha()), 0x1234);
     }
 
     #[test]
-    fn with_buffer_rgb8() {
-        let b = vec![
-            0xAA,0x00,0x00, 0x00,0x11,0x22, 0x33,0x44,0x55,
-            0x00,0xBB,0x00, 0x66,0x77,0x88, 0x99,0xAA,0xBB,
-            0x00,0x00,0xCC, 0xCC,0xDD,0xEE, 0xFF,0x00,0x11,
-        ];
-        let r = Raster::<SRgb8>::with_u8_buffer(3, 3, b);
-        let v = vec![
-            SRgb8::new(0xAA, 0x00, 0x00), SRgb8::new(0x00, 0x11, 0x22),
-            SRgb8::new(0x33, 0x44, 0x55),
-            SRgb8::new(0x00, 0xBB, 0x00), SRgb8::new(0x66, 0x77, 0x88),
-            SRgb8::new(0x99, 0xAA, 0xBB),
-            SRgb8::new(0x00, 0x00, 0xCC), SRgb8::new(0xCC, 0xDD, 0xEE),
-            SRgb8::new(0xFF, 0x00, 0x11),
-        ];
-        assert_eq!(r.pixels(), &v[..]);
+    fn premultiply_round_trip() {
+        let mut r = Raster::<SRgba8>::with_row_fn(256, 256, |a, row| {
+            for (c, p) in row.iter_mut().enumerate() {
+                *p = SRgba8::new(c as u8, 0xFF - c as u8, 0, a as u8);
+            }
+        });
+        let orig = r.clone();
+        r.premultiply();
+        assert_eq!(r.pixel(0xFF, 0x80), SRgba8::new(0x80, 0, 0, 0x80));
+        assert_eq!(r.pixel(0x40, 0), SRgba8::new(0, 0, 0, 0));
+        r.unpremultiply();
+        for (p, o) in r.pixels().iter().zip(orig.pixels()) {
+            let a = u8::from(o.alpha());
+            for (c, oc) in p.channels().iter().zip(o.channels()) {
+                let d = u8::from(*c).abs_diff(u8::from(*oc));
+                match a {
+                    0 => assert_eq!(u8::from(*c), 0),
+                    0xF0..=0xFF => assert!(d <= 1, "{p:?} {o:?}"),
+                    _ => assert!(u16::from(d) <= 0x100 / u16::from(a) + 1),
+                }
+            }
+        }
     }
 
     #[test]
-    fn with_buffer_graya16() {
-        let b = vec![
-            0x1001,0x5005, 0x1000,0x3002, 0x5004,0x7006,
-            0x2002,0x6006, 0x9008,0xB00A, 0xD00C,0xF00E,
-            0x3003,0x7007, 0xE00F,0xC00D, 0xA00B,0x8009,
-        ];
-        let r = Raster::<SGraya16>::with_u16_buffer(3, 3, b);
-        let v = vec![
-            SGraya16::new(0x1001, 0x5005), SGraya16::new(0x1000, 0x3002),
-            SGraya16::new(0x5004, 0x7006),
-            SGraya16::new(0x2002, 0x6006), SGraya16::new(0x9008, 0xB00A),
-            SGraya16::new(0xD00C, 0xF00E),
-            SGraya16::new(0x3003, 0x7007), SGraya16::new(0xE00F, 0xC00D),
-            SGraya16::new(0xA00B, 0x8009),
-        ];
-        assert_eq!(r.pixels(), &v[..]);
+    fn histogram() {
+        let r = Raster::<SRgba16>::with_row_fn(16, 4, |y, row| {
+            for (x, p) in row.iter_mut().enumerate() {
+                let v = x as u16 * 0x1000;
+                *p = SRgba16::new(v, 0xFFFF - v, 0, y as u16 * 0x4000);
+            }
+        });
+        let hist = r.histogram();
+        assert_eq!(hist.len(), 4);
+        for h in &hist {
+            assert_eq!(h.iter().sum::<u32>(), 64);
+        }
+        assert_eq!(hist[0][0x10], 4);
+        assert_eq!(hist[1][0xEF], 4);
+        assert_eq!(hist[2][0], 64);
+        assert_eq!(hist[3][0x40], 16);
+        let r = Raster::<Matte8>::with_color(5, 3, Matte8::new(0x20));
+        let hist = r.histogram();
+        assert_eq!(hist.len(), 1);
+        assert_eq!(hist[0][0x20], 15);
     }
 
     #[test]
-    fn with_pixels_matte32() {
-        let p = vec![
-            Matte32::new(0.25), Matte32::new(0.5), Matte32::new(0.75),
-            Matte32::new(0.5), Matte32::new(0.6), Matte32::new(0.7),
-            Matte32::new(0.85), Matte32::new(0.65), Matte32::new(0.45),
-        ];
-        let r = Raster::with_pixels(3, 3, p.clone());
-        assert_eq!(r.pixels(), &p[..]);
+    fn brightness_contrast() {
+        let r = Raster::<SRgba8>::with_row_fn(16, 16, |y, row| {
+            for (x, p) in row.iter_mut().enumerate() {
+                let (x, y) = (x as u8 * 16, y as u8 * 16);
+                *p = SRgba8::new(x, y, x ^ y, 0xFF - x);
+            }
+        });
+        let mut r1 = r.clone();
+        r1.adjust_brightness(0.0);
+        r1.adjust_contrast(1.0);
+        assert_eq!(r1.pixels(), r.pixels());
+        let mut r1 = r.clone();
+        r1.adjust_brightness(10.0);
+        assert_eq!(r1.pixel(3, 7), SRgba8::new(0xFF, 0xFF, 0xFF, 0xCF));
+        r1.adjust_brightness(-10.0);
+        assert_eq!(r1.pixel(3, 7), SRgba8::new(0, 0, 0, 0xCF));
+        let mut r1 = r.clone();
+        r1.adjust_contrast(100.0);
+        assert_eq!(r1.pixel(15, 2), SRgba8::new(0xFF, 0, 0xFF, 0x0F));
+        let mut r1 = Raster::with_color(2, 2, Hwb8::new(0x40, 0x60, 0x90));
+        r1.adjust_contrast(0.0);
+        assert_eq!(r1.pixel(1, 1), Hwb8::new(0x40, 0x80, 0x80));
     }
 
     #[test]
-    fn pixel_mut_matte8() {
-        let mut r = Raster::<Matte8>::with_clear(3, 3);
-        *r.pixel_mut(0, 0) = Matte8::new(0xFF);
-        *r.pixel_mut(2, 0) = Matte8::new(0x12);
-        *r.pixel_mut(1, 1) = Matte8::new(0x34);
-        *r.pixel_mut(0, 2) = Matte8::new(0x56);
-        *r.pixel_mut(2, 2) = Matte8::new(0x78);
-        let v = vec![
-            Matte8::new(0xFF), Matte8::new(0x00), Matte8::new(0x12),
-            Matte8::new(0x00), Matte8::new(0x34), Matte8::new(0x00),
-            Matte8::new(0x56), Matte8::new(0x00), Matte8::new(0x78),
-        ];
-        assert_eq!(r.pixels(), &v[..]);
+    fn to_luma() {
+        let r = Raster::with_row_fn(3, 1, |_, row| {
+            row[0] = SRgba8::new(0, 0xFF, 0, 0xFF);
+            row[1] = SRgba8::new(0, 0, 0xFF, 0xFF);
+            row[2] = SRgba8::new(0xFF, 0xFF, 0xFF, 0x80);
+        });
+        let g = r.to_luma::<SGraya8>();
+        assert!(g.pixel(0, 0).one() > g.pixel(1, 0).one());
+        assert_eq!(g.pixel(0, 0), SGraya8::new(0xDC, 0xFF));
+        assert_eq!(g.pixel(1, 0), SGraya8::new(0x4C, 0xFF));
+        assert_eq!(g.pixel(2, 0), SGraya8::new(0xFF, 0x80));
+        let g = r.to_luma::<Graya8p>();
+        assert_eq!(g.pixel(2, 0), Graya8p::new(0x80, 0x80));
     }
 
     #[test]
-    fn pixel_mut_matte16() {
-        let mut r = Raster::<Matte16>::with_clear(3, 3);
-        *r.pixel_mut(2, 0) = Matte16::new(0x9ABC);
-        *r.pixel_mut(1, 1) = Matte16::new(0x5678);
-        *r.pixel_mut(0, 2) = Matte16::new(0x1234);
-        *r.pixel_mut(0, 0) = Matte16::new(1.0);
-        *r.pixel_mut(2, 2) = Matte16::new(0x8080);
-        let v = vec![
-            Matte16::new(0xFFFF), Matte16::new(0x0000), Matte16::new(0x9ABC),
-            Matte16::new(0x0000), Matte16::new(0x5678), Matte16::new(0x0000),
-            Matte16::new(0x1234), Matte16::new(0x0000), Matte16::new(0x8080),
-        ];
-        assert_eq!(r.pixels(), &v[..]);
+    fn blend_modes() {
+        let clr = Rgba8p::new(0x20, 0x60, 0xC0, 0xFF);
+        let r = Raster::with_row_fn(4, 4, |y, row| {
+            for (x, p) in row.iter_mut().enumerate() {
+                *p = Rgba8p::new(x as u8 * 0x40, y as u8 * 0x40, 0x80, 0xFF);
+            }
+        });
+        let white = Rgba8p::new(0xFF, 0xFF, 0xFF, 0xFF);
+        let white = Raster::with_color(4, 4, white);
+        let black = Raster::with_color(4, 4, Rgba8p::new(0, 0, 0, 0xFF));
+        let mut r1 = r.clone();
+        r1.composite_raster((), &white, (), Multiply);
+        assert_eq!(r1.pixels(), r.pixels());
+        r1.composite_raster((), &black, (), Multiply);
+        assert_eq!(r1.pixels(), black.pixels());
+        let mut r1 = r.clone();
+        r1.composite_raster((), &r, (), Difference);
+        assert_eq!(r1.pixels(), black.pixels());
+        let mut r1 = Raster::with_color(1, 1, clr);
+        r1.composite_color((), Rgba8p::new(0x80, 0x80, 0x80, 0xFF), Overlay);
+        // mid-gray is (nearly) a no-op
+        assert_eq!(r1.pixel(0, 0), Rgba8p::new(0x20, 0x60, 0xC1, 0xFF));
+        let mut r1 = Raster::with_color(1, 1, clr);
+        r1.composite_color((), Rgba8p::new(0x40, 0x40, 0x40, 0xFF), Overlay);
+        // dark channels multiplied, light channels screened
+        assert_eq!(r1.pixel(0, 0), Rgba8p::new(0x10, 0x30, 0xA1, 0xFF));
+        let mut r1 = Raster::with_color(1, 1, clr);
+        let src = Rgba8p::new(0x40, 0x40, 0x40, 0xFF);
+        r1.composite_color((), src, Screen);
+        assert_eq!(r1.pixel(0, 0), Rgba8p::new(0x58, 0x88, 0xD0, 0xFF));
+        let mut r1 = Raster::with_color(1, 1, clr);
+        r1.composite_color((), src, Darken);
+        assert_eq!(r1.pixel(0, 0), Rgba8p::new(0x20, 0x40, 0x40, 0xFF));
+        let mut r1 = Raster::with_color(1, 1, clr);
+        r1.composite_color((), src, Lighten);
+        assert_eq!(r1.pixel(0, 0), Rgba8p::new(0x40, 0x60, 0xC0, 0xFF));
+        // half-transparent source over transparent destination
+        let mut r1 = Raster::<Rgba8p>::with_clear(1, 1);
+        let src = Rgba8p::new(0x40, 0x20, 0, 0x80);
+        r1.composite_color((), src, Difference);
+        assert_eq!(r1.pixel(0, 0), src);
     }
 
     #[test]
-    fn invalid_rows() {
-        let r = Raster::<Matte8>::with_clear(10, 10);
-        let mut rows = r.rows((0, 20, 0, 0));
-        assert_eq!(rows.next(), None);
+    fn resample_into() {
+        let src = Raster::<SGray8>::with_row_fn(4, 4, |y, row| {
+            for (x, p) in row.iter_mut().enumerate() {
+                *p = SGray8::new((y * 4 + x as i32) as u8 * 0x10);
+            }
+        });
+        let mut r = Raster::<SGray8>::with_clear(6, 6);
+        r.resample_into((1, 1, 4, 4), &src, (1, 2, 2, 2), Filter::Nearest);
+        let v = |r: &Raster<SGray8>, x, y| u8::from(Gray::value(r.pixel(x, y)));
+        for y in 0..6 {
+            for x in 0..6 {
+                let e = match (x, y) {
+                    (1..=2, 1..=2) => 0x90,
+                    (3..=4, 1..=2) => 0xA0,
+                    (1..=2, 3..=4) => 0xD0,
+                    (3..=4, 3..=4) => 0xE0,
+                    _ => 0,
+                };
+                assert_eq!(v(&r, x, y), e, "{x},{y}");
+            }
+        }
+        // bilinear, into a clipped region with conversion
+        let mut r = Raster::<SGray16>::with_clear(3, 1);
+        let src = Raster::<SGray8>::with_row_fn(2, 1, |_y, row| {
+            row[0] = SGray8::new(0x10);
+            row[1] = SGray8::new(0x30);
+        });
+        r.resample_into((-1, 0, 4, 1), &src, (0, 0, 2, 1), Filter::Bilinear);
+        let v = |x| u16::from(Gray::value(r.pixel(x, 0)));
+        assert_eq!([v(0), v(1), v(2)], [0x1010, 0x2020, 0x3030]);
+        // source region partly outside
+        let mut r = Raster::<SGray8>::with_clear(2, 1);
+        r.resample_into((), &src, (1, 0, 2, 1), Filter::Nearest);
+        assert_eq!(r.pixels(), [SGray8::new(0x30), SGray8::new(0x30)]);
+        // unit type for entire rasters
+        let (a, b) = (SGray8::new(0x10), SGray8::new(0x30));
+        let mut r = Raster::<SGray8>::with_clear(2, 1);
+        r.resample_into((), &src, (0, 0, 2, 1), Filter::Nearest);
+        assert_eq!(r.pixels(), [a, b]);
+        let mut r = Raster::<SGray8>::with_clear(4, 1);
+        r.resample_into((0, 0, 4, 1), &src, (), Filter::Nearest);
+        assert_eq!(r.pixels(), [a, a, b, b]);
+        let mut r = Raster::<SGray8>::with_clear(4, 1);
+        r.resample_into((), &src, (), Filter::Nearest);
+        assert_eq!(r.pixels(), [a, a, b, b]);
     }
 
     #[test]
-    fn raster_with_color() {
-        let r = Raster::with_color(3, 3, Hwb8::new(0x80, 0, 0));
-        let v = vec![Hwb8::new(0x80, 0, 0); 9];
-        assert_eq!(r.pixels(), &v[..]);
+    fn debug() {
+        let r = Raster::<SRgb8>::with_clear(100, 50);
+        let s = format!("{r:?}");
+        assert!(s.starts_with("Raster<"), "{s}");
+        assert!(s.ends_with("> { 100x50, 5000 px }"), "{s}");
+        assert!(s.len() < 200, "{s}");
     }
 
     #[test]
-    fn copy_color_gray8() {
-        let mut r = Raster::<SGray8>::with_clear(3, 3);
-        r.copy_color((0, 0, 1, 1), SGray8::new(0x23));
-        r.copy_color((10, 10, 1, 1), SGray8::new(0x45));
-        r.copy_color((1, 1, 10, 10), SGray8::new(0xBB));
-        let v = vec![
-            SGray8::new(0x23), SGray8::new(0), SGray8::new(0),
-            SGray8::new(0), SGray8::new(0xBB), SGray8::new(0xBB),
-            SGray8::new(0), SGray8::new(0xBB), SGray8::new(0xBB),
-        ];
-        assert_eq!(r.pixels(), &v[..]);
+    fn partial_eq() {
+        let r0 = Raster::<SRgb8>::with_row_fn(4, 3, |y, row| {
+            for (x, p) in row.iter_mut().enumerate() {
+                *p = SRgb8::new(x as u8, y as u8, 0x80);
+            }
+        });
+        let mut r1 = Raster::<SRgb8>::with_color(4, 3, SRgb8::new(0, 0, 0x80));
+        for y in 0..3 {
+            for x in 0..4 {
+                *r1.pixel_mut(x, y) = SRgb8::new(x as u8, y as u8, 0x80);
+            }
+        }
+        assert_eq!(r0, r1);
+        *r1.pixel_mut(3, 2) = SRgb8::new(0, 0, 0);
+        assert_ne!(r0, r1);
+        let r2 = Raster::<SRgb8>::with_pixels(3, 4, r0.pixels().to_vec());
+        assert_ne!(r0, r2);
+        let e0 = Raster::<SRgb8>::with_clear(0, 5);
+        assert_eq!(e0, Raster::with_clear(0, 5));
+        assert_ne!(e0, Raster::with_clear(5, 0));
     }
 
     #[test]
-    fn copy_color_srgb8() {
-        let mut r = Raster::<SRgb8>::with_clear(3, 3);
-        r.copy_color((2, -1, 3, 4), SRgb8::new(0xCC, 0xAA, 0xBB));
-        let v = vec![
-            SRgb8::new(0, 0, 0), SRgb8::new(0, 0, 0),
-            SRgb8::new(0xCC, 0xAA, 0xBB),
-            SRgb8::new(0, 0, 0), SRgb8::new(0, 0, 0),
-            SRgb8::new(0xCC, 0xAA, 0xBB),
-            SRgb8::new(0, 0, 0), SRgb8::new(0, 0, 0),
-            SRgb8::new(0xCC, 0xAA, 0xBB),
-        ];
-        assert_eq!(r.pixels(), &v[..]);
+    fn clone() {
+        let r0 = Raster::with_color(3, 2, SRgb8::new(0x10, 0x20, 0x30));
+        let mut r1 = r0.clone();
+        assert_eq!(r0, r1);
+        *r1.pixel_mut(1, 1) = SRgb8::new(0xFF, 0, 0);
+        assert_eq!(r0.pixel(1, 1), SRgb8::new(0x10, 0x20, 0x30));
+        assert_ne!(r0, r1);
     }
 
     #[test]
-    fn copy_raster_gray() {
-        let mut g0 = Raster::<Gray8>::with_clear(3, 3);
-        let g1 = Raster::<Gray8>::with_color(3, 3, Gray8::new(0x40));
-        let g2 = Raster::<Gray8>::with_color(3, 3, Gray8::new(0x60));
-        let g3 = Raster::<Gray8>::with_color(3, 3, Gray8::new(0x80));
-        g0.copy_raster((-1, 2, 3, 3), &g1, ());
-        g0.copy_raster((2, -1, 3, 3), &g2, ());
-        g0.copy_raster((-2, -2, 3, 3), &g3, ());
-        let v = vec![
-            Gray8::new(0x80), Gray8::new(0x00), Gray8::new(0x60),
-            Gray8::new(0x00), Gray8::new(0x00), Gray8::new(0x60),
-            Gray8::new(0x40), Gray8::new(0x40), Gray8::new(0x00),
-        ];
-        assert_eq!(g0.pixels(), &v[..]);
+    fn row() {
+        let mut r = Raster::<SGray8>::with_row_fn(3, 3, |y, row| {
+            for (x, p) in row.iter_mut().enumerate() {
+                *p = SGray8::new(y as u8 * 3 + x as u8);
+            }
+        });
+        let v = |v| SGray8::new(v);
+        assert_eq!(r.row(1), [v(3), v(4), v(5)]);
+        r.row_mut(2)[1] = v(0xFF);
+        assert_eq!(r.row(2), [v(6), v(0xFF), v(8)]);
+        assert_eq!(r.row(0), [v(0), v(1), v(2)]);
     }
 
     #[test]
-    fn composite_color_graya8_over() {
-        let clr = Graya8p::new(0x20, 0x40);
-        let mut r = Raster::<Graya8p>::with_color(2, 2, clr);
-        r.composite_color((0, 0, 3, 1), Graya8p::new(0x60, 0xA0), SrcOver);
-        r.composite_color((1, 1, 4, 4), Graya8p::new(0x80, 0x80), SrcOver);
-        let v = vec![
-            Graya8p::new(0x6B, 0xB7), Graya8p::new(0x6B, 0xB7),
-            Graya8p::new(0x20, 0x40), Graya8p::new(0x8F, 0x9F),
-        ];
-        assert_eq!(r.pixels(), &v[..]);
+    #[should_panic]
+    fn row_out_of_bounds() {
+        let r = Raster::<SGray8>::with_clear(3, 3);
+        r.row(3);
     }
 
     #[test]
-    fn composite_raster_rgb() {
-        let mut rgb = Raster::<Rgba8p>::with_clear(3, 3);
-        let gray = Raster::with_color(3, 3, Gray16::new(0x8000));
-        let r = Raster::with_raster(&gray);
-        rgb.composite_raster((), &r, (0, 1), Src);
-        let mut v = vec![Rgba8p::new(0x80, 0x80, 0x80, 0xFF); 6];
-        v.extend_from_slice(&vec![Rgba8p::new(0, 0, 0, 0); 3]);
-        assert_eq!(rgb.pixels(), &v[..]);
+    fn rotate_quarter() {
+        let v = [1, 2, 3, 4, 5, 6].map(SGray8::new);
+        let r = Raster::<SGray8>::with_pixels(2, 3, v.to_vec());
+        let r1 = r.rotate_quarter(1);
+        assert_eq!((r1.width(), r1.height()), (3, 2));
+        let cw = [5, 3, 1, 6, 4, 2].map(SGray8::new);
+        assert_eq!(r1.pixels(), &cw);
+        let r2 = r.rotate_quarter(2);
+        assert_eq!((r2.width(), r2.height()), (2, 3));
+        let half = [6, 5, 4, 3, 2, 1].map(SGray8::new);
+        assert_eq!(r2.pixels(), &half);
+        let r3 = r.rotate_quarter(3);
+        assert_eq!((r3.width(), r3.height()), (3, 2));
+        let ccw = [2, 4, 6, 1, 3, 5].map(SGray8::new);
+        assert_eq!(r3.pixels(), &ccw);
+        assert_eq!(r.rotate_quarter(0).pixels(), r.pixels());
+        assert_eq!(r.rotate_quarter(5).pixels(), r1.pixels());
     }
 
     #[test]
-    fn composite_matte_full() {
-        let mut r = Raster::<Rgba8p>::with_clear(2, 2);
-        let mut m = Raster::<Matte8>::with_clear(2, 2);
-        *m.pixel_mut(0, 0) = Matte8::new(0xFF);
-        *m.pixel_mut(1, 1) = Matte8::new(0x80);
-        let v = [
-            Matte8::new(0xFF), Matte8::new(0),
-            Matte8::new(0), Matte8::new(0x80),
-        ];
-        assert_eq!(m.pixels(), &v);
-        let c = Rgba8p::new(0xFF, 0x80, 0x40, 0xFF);
-        r.composite_matte((), &m, (), c, SrcOver);
-        let v = [
-            Rgba8p::new(0xFF, 0x80, 0x40, 0xFF), Rgba8p::new(0, 0, 0, 0),
-            Rgba8p::new(0, 0, 0, 0), Rgba8p::new(0x80, 0x40, 0x20, 0x80),
-        ];
-        assert_eq!(r.pixels(), &v);
+    fn flip() {
+        let v = [1, 2, 3, 4, 5, 6, 7, 8, 9].map(Matte8::new);
+        let orig = Raster::<Matte8>::with_pixels(3, 3, v.to_vec());
+        let mut r = orig.clone();
+        r.flip_horizontal();
+        let h = [3, 2, 1, 6, 5, 4, 9, 8, 7].map(Matte8::new);
+        assert_eq!(r.pixels(), &h);
+        r.flip_horizontal();
+        assert_eq!(r.pixels(), orig.pixels());
+        r.flip_vertical();
+        let fv = [7, 8, 9, 4, 5, 6, 1, 2, 3].map(Matte8::new);
+        assert_eq!(r.pixels(), &fv);
+        r.flip_vertical();
+        assert_eq!(r.pixels(), orig.pixels());
+        let mut r = Raster::<Matte8>::with_clear(0, 3);
+        r.flip_horizontal();
+        r.flip_vertical();
+        let mut r = Raster::<Matte8>::with_clear(3, 0);
+        r.flip_horizontal();
+        r.flip_vertical();
+        assert!(r.pixels().is_empty());
     }
 
     #[test]
-    fn composite_matte_smaller() {
-        let mut r = Raster::<Rgba8p>::with_clear(3, 3);
-        let m = vec![
-            Matte8::new(0xFF), Matte8::new(0x80),
-            Matte8::new(0x40), Matte8::new(0x20),
-        ];
-        let m = Raster::<Matte8>::with_pixels(2, 2, m);
-        let c = Rgba8p::new(0x40, 0x80, 0x60, 0x80);
-        r.composite_matte((1, 1, 4, 4), &m, (), c, SrcOver);
-        let v = [
-            Rgba8p::new(0, 0, 0, 0),
-            Rgba8p::new(0, 0, 0, 0),
-            Rgba8p::new(0, 0, 0, 0),
+    fn row_hashes() {
+        let mut r = Raster::<SRgba16>::with_clear(8, 5);
+        let h0 = r.row_hashes();
+        assert_eq!(h0.len(), 5);
+        *r.pixel_mut(6, 2) = SRgba16::new(1, 2, 3, 4);
+        let h1 = r.row_hashes();
+        for (y, (a, b)) in h0.iter().zip(&h1).enumerate() {
+            assert_eq!(a == b, y != 2);
+        }
+        let r = Raster::<SRgba16>::with_clear(0, 3);
+        assert_eq!(r.row_hashes().len(), 3);
+    }
 
-            Rgba8p::new(0, 0, 0, 0),
-            Rgba8p::new(0x40, 0x80, 0x60, 0x80),
-            Rgba8p::new(0x20, 0x40, 0x30, 0x40),
+    #[test]
+    fn with_row_fn() {
+        let r = Raster::with_row_fn(3, 4, |y, row: &mut [SRgb8]| {
+            for (x, p) in row.iter_mut().enumerate() {
+                *p = SRgb8::new(x as u8, y as u8, 0x80);
+            }
+        });
+        let mut r1 = Raster::with_clear(3, 4);
+        for y in 0..4 {
+            for x in 0..3 {
+                *r1.pixel_mut(x, y) = SRgb8::new(x as u8, y as u8, 0x80);
+            }
+        }
+        assert_eq!(r.pixels(), r1.pixels());
+    }
 
-            Rgba8p::new(0, 0, 0, 0),
-            Rgba8p::new(0x10, 0x20, 0x18, 0x20),
-            Rgba8p::new(0x08, 0x10, 0x0C, 0x10),
-        ];
-        assert_eq!(r.pixels(), &v);
+    #[test]
+    fn convert_into() {
+        let mut r = Raster::<SRgb8>::with_clear(3, 2);
+        *r.pixel_mut(1, 0) = SRgb8::new(0x12, 0x34, 0x56);
+        *r.pixel_mut(2, 1) = SRgb8::new(0xAB, 0xCD, 0xEF);
+        let mut r16 = Raster::<SRgb16>::with_clear(3, 2);
+        r.convert_into(&mut r16);
+        assert_eq!(r16.pixels(), Raster::<SRgb16>::with_raster(&r).pixels());
+        assert_eq!(r16.pixel(2, 1), SRgb16::new(0xABAB, 0xCDCD, 0xEFEF));
     }
 
     #[test]
-    fn composite_matte_gray() {
-        let mut g0 = Raster::<Graya8p>::with_clear(2, 2);
-        let g1 = Raster::<Matte8>::with_color(3, 3, Matte8::new(0x40));
-        let g2 = Raster::<Matte8>::with_color(3, 3, Matte8::new(0x60));
-        let g3 = Raster::<Matte8>::with_color(3, 3, Matte8::new(0x80));
-        let clr = Graya8p::new(0xFF, 0xFF);
-        g0.composite_matte((1, 1, 3, 3), &g1, (), clr, SrcOver);
-        g0.composite_matte((1, -2, 3, 3), &g2, (), clr, SrcOver);
-        g0.composite_matte((-2, -2, 3, 3), &g3, (), clr, SrcOver);
-        let v = vec![
-            Graya8p::new(0x80, 0x80), Graya8p::new(0x60, 0x60),
-            Graya8p::new(0x00, 0x00), Graya8p::new(0x40, 0x40),
-        ];
-        assert_eq!(g0.pixels(), &v[..]);
+    #[should_panic]
+    fn convert_into_mismatch() {
+        let r = Raster::<SRgb8>::with_clear(3, 2);
+        let mut r16 = Raster::<SRgb16>::with_clear(2, 3);
+        r.convert_into(&mut r16);
     }
 
     #[test]
@@ -312,4 +312,17 @@ mod tests {
         dst.composite_channels(&Rgba8p::new(0, 0, 0, 0), SrcOver);
         assert_eq!(dst, Rgba8p::new(0xFF, 0xFF, 0xFF, 0x00));
     }
+
+    #[test]
+    fn ch64_convert() {
+        use crate::chan::{Ch64, Straight};
+        use crate::el::Pix4;
+        type Rgba64 = Pix4<Ch64, Rgb, Straight, Linear>;
+        let p = Rgba8::new(0x20, 0x80, 0xF0, 0x40);
+        let q: Rgba64 = p.convert();
+        assert_eq!(q.alpha(), Ch64::new(f64::from(0x40) / 255.0));
+        assert_eq!(q.convert::<Rgba8>(), p);
+        assert_eq!(q.convert::<Rgba16>(), p.convert());
+        assert_eq!(q.convert::<SRgba8>(), p.convert());
+    }
 }
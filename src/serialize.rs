@@ -0,0 +1,233 @@
+// serialize.rs     Serde support (optional feature).
+//
+// Copyright (c) 2024  Douglas P Lau
+//
+use crate::chan::Channel;
+use crate::el::Pixel;
+use crate::raster::{Raster, Region};
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeTuple, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+
+/// Byte buffer, serialized compactly
+struct Bytes(Vec<u8>);
+
+impl Serialize for Bytes {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Bytes {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct BytesVisitor;
+
+        impl<'de> Visitor<'de> for BytesVisitor {
+            type Value = Bytes;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("pixel bytes")
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Bytes, E> {
+                Ok(Bytes(v.to_vec()))
+            }
+
+            fn visit_byte_buf<E: de::Error>(
+                self,
+                v: Vec<u8>,
+            ) -> Result<Bytes, E> {
+                Ok(Bytes(v))
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Bytes, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut v = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                while let Some(b) = seq.next_element()? {
+                    v.push(b);
+                }
+                Ok(Bytes(v))
+            }
+        }
+
+        deserializer.deserialize_byte_buf(BytesVisitor)
+    }
+}
+
+/// Swap byte order of each channel, on big-endian targets
+fn swap_le<C: Channel>(bytes: &mut [u8]) {
+    if cfg!(target_endian = "big") {
+        let size = std::mem::size_of::<C>();
+        bytes.chunks_exact_mut(size).for_each(|c| c.reverse());
+    }
+}
+
+/// Serialized as a tuple of width, height and pixel data.
+///
+/// Pixel data is a byte array, with each channel in little-endian order.
+impl<P: Pixel> Serialize for Raster<P> {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut bytes = self.as_u8_slice().to_vec();
+        swap_le::<P::Chan>(&mut bytes);
+        let mut tup = serializer.serialize_tuple(3)?;
+        tup.serialize_element(&self.width())?;
+        tup.serialize_element(&self.height())?;
+        tup.serialize_element(&Bytes(bytes))?;
+        tup.end()
+    }
+}
+
+impl<'de, P: Pixel> Deserialize<'de> for Raster<P> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct RasterVisitor<P>(PhantomData<P>);
+
+        impl<'de, P: Pixel> Visitor<'de> for RasterVisitor<P> {
+            type Value = Raster<P>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("raster width, height and pixel bytes")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Raster<P>, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let missing = |i| de::Error::invalid_length(i, &self);
+                let width: u32 =
+                    seq.next_element()?.ok_or_else(|| missing(0))?;
+                let height: u32 =
+                    seq.next_element()?.ok_or_else(|| missing(1))?;
+                let Bytes(mut bytes) =
+                    seq.next_element()?.ok_or_else(|| missing(2))?;
+                if width > i32::MAX as u32
+                    || height > i32::MAX as u32
+                    || i32::try_from(u64::from(width) * u64::from(height))
+                        .is_err()
+                {
+                    return Err(de::Error::custom("raster too big"));
+                }
+                let len =
+                    width as usize * height as usize * std::mem::size_of::<P>();
+                if bytes.len() != len {
+                    return Err(de::Error::invalid_length(bytes.len(), &self));
+                }
+                swap_le::<P::Chan>(&mut bytes);
+                let mut raster = Raster::<P>::with_clear(width, height);
+                raster.as_u8_slice_mut().copy_from_slice(&bytes);
+                let valid = raster.pixels().iter().all(|p| {
+                    p.channels()
+                        .iter()
+                        .all(|c| (0.0..=1.0).contains(&c.to_f32()))
+                });
+                if !valid {
+                    return Err(de::Error::custom("invalid channel value"));
+                }
+                Ok(raster)
+            }
+        }
+
+        deserializer.deserialize_tuple(3, RasterVisitor(PhantomData))
+    }
+}
+
+/// Serialized as a tuple of *x*, *y*, *width* and *height*.
+impl Serialize for Region {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        (self.left(), self.top(), self.width(), self.height())
+            .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Region {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (x, y, width, height) =
+            <(i32, i32, u32, u32)>::deserialize(deserializer)?;
+        if width > i32::MAX as u32 || height > i32::MAX as u32 {
+            return Err(de::Error::custom("region too big"));
+        }
+        Ok(Region::new(x, y, width, height))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::gray::*;
+    use crate::rgb::*;
+    use crate::*;
+
+    #[test]
+    fn raster_bincode() {
+        let r = Raster::with_2d(&[
+            [
+                SRgb8::new(1, 2, 3),
+                SRgb8::new(4, 5, 6),
+                SRgb8::new(7, 8, 9),
+            ],
+            [
+                SRgb8::new(9, 8, 7),
+                SRgb8::new(6, 5, 4),
+                SRgb8::new(3, 2, 1),
+            ],
+        ]);
+        let buf = bincode::serialize(&r).unwrap();
+        // width + height + length prefix + pixels
+        assert_eq!(buf.len(), 4 + 4 + 8 + 18);
+        let r2: Raster<SRgb8> = bincode::deserialize(&buf).unwrap();
+        assert_eq!((r2.width(), r2.height()), (3, 2));
+        assert_eq!(r2.pixels(), r.pixels());
+    }
+
+    #[test]
+    fn raster_16_32() {
+        let r = Raster::with_color(2, 2, Graya16::new(0x1234, 0xFFFF));
+        let buf = bincode::serialize(&r).unwrap();
+        assert_eq!(&buf[16..20], &[0x34, 0x12, 0xFF, 0xFF]);
+        let r2: Raster<Graya16> = bincode::deserialize(&buf).unwrap();
+        assert_eq!(r2.pixels(), r.pixels());
+        let r = Raster::with_color(2, 1, Rgb32::new(0.25, 0.5, 1.0));
+        let buf = bincode::serialize(&r).unwrap();
+        let r2: Raster<Rgb32> = bincode::deserialize(&buf).unwrap();
+        assert_eq!(r2.pixels(), r.pixels());
+    }
+
+    #[test]
+    fn raster_invalid() {
+        let r = Raster::with_color(2, 2, SRgb8::new(1, 2, 3));
+        let buf = bincode::serialize(&r).unwrap();
+        assert!(bincode::deserialize::<Raster<SRgba8>>(&buf).is_err());
+        let r = Raster::with_color(1, 1, Gray32::new(0.5));
+        let mut buf = bincode::serialize(&r).unwrap();
+        buf[16..20].copy_from_slice(&2.0_f32.to_le_bytes());
+        assert!(bincode::deserialize::<Raster<Gray32>>(&buf).is_err());
+    }
+
+    #[test]
+    fn region() {
+        let reg = Region::new(-5, 10, 20, 30);
+        let buf = bincode::serialize(&reg).unwrap();
+        assert_eq!(bincode::deserialize::<Region>(&buf).unwrap(), reg);
+        let buf = bincode::serialize(&(0, 0, u32::MAX, 1_u32)).unwrap();
+        assert!(bincode::deserialize::<Region>(&buf).is_err());
+    }
+}
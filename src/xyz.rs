@@ -16,6 +16,12 @@ use std::ops::Range;
 ///
 /// The components are *[X]*, *[Y]*, *[Z]* and optional *[alpha]*.
 ///
+/// Conversion uses the sRGB primaries, and is done with `f32` math for all
+/// channel types.  Components are stored in the channel range, so values
+/// greater than 1.0 are clamped.  This affects *Z* for bright, bluish colors
+/// (the D65 white point has *Z* of about 1.089).  Lower bit depths also lose
+/// precision; `Xyz32` is recommended for intermediate calculations.
+///
 /// [alpha]: ../el/trait.Pixel.html#method.alpha
 /// [color model]: ../trait.ColorModel.html
 /// [x]: #method.x
@@ -146,9 +152,7 @@ impl ColorModel for Xyz {
         let py = Self::y(p).to_f32();
         let pz = Self::z(p).to_f32();
 
-        let red = px * 3.2406 + py * -1.5372 + pz * -0.4986;
-        let green = px * -0.9689 + py * 1.8758 + pz * 0.0415;
-        let blue = px * 0.0557 + py * -0.2040 + pz * 1.0570;
+        let [red, green, blue] = xyz_to_rgb([px, py, pz]);
 
         PixRgba::<P>::new(red, green, blue, p.alpha().to_f32())
     }
@@ -164,14 +168,28 @@ impl ColorModel for Xyz {
         let blue = chan[2].to_f32();
         let alpha = chan[3];
 
-        let x = red * 0.4124 + green * 0.3576 + blue * 0.1805;
-        let y = red * 0.2126 + green * 0.7152 + blue * 0.0722;
-        let z = red * 0.0193 + green * 0.1192 + blue * 0.9505;
+        let [x, y, z] = rgb_to_xyz([red, green, blue]);
 
         P::from_channels(&[x.into(), y.into(), z.into(), alpha])
     }
 }
 
+/// Convert linear *red*, *green* and *blue* to *X*, *Y* and *Z*
+pub(crate) fn rgb_to_xyz([red, green, blue]: [f32; 3]) -> [f32; 3] {
+    let x = red * 0.4124 + green * 0.3576 + blue * 0.1805;
+    let y = red * 0.2126 + green * 0.7152 + blue * 0.0722;
+    let z = red * 0.0193 + green * 0.1192 + blue * 0.9505;
+    [x, y, z]
+}
+
+/// Convert *X*, *Y* and *Z* to linear *red*, *green* and *blue*
+pub(crate) fn xyz_to_rgb([x, y, z]: [f32; 3]) -> [f32; 3] {
+    let red = x * 3.2406 + y * -1.5372 + z * -0.4986;
+    let green = x * -0.9689 + y * 1.8758 + z * 0.0415;
+    let blue = x * 0.0557 + y * -0.2040 + z * 1.0570;
+    [red, green, blue]
+}
+
 /// [Xyz](struct.Xyz.html) 8-bit opaque (no *alpha* channel)
 /// [linear](../chan/struct.Linear.html) gamma [pixel](../el/trait.Pixel.html)
 /// format.
@@ -225,8 +243,30 @@ pub type Xyza32p = Pix4<Ch32, Xyz, Premultiplied, Linear>;
 
 #[cfg(test)]
 mod test {
+    use super::*;
+    use crate::rgb::*;
+
     #[test]
     fn xyz_to_rgb() {
-        // TODO
+        let p: Rgb32 = Xyz32::new(0.4124, 0.2126, 0.0193).convert();
+        for (c, v) in p.channels().iter().zip([1.0, 0.0, 0.0]) {
+            assert!((c.to_f32() - v).abs() < 1e-3, "{p:?}");
+        }
+    }
+
+    #[test]
+    fn gray_round_trip() {
+        let gray = SRgb32::new(0.5, 0.5, 0.5);
+        let p: Xyz32 = gray.convert();
+        assert!((Xyz::y(p).to_f32() - 0.214).abs() < 1e-3, "{p:?}");
+        let p: SRgb32 = p.convert();
+        for (c, v) in p.channels().iter().zip(gray.channels()) {
+            assert!((c.to_f32() - v.to_f32()).abs() < 1e-3, "{p:?}");
+        }
+        let p: Xyz16 = SRgb16::new(0x8000, 0x8000, 0x8000).convert();
+        let p: SRgb16 = p.convert();
+        for c in p.channels() {
+            assert!(u16::from(*c).abs_diff(0x8000) < 0x40, "{p:?}");
+        }
     }
 }
@@ -15,6 +15,11 @@ use std::ops::Range;
 ///
 /// The components are *[y]*, *[cb]*, *[cr]* and optional *[alpha]*.
 ///
+/// Conversion uses the ITU-R BT.601 coefficients with *full range*, as in
+/// JPEG (JFIF): *y* spans the entire channel range, and *Cb* / *Cr* are
+/// centered at the channel midpoint.  Studio (limited) range video, where
+/// 8-bit *y* spans 16 to 235, must be scaled before conversion.
+///
 /// [alpha]: ../el/trait.Pixel.html#method.alpha
 /// [cb]: #method.cb
 /// [cr]: #method.cr
@@ -226,3 +231,43 @@ pub type YCbCra16p = Pix4<Ch16, YCbCr, Premultiplied, Linear>;
 /// [linear](../chan/struct.Linear.html) gamma [pixel](../el/trait.Pixel.html)
 /// format.
 pub type YCbCra32p = Pix4<Ch32, YCbCr, Premultiplied, Linear>;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::el::Pixel;
+    use crate::rgb::*;
+
+    fn assert_near<P: Pixel>(p: P, v: [f32; 3]) {
+        for (c, v) in p.channels().iter().zip(v) {
+            assert!((c.to_f32() - v).abs() < 1e-5, "{p:?} {v}");
+        }
+    }
+
+    #[test]
+    fn rgb_to_ycbcr() {
+        assert_eq!(YCbCr8::new(0, 128, 128), Rgb8::new(0, 0, 0).convert());
+        assert_eq!(
+            YCbCr8::new(255, 128, 128),
+            Rgb8::new(255, 255, 255).convert(),
+        );
+        assert_eq!(YCbCr8::new(76, 84, 255), Rgb8::new(255, 0, 0).convert());
+        let p: YCbCr32 = Rgb32::new(0.0, 0.0, 0.0).convert();
+        assert_near(p, [0.0, 0.5, 0.5]);
+        let p: YCbCr32 = Rgb32::new(1.0, 1.0, 1.0).convert();
+        assert_near(p, [1.0, 0.5, 0.5]);
+        let p: YCbCr32 = Rgb32::new(1.0, 0.0, 0.0).convert();
+        assert_near(p, [0.299, 0.331_264, 1.0]);
+    }
+
+    #[test]
+    fn ycbcr_to_rgb() {
+        let p: Rgb32 = YCbCr32::new(0.0, 0.5, 0.5).convert();
+        assert_near(p, [0.0, 0.0, 0.0]);
+        let p: Rgb32 = YCbCr32::new(1.0, 0.5, 0.5).convert();
+        assert_near(p, [1.0, 1.0, 1.0]);
+        let p: Rgb32 = YCbCr32::new(0.299, 0.331_264, 1.0).convert();
+        assert_near(p, [1.0, 0.0, 0.0]);
+        assert_eq!(Rgb8::new(255, 0, 0), YCbCr8::new(76, 84, 255).convert());
+    }
+}